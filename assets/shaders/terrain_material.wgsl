@@ -12,6 +12,10 @@ struct TerrainMaterial {
 
 @group(#{MATERIAL_BIND_GROUP}) @binding(100)
 var<uniform> terrain_material: TerrainMaterial;
+@group(#{MATERIAL_BIND_GROUP}) @binding(101)
+var block_textures: texture_2d_array<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(102)
+var block_textures_sampler: sampler;
 
 fn pcg(n: u32) -> u32 {
     var h = n * 747796405u + 2891336453u;
@@ -48,7 +52,23 @@ fn fragment(
     var rand_block_color = vec3<f32>(f32(random1)/ 2147483647.5f - 1f, f32(random2)/ 2147483647.5f - 1f, f32(random3)/ 2147483647.5f - 1f);
     var max_color = max(max(pbr_input.material.base_color.x, pbr_input.material.base_color.y), pbr_input.material.base_color.z);
 
-    pbr_input.material.base_color += vec4<f32>((rand_block_color * 0.08 - 0.04) * max_color, 1);
+    var textured = false;
+
+#ifdef VERTEX_UVS_B
+    // close chunks store the texture layer of the block face in the second uv, -1 if the block has no texture
+    var texture_layer = i32(round(in.uv_b.x));
+
+    if (texture_layer >= 0) {
+        // the uvs count blocks, so the texture repeats once per block across greedy meshed quads
+        var texture_color = textureSampleLevel(block_textures, block_textures_sampler, fract(in.uv), texture_layer, 0.0);
+        pbr_input.material.base_color *= texture_color;
+        textured = true;
+    }
+#endif
+
+    if (!textured) {
+        pbr_input.material.base_color += vec4<f32>((rand_block_color * 0.08 - 0.04) * max_color, 0);
+    }
 
     // alpha discard
     pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
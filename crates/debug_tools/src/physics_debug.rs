@@ -1,12 +1,14 @@
 use bevy::prelude::*;
 
 use physics::{
-    collider::Collider,
-    collider_trait::ColliderTrait,
-    physics_object::{DynamicPhysicsObject, StaticPhysicsObject},
-    physics_position::PhysicsPosition,
+    collider::Collider, collider_trait::ColliderTrait,
+    physics_object::DynamicPhysicsObject, physics_position::PhysicsPosition,
+    static_colliders::StaticColliders,
 };
 
+/// How far around dynamic objects static colliders are drawn.
+const STATIC_COLLIDER_RANGE: f32 = 4.;
+
 #[derive(Resource, Default)]
 pub struct PhysicsDebugResource;
 
@@ -16,7 +18,7 @@ pub fn render_physics_debug(
         (&Collider, &PhysicsPosition),
         With<DynamicPhysicsObject>,
     >,
-    colliders_static: Query<(&Collider, &Transform), With<StaticPhysicsObject>>,
+    static_colliders: StaticColliders,
 ) {
     for (collider, position) in colliders_dynamic {
         for aabb in collider.get_aabbs() {
@@ -26,30 +28,21 @@ pub fn render_physics_debug(
                 Color::hsl(100., 1., 0.5),
             );
         }
-    }
-
-    for (collider, transform) in colliders_static {
-        let min_distance = colliders_dynamic
-            .iter()
-            .map(|d| d.1.distance_squared(transform.translation).ceil() as i32)
-            .min();
 
-        let Some(min_distance) = min_distance else {
-            return;
-        };
-
-        if min_distance > 32i32.pow(2) {
-            continue;
-        }
-
-        for aabb in collider.get_aabbs() {
-            gizmos.cube(
-                Transform::from_translation(
-                    transform.translation + aabb.offset,
-                )
-                .with_scale(aabb.size),
-                Color::hsl(200., 1., 0.5),
-            );
+        let (min, max) = collider.get_bounds(**position);
+        for (static_collider, static_position) in static_colliders
+            .get_colliders_in(
+                min - STATIC_COLLIDER_RANGE,
+                max + STATIC_COLLIDER_RANGE,
+            )
+        {
+            for aabb in static_collider.get_aabbs() {
+                gizmos.cube(
+                    Transform::from_translation(static_position + aabb.offset)
+                        .with_scale(aabb.size),
+                    Color::hsl(200., 1., 0.5),
+                );
+            }
         }
     }
 }
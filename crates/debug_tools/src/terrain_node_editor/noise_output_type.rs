@@ -5,6 +5,12 @@ use serde::{Deserialize, Serialize};
 pub enum NoiseOutputType {
     TerrainHeight,
     GrassHue,
+    // Biomes
+    Temperature,
+    Humidity,
+    // Density
+    OverhangDensity,
+    CaveDensity,
     // Oak
     OakMinThickness,
     OakMaxLength,
@@ -27,6 +33,10 @@ pub enum NoiseOutputType {
 pub const ALL_NOISE_OUTPUT_TYPES: &[NoiseOutputType] = &[
     NoiseOutputType::TerrainHeight,
     NoiseOutputType::GrassHue,
+    NoiseOutputType::Temperature,
+    NoiseOutputType::Humidity,
+    NoiseOutputType::OverhangDensity,
+    NoiseOutputType::CaveDensity,
     NoiseOutputType::OakMinThickness,
     NoiseOutputType::OakMaxLength,
     NoiseOutputType::OakMinLength,
@@ -49,6 +59,10 @@ impl NoiseOutputType {
         match self {
             NoiseOutputType::TerrainHeight => "Terrain Height",
             NoiseOutputType::GrassHue => "Grass Hue",
+            NoiseOutputType::Temperature => "Temperature",
+            NoiseOutputType::Humidity => "Humidity",
+            NoiseOutputType::OverhangDensity => "Overhang Density",
+            NoiseOutputType::CaveDensity => "Cave Density",
             NoiseOutputType::OakMinThickness => "Oak Min Thickness",
             NoiseOutputType::OakMaxLength => "Oak Max Length",
             NoiseOutputType::OakMinLength => "Oak Min Length",
@@ -9,6 +9,7 @@ use bevy::prelude::*;
 use egui::Ui;
 use egui_node_editor::{GraphEditorState, Node, NodeResponse, OutputId};
 use ron::ser::PrettyConfig;
+use utils::file_utils::get_asset_path;
 use world_generation::chunk_generation::noise::terrain_noise_group::TerrainNoiseGroup;
 
 use crate::{
@@ -41,12 +42,11 @@ pub struct TerrainGraphResource {
     state: TerrainGraphEditorState,
 }
 
-const TERRAIN_NOISE_GRAPH_FILE_PATH: &'static str =
-    "assets/terrain_noise_graph.ron";
+const TERRAIN_NOISE_GRAPH_FILE_PATH: &'static str = "terrain_noise_graph.ron";
 
 impl Default for TerrainGraphResource {
     fn default() -> Self {
-        let file = File::open(TERRAIN_NOISE_GRAPH_FILE_PATH);
+        let file = File::open(get_asset_path(TERRAIN_NOISE_GRAPH_FILE_PATH));
         let Ok(mut file) = file else {
             return Self {
                 state: Default::default(),
@@ -142,9 +142,43 @@ impl TerrainGraphResource {
             Ok(terrain_noise)
         };
 
+        // Graphs saved before an output existed keep its noise from the
+        // current config.
+        let saved_noise_group: Option<TerrainNoiseGroup> =
+            std::fs::read_to_string(get_asset_path(TERRAIN_NOISE_FILE_PATH))
+                .ok()
+                .and_then(|text| ron::from_str(&text).ok());
+        let get_noise_or_saved =
+            |output_type: NoiseOutputType,
+             get_saved: fn(&TerrainNoiseGroup) -> &TerrainNoise|
+             -> Result<TerrainNoise, anyhow::Error> {
+                get_terrain_noise(output_type).or_else(|err| {
+                    saved_noise_group
+                        .as_ref()
+                        .map(|noise_group| get_saved(noise_group).clone())
+                        .ok_or(err)
+                })
+            };
+
         let noise_group = TerrainNoiseGroup {
             terrain_height: get_terrain_noise(NoiseOutputType::TerrainHeight)?,
             grass_hue: get_terrain_noise(NoiseOutputType::GrassHue)?,
+            temperature: get_noise_or_saved(
+                NoiseOutputType::Temperature,
+                |noise_group| &noise_group.temperature,
+            )?,
+            humidity: get_noise_or_saved(
+                NoiseOutputType::Humidity,
+                |noise_group| &noise_group.humidity,
+            )?,
+            overhang_density: get_noise_or_saved(
+                NoiseOutputType::OverhangDensity,
+                |noise_group| &noise_group.overhang_density,
+            )?,
+            cave_density: get_noise_or_saved(
+                NoiseOutputType::CaveDensity,
+                |noise_group| &noise_group.cave_density,
+            )?,
             oak_min_thickness: get_terrain_noise(
                 NoiseOutputType::OakMinThickness,
             )?,
@@ -184,13 +218,14 @@ impl TerrainGraphResource {
             )?,
         };
 
-        let mut file = File::create(TERRAIN_NOISE_FILE_PATH)?;
+        let mut file = File::create(get_asset_path(TERRAIN_NOISE_FILE_PATH))?;
         let text =
             ron::ser::to_string_pretty(&noise_group, PrettyConfig::default())?;
         file.write_all(text.as_bytes())?;
         file.flush()?;
 
-        let mut file = File::create(TERRAIN_NOISE_GRAPH_FILE_PATH)?;
+        let mut file =
+            File::create(get_asset_path(TERRAIN_NOISE_GRAPH_FILE_PATH))?;
         let text =
             ron::ser::to_string_pretty(&self.state, PrettyConfig::default())?;
         file.write_all(text.as_bytes())?;
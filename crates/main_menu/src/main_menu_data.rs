@@ -1,6 +1,12 @@
+use bevy::prelude::*;
+use player::player_profile::PlayerProfile;
+
+#[derive(Resource)]
 pub struct MainMenuData {
     pub seed: String,
     pub server_ip: String,
+    pub error: Option<String>,
+    pub player_profile: PlayerProfile,
 }
 
 impl Default for MainMenuData {
@@ -8,6 +14,8 @@ impl Default for MainMenuData {
         Self {
             seed: "Seed".into(),
             server_ip: "".into(),
+            error: None,
+            player_profile: PlayerProfile::default(),
         }
     }
 }
@@ -5,12 +5,17 @@ use bevy::{
     window::{CursorGrabMode, CursorOptions},
 };
 use bevy_egui::{EguiContexts, EguiPrimaryContextPass};
+#[cfg(feature = "steam")]
+use bevy_steamworks::Client;
+#[cfg(feature = "steam")]
+use networking::start_steam_server::StartSteamServer;
 use networking::{
-    create_world::CreateWorld, start_self_session::StartSelfSession,
-    start_steam_server::StartSteamServer,
+    connection_failed::ConnectionFailed, create_world::CreateWorld,
+    start_self_session::StartSelfSession,
     start_websocket_client::StartWebsocketClient,
     start_websocket_server::StartWebsocketServer,
 };
+use player::player_profile::LocalPlayerProfile;
 
 use crate::{
     main_menu_data::MainMenuData,
@@ -23,7 +28,11 @@ pub struct MainMenuPlugin;
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<MainMenuState>()
-            .add_systems(OnEnter(MainMenuState::Shown), add_menu_cam)
+            .init_resource::<MainMenuData>()
+            .add_systems(
+                OnEnter(MainMenuState::Shown),
+                (add_menu_cam, show_cursor),
+            )
             .add_systems(OnEnter(MainMenuState::Hidden), hide_cursor)
             .add_systems(
                 EguiPrimaryContextPass,
@@ -33,32 +42,72 @@ impl Plugin for MainMenuPlugin {
                         .run_if(in_state(MainMenuState::LoadingWorldGen)),
                 ),
             )
-            .add_observer(hide_main_menu);
+            .add_observer(hide_main_menu)
+            .add_observer(show_connection_error);
     }
 }
 
 #[derive(Component)]
 pub struct MenuCamera;
 
-fn add_menu_cam(mut commands: Commands) {
+fn add_menu_cam(
+    mut commands: Commands,
+    menu_cameras: Query<(), With<MenuCamera>>,
+) {
+    // The camera is still around when coming back from a failed connection.
+    if !menu_cameras.is_empty() {
+        return;
+    }
+
     commands.spawn((Camera2d, MenuCamera));
 }
 
+fn show_connection_error(
+    event: On<ConnectionFailed>,
+    mut menu_data: ResMut<MainMenuData>,
+    mut menu_state: ResMut<NextState<MainMenuState>>,
+) {
+    menu_data.error = Some(event.message.clone());
+    menu_state.set(MainMenuState::Shown);
+}
+
+/// Coming back from a session, e.g. after being kicked.
+fn show_cursor(mut cursor_options: Single<&mut CursorOptions, With<Window>>) {
+    cursor_options.visible = true;
+    cursor_options.grab_mode = CursorGrabMode::None;
+}
+
 fn hide_cursor(mut cursor_options: Single<&mut CursorOptions, With<Window>>) {
     cursor_options.visible = false;
     cursor_options.grab_mode = CursorGrabMode::Locked;
 }
 
 fn render_main_menu(
-    mut menu_data: Local<MainMenuData>,
+    mut menu_data: ResMut<MainMenuData>,
     mut menu_state: ResMut<NextState<MainMenuState>>,
+    mut local_player_profile: ResMut<LocalPlayerProfile>,
     mut commands: Commands,
     mut contexts: EguiContexts,
+    #[cfg(feature = "steam")] steam_client: Option<Res<Client>>,
 ) -> Result {
     egui::CentralPanel::default().show(contexts.ctx_mut()?, |ui| {
         ui.vertical_centered(|ui| {
             ui.heading("SpellHaven");
 
+            if let Some(error) = &menu_data.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut menu_data.player_profile.name)
+                    .on_hover_text("Player name");
+                ui.color_edit_button_rgb(&mut menu_data.player_profile.color)
+                    .on_hover_text("Player color");
+            });
+            local_player_profile.0 = menu_data.player_profile.sanitized();
+
+            ui.add_space(10.);
+
             ui.text_edit_singleline(&mut menu_data.seed);
             if ui.button("Singleplayer").clicked() {
                 let mut hasher = DefaultHasher::new();
@@ -75,7 +124,11 @@ fn render_main_menu(
 
             ui.add_space(10.);
 
-            if ui.button("Host game (Steam)").clicked() {
+            // Only offered when Steam could be initialized on startup.
+            #[cfg(feature = "steam")]
+            if steam_client.is_some()
+                && ui.button("Host game (Steam)").clicked()
+            {
                 let mut hasher = DefaultHasher::new();
                 menu_data.seed.hash(&mut hasher);
                 let seed = hasher.finish();
@@ -88,6 +141,7 @@ fn render_main_menu(
                 menu_state.set(MainMenuState::LoadingWorldGen);
             }
 
+            #[cfg(feature = "steam")]
             ui.add_space(10.);
 
             if ui.button("Host game (Server)").clicked() {
@@ -97,7 +151,7 @@ fn render_main_menu(
 
                 info!("Seed to use: {}", seed);
 
-                commands.trigger(StartWebsocketServer);
+                commands.trigger(StartWebsocketServer::default());
                 commands.trigger(CreateWorld { seed });
 
                 menu_state.set(MainMenuState::LoadingWorldGen);
@@ -106,13 +160,18 @@ fn render_main_menu(
             ui.add_space(20.);
 
             ui.text_edit_singleline(&mut menu_data.server_ip)
-                .on_hover_text("Server Ip");
+                .on_hover_text("Server address (host:port)");
             if ui.button("Join game").clicked() {
-                commands.trigger(StartWebsocketClient {
-                    address: menu_data.server_ip.clone(),
-                });
-
-                menu_state.set(MainMenuState::LoadingWorldGen);
+                match StartWebsocketClient::from_address(&menu_data.server_ip) {
+                    Ok(start_websocket_client) => {
+                        menu_data.error = None;
+                        commands.trigger(start_websocket_client);
+                        menu_state.set(MainMenuState::LoadingWorldGen);
+                    }
+                    Err(err) => {
+                        menu_data.error = Some(err.to_string());
+                    }
+                }
             }
         });
     });
@@ -0,0 +1,479 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use bevy_hookup_core::{
+    from_session::FromSession, hook_session::SessionId,
+    received_event::ReceivedEvent, session::Session,
+    share_component::ShareComponent, sync_entity::SyncEntityOwner,
+};
+use player::{
+    network_movement::{
+        authoritative_movement::get_player_id,
+        player_input_buffer::PlayerInputBuffer, player_snapshot::LocalPlayerId,
+    },
+    player_component::Player,
+};
+use serde::{Deserialize, Serialize};
+use world_generation::{
+    chunk_generation::{CHUNK_SIZE, VOXEL_SIZE, block_type::BlockType},
+    chunk_loading::{chunk_loader::ChunkLoader, chunk_tree::ChunkTreePos},
+    voxel_editing::{
+        edit_block::EditBlock,
+        set_block::SetBlock,
+        voxel_ray_cast::{VoxelRayCast, get_voxel_point},
+    },
+    world_save::voxel_delta_store::{ChunkDelta, VoxelDeltaStore},
+};
+
+use crate::{networking_state::NetworkingState, sendables::Sendables};
+
+/// How far away from a player the host still accepts block edits.
+/// Generous, since players reach blocks from their third person camera.
+const MAX_EDIT_DISTANCE: f32 = 32.;
+/// How many block edits a client keeps sending until the host acknowledges
+/// them. Edits beyond that get dropped instead of growing every message.
+const MAX_PENDING_BLOCK_EDITS: usize = 64;
+
+/// The block edits a client asks the host for, kept until the host
+/// acknowledged them, so edits made between two syncs don't get lost.
+/// Lives on the player entity of the client, so the host knows who sent them.
+#[derive(Component, Clone, Default, Serialize, Deserialize, Debug)]
+pub struct BlockEditRequests {
+    pub next_sequence: u64,
+    pub requests: Vec<BlockEditRequest>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BlockEditRequest {
+    pub sequence: u64,
+    pub voxel_pos: IVec3,
+    pub block: BlockType,
+}
+
+impl BlockEditRequests {
+    fn push(&mut self, voxel_pos: IVec3, block: BlockType) {
+        if self.requests.len() >= MAX_PENDING_BLOCK_EDITS {
+            warn!(
+                "Dropped block edit at {}, the host has not acknowledged the \
+                 previous ones",
+                voxel_pos
+            );
+            return;
+        }
+
+        self.requests.push(BlockEditRequest {
+            sequence: self.next_sequence,
+            voxel_pos,
+            block,
+        });
+        self.next_sequence += 1;
+    }
+}
+
+/// The sequence of the last block edit request the host handled for a
+/// player. Its client stops sending the requests up to it.
+#[derive(Component, Clone, Serialize, Deserialize, Debug)]
+pub struct BlockEditAck {
+    pub player_id: u64,
+    pub sequence: u64,
+}
+
+/// The sequence of the last request the host handled for a player.
+#[derive(Component)]
+pub(crate) struct HandledBlockEditSequence(u64);
+
+/// The ack entities the host shares, by the player they belong to.
+#[derive(Default)]
+pub(crate) struct SharedBlockEditAcks {
+    acks: HashMap<Entity, Entity>,
+}
+
+/// Block edits the host accepted inside one chunk column. Clients patch
+/// them onto the terrain they generate from the shared seed.
+/// Only sent to the sessions whose players load the column.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ChunkColumnEdits {
+    pub chunk_column: IVec2,
+    pub blocks: Vec<(IVec3, BlockType)>,
+}
+
+impl ChunkColumnEdits {
+    fn from_delta(chunk_column: IVec2, chunk_delta: &ChunkDelta) -> Self {
+        let mut blocks = chunk_delta
+            .blocks
+            .iter()
+            .map(|(voxel_pos, block)| (*voxel_pos, *block))
+            .collect::<Vec<_>>();
+        blocks.sort_by_key(|(voxel_pos, _)| voxel_pos.to_array());
+
+        Self {
+            chunk_column,
+            blocks,
+        }
+    }
+}
+
+/// The blocks the host set since their chunk columns were last shared,
+/// in order.
+#[derive(Resource, Default)]
+pub(crate) struct UnsharedBlockEdits(Vec<(IVec3, BlockType)>);
+
+/// The chunk column edits a client received, until its world is created.
+#[derive(Resource, Default)]
+pub(crate) struct ReceivedChunkColumnEdits(Vec<ChunkColumnEdits>);
+
+/// The edited chunk columns a remote player loads, and the chunk tree its
+/// loader was in when they were looked up.
+#[derive(Default)]
+struct LoadedChunkColumns {
+    tree_pos: Option<ChunkTreePos>,
+    chunk_columns: HashSet<IVec2>,
+}
+
+#[derive(Default)]
+pub(crate) struct SharedChunkColumns {
+    players: HashMap<Entity, LoadedChunkColumns>,
+}
+
+pub(crate) fn edit_block(
+    edit_block: On<EditBlock>,
+    mut commands: Commands,
+    networking_state: Res<State<NetworkingState>>,
+    mut players: Query<(Entity, Option<&mut BlockEditRequests>), With<Player>>,
+) {
+    if *networking_state.get() != NetworkingState::Client {
+        commands.trigger(SetBlock {
+            voxel_pos: edit_block.voxel_pos,
+            block: edit_block.block,
+        });
+        return;
+    }
+
+    let Ok((player_entity, block_edit_requests)) = players.single_mut() else {
+        return;
+    };
+
+    match block_edit_requests {
+        Some(mut block_edit_requests) => {
+            block_edit_requests.push(edit_block.voxel_pos, edit_block.block);
+        }
+        None => {
+            let mut block_edit_requests = BlockEditRequests::default();
+            block_edit_requests.push(edit_block.voxel_pos, edit_block.block);
+
+            commands.entity(player_entity).insert((
+                block_edit_requests,
+                ShareComponent::<BlockEditRequests>::default(),
+            ));
+        }
+    }
+}
+
+type EditingPlayer = (
+    Entity,
+    &'static BlockEditRequests,
+    &'static Transform,
+    Option<&'static HandledBlockEditSequence>,
+);
+
+pub(crate) fn validate_block_edit_requests(
+    mut commands: Commands,
+    players: Query<
+        EditingPlayer,
+        (Changed<BlockEditRequests>, Without<Player>),
+    >,
+    voxel_ray_cast: VoxelRayCast,
+) {
+    for (entity, block_edit_requests, player_transform, handled_sequence) in
+        &players
+    {
+        let requests = block_edit_requests.requests.iter().filter(|request| {
+            handled_sequence.is_none_or(|handled_sequence| {
+                request.sequence > handled_sequence.0
+            })
+        });
+
+        // The edits only get applied once the commands run, so later
+        // requests are checked against the earlier ones of the same batch.
+        let mut edited_blocks = HashMap::new();
+        let mut last_sequence = None;
+
+        for request in requests {
+            last_sequence = Some(request.sequence);
+
+            let current_block = edited_blocks
+                .get(&request.voxel_pos)
+                .copied()
+                .or_else(|| voxel_ray_cast.get_block(request.voxel_pos));
+
+            if let Err(err) = validate_request(
+                request,
+                current_block,
+                player_transform.translation,
+            ) {
+                warn!("Rejected block edit at {}: {}", request.voxel_pos, err);
+                continue;
+            }
+
+            edited_blocks.insert(request.voxel_pos, request.block);
+            commands.trigger(SetBlock {
+                voxel_pos: request.voxel_pos,
+                block: request.block,
+            });
+        }
+
+        if let Some(last_sequence) = last_sequence {
+            commands
+                .entity(entity)
+                .insert(HandledBlockEditSequence(last_sequence));
+        }
+    }
+}
+
+fn validate_request(
+    request: &BlockEditRequest,
+    current_block: Option<BlockType>,
+    player_position: Vec3,
+) -> Result<(), String> {
+    let distance = get_voxel_point(request.voxel_pos).distance(player_position);
+    if distance > MAX_EDIT_DISTANCE {
+        return Err(format!("Player is {:.1}m away", distance));
+    }
+
+    if request.block != BlockType::AIR
+        && !request.block.get_definition().placeable
+    {
+        return Err(format!(
+            "{} can not be placed",
+            request.block.get_definition().name
+        ));
+    }
+
+    let Some(current_block) = current_block else {
+        return Err("Block is not loaded".into());
+    };
+
+    match (current_block, request.block) {
+        (BlockType::AIR, BlockType::AIR) => Err("Block is already air".into()),
+        (_, BlockType::AIR) => Ok(()),
+        // Blocks can be placed into air and water.
+        (current_block, _) if !current_block.is_colliding() => Ok(()),
+        _ => Err("Block is occupied".into()),
+    }
+}
+
+/// Shares the last handled request of every player, rejected ones
+/// included, so their clients stop sending them.
+pub(crate) fn share_block_edit_acks(
+    mut commands: Commands,
+    players: Query<
+        (Entity, &HandledBlockEditSequence),
+        Changed<HandledBlockEditSequence>,
+    >,
+    mut removed_players: RemovedComponents<HandledBlockEditSequence>,
+    mut shared_block_edit_acks: Local<SharedBlockEditAcks>,
+) {
+    for entity in removed_players.read() {
+        if let Some(ack_entity) = shared_block_edit_acks.acks.remove(&entity) {
+            commands.entity(ack_entity).despawn();
+        }
+    }
+
+    for (entity, handled_sequence) in &players {
+        let block_edit_ack = BlockEditAck {
+            player_id: get_player_id(entity),
+            sequence: handled_sequence.0,
+        };
+
+        match shared_block_edit_acks.acks.get(&entity) {
+            Some(ack_entity) => {
+                commands.entity(*ack_entity).insert(block_edit_ack);
+            }
+            None => {
+                let ack_entity = commands
+                    .spawn((
+                        SyncEntityOwner::new(),
+                        block_edit_ack,
+                        ShareComponent::<BlockEditAck>::default(),
+                        Name::new("BlockEditAck"),
+                    ))
+                    .id();
+                shared_block_edit_acks.acks.insert(entity, ack_entity);
+            }
+        }
+    }
+}
+
+/// Stops sending the requests the host acknowledged.
+pub(crate) fn remove_acknowledged_block_edits(
+    block_edit_acks: Query<&BlockEditAck, Changed<BlockEditAck>>,
+    mut players: Query<&mut BlockEditRequests, With<Player>>,
+    local_player_id: LocalPlayerId,
+) {
+    let Ok(mut block_edit_requests) = players.single_mut() else {
+        return;
+    };
+    let Some(player_id) = local_player_id.get() else {
+        return;
+    };
+
+    for block_edit_ack in &block_edit_acks {
+        if block_edit_ack.player_id != player_id {
+            continue;
+        }
+
+        block_edit_requests
+            .requests
+            .retain(|request| request.sequence > block_edit_ack.sequence);
+    }
+}
+
+type RemotePlayerLoaders = (
+    Entity,
+    &'static FromSession,
+    &'static ChunkLoader,
+    &'static Transform,
+);
+
+/// Chunk trees are made of whole chunk columns.
+fn get_chunk_column_tree_pos(chunk_column: IVec2) -> ChunkTreePos {
+    ChunkTreePos::from_global_pos(
+        chunk_column.as_vec2() * CHUNK_SIZE as f32 * VOXEL_SIZE,
+    )
+}
+
+/// Keeps every block the host sets, so clients only get sent the new ones.
+pub(crate) fn record_block_edit(
+    set_block: On<SetBlock>,
+    networking_state: Res<State<NetworkingState>>,
+    mut unshared_block_edits: ResMut<UnsharedBlockEdits>,
+) {
+    if *networking_state.get() != NetworkingState::Host {
+        return;
+    }
+
+    unshared_block_edits
+        .0
+        .push((set_block.voxel_pos, set_block.block));
+}
+
+/// Sends every edited chunk column to the clients whose players start
+/// loading it, and after that only the blocks the host set inside it.
+/// The loaded columns of a player are only looked up again once its loader
+/// moves into another chunk tree.
+pub(crate) fn share_chunk_column_edits(
+    players: Query<RemotePlayerLoaders, With<PlayerInputBuffer>>,
+    mut removed_players: RemovedComponents<PlayerInputBuffer>,
+    sessions: Query<&mut Session<Sendables>>,
+    voxel_delta_store: Res<VoxelDeltaStore>,
+    mut unshared_block_edits: ResMut<UnsharedBlockEdits>,
+    mut shared_chunk_columns: Local<SharedChunkColumns>,
+) {
+    for entity in removed_players.read() {
+        shared_chunk_columns.players.remove(&entity);
+    }
+
+    let mut edited_chunk_columns = HashMap::<IVec2, Vec<_>>::new();
+    for (voxel_pos, block) in unshared_block_edits.0.drain(..) {
+        edited_chunk_columns
+            .entry(VoxelDeltaStore::get_chunk_column(voxel_pos))
+            .or_default()
+            .push((voxel_pos, block));
+    }
+
+    let mut session_edits = HashMap::<SessionId, Vec<_>>::new();
+
+    for (entity, from_session, chunk_loader, transform) in &players {
+        let tree_pos =
+            ChunkTreePos::from_global_pos(transform.translation.xz());
+        let keeps_loaded = |chunk_column: IVec2| {
+            chunk_loader.keeps_loaded(
+                get_chunk_column_tree_pos(chunk_column),
+                transform.translation,
+            )
+        };
+        let edits = session_edits.entry(from_session.session_id).or_default();
+        let loaded_columns =
+            shared_chunk_columns.players.entry(entity).or_default();
+
+        // Columns sent whole already contain the new edits.
+        let mut sent_columns = HashSet::new();
+        if loaded_columns.tree_pos != Some(tree_pos) {
+            loaded_columns.tree_pos = Some(tree_pos);
+            let previous_columns =
+                std::mem::take(&mut loaded_columns.chunk_columns);
+
+            for (chunk_column, chunk_delta) in voxel_delta_store.iter() {
+                if !keeps_loaded(*chunk_column) {
+                    continue;
+                }
+
+                loaded_columns.chunk_columns.insert(*chunk_column);
+                if !previous_columns.contains(chunk_column) {
+                    edits.push(ChunkColumnEdits::from_delta(
+                        *chunk_column,
+                        chunk_delta,
+                    ));
+                    sent_columns.insert(*chunk_column);
+                }
+            }
+        }
+
+        for (chunk_column, blocks) in &edited_chunk_columns {
+            if sent_columns.contains(chunk_column)
+                || !keeps_loaded(*chunk_column)
+            {
+                continue;
+            }
+
+            loaded_columns.chunk_columns.insert(*chunk_column);
+            edits.push(ChunkColumnEdits {
+                chunk_column: *chunk_column,
+                blocks: blocks.clone(),
+            });
+        }
+    }
+
+    for mut session in sessions {
+        let Some(edits) = session_edits.remove(&session.get_session_id())
+        else {
+            continue;
+        };
+
+        for chunk_column_edits in &edits {
+            session.send_event(Sendables::from(chunk_column_edits));
+        }
+    }
+}
+
+pub(crate) fn receive_chunk_column_edits(
+    chunk_column_edits: On<ReceivedEvent<ChunkColumnEdits>>,
+    mut received_chunk_column_edits: ResMut<ReceivedChunkColumnEdits>,
+) {
+    received_chunk_column_edits
+        .0
+        .push(chunk_column_edits.event.clone());
+}
+
+/// Applies the edits the host shared on top of the locally generated terrain.
+pub(crate) fn apply_chunk_column_edits(
+    mut commands: Commands,
+    mut received_chunk_column_edits: ResMut<ReceivedChunkColumnEdits>,
+    voxel_delta_store: Res<VoxelDeltaStore>,
+) {
+    for chunk_column_edits in received_chunk_column_edits.0.drain(..) {
+        for (voxel_pos, block) in chunk_column_edits.blocks {
+            if voxel_delta_store.get_block(voxel_pos) == Some(block) {
+                continue;
+            }
+
+            commands.trigger(SetBlock { voxel_pos, block });
+        }
+    }
+}
+
+/// The delta store is replaced once the world is created,
+/// so edits received before that would get lost.
+pub(crate) fn has_world_seed(voxel_delta_store: Res<VoxelDeltaStore>) -> bool {
+    voxel_delta_store.get_seed().is_some()
+}
@@ -0,0 +1,377 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use bevy_hookup_core::{
+    from_session::FromSession, hook_session::SessionId,
+    received_event::ReceivedEvent, session::Session,
+    share_component::ShareComponent,
+};
+use bevy_hookup_messenger_websocket::websocket_client::WebsocketClient;
+use player::{
+    network_movement::player_input_buffer::PlayerInputBuffer,
+    player_component::Player, player_profile::PlayerProfile,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connection_failed::ConnectionFailed, networking_state::NetworkingState,
+    sendables::Sendables,
+};
+
+/// How many messages the host keeps, older ones are dropped.
+const CHAT_HISTORY_LENGTH: usize = 100;
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 256;
+const KICK_COMMAND: &str = "/kick ";
+/// How long the host keeps the connection of a kicked client open,
+/// so the message telling it why gets out first.
+const KICKED_SESSION_CLOSE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum ChatMessageSender {
+    Player(PlayerProfile),
+    System,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ChatMessage {
+    pub sender: ChatMessageSender,
+    pub text: String,
+}
+
+/// Every chat message of the session. Owned by the host and shared with
+/// all clients, so players joining later get the history as well.
+#[derive(Component, Clone, Serialize, Deserialize, Debug, Default)]
+pub struct ChatHistory {
+    pub messages: Vec<ChatMessage>,
+}
+
+impl ChatHistory {
+    pub fn push(&mut self, sender: ChatMessageSender, text: String) {
+        self.messages.push(ChatMessage { sender, text });
+
+        if self.messages.len() > CHAT_HISTORY_LENGTH {
+            self.messages.remove(0);
+        }
+    }
+
+    fn push_system(&mut self, text: String) {
+        info!("{}", text);
+        self.push(ChatMessageSender::System, text);
+    }
+}
+
+/// The latest chat message a client asks the host to post.
+/// Lives on the player entity of the client, so the host knows who sent it.
+#[derive(Component, Clone, Serialize, Deserialize, Debug)]
+pub struct ChatInput {
+    pub sequence: u64,
+    pub text: String,
+}
+
+/// The sequence of the last chat message the host handled for a player.
+#[derive(Component)]
+pub(crate) struct HandledChatSequence(u64);
+
+/// Tells a client why the host removed its player from the session.
+/// Only sent to the session of that client.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PlayerKicked {
+    pub reason: KickReason,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum KickReason {
+    KickedByHost,
+    ServerFull,
+}
+
+/// The sessions the host removed the players of.
+#[derive(Resource, Default)]
+pub(crate) struct KickedSessions(HashSet<SessionId>);
+
+impl KickReason {
+    fn get_message(&self) -> &'static str {
+        match self {
+            KickReason::KickedByHost => "You were kicked by the host",
+            KickReason::ServerFull => "The server is full",
+        }
+    }
+}
+
+/// Closes the connection of a kicked client once the timer finishes.
+#[derive(Component)]
+pub(crate) struct ClosingSession(Timer);
+
+/// Posts a message from the local player.
+/// As the host, `/kick <name>` kicks a player instead.
+#[derive(Event)]
+pub struct SendChatMessage {
+    pub text: String,
+}
+
+/// Removes a player from the session. Only does something on the host.
+#[derive(Event)]
+pub struct KickPlayer {
+    pub name: String,
+}
+
+/// Removes the player of a session and tells its client why.
+/// Only does something on the host.
+#[derive(Event)]
+pub struct KickSession {
+    pub session_id: SessionId,
+    pub reason: KickReason,
+}
+
+/// The names of the remote players the host knows,
+/// so it can still name them after they left.
+#[derive(Resource, Default)]
+pub(crate) struct RemotePlayerNames(HashMap<Entity, String>);
+
+fn sanitize_message(text: &str) -> Option<String> {
+    let text = text
+        .trim()
+        .chars()
+        .take(MAX_CHAT_MESSAGE_LENGTH)
+        .collect::<String>();
+
+    (!text.is_empty()).then_some(text)
+}
+
+pub(crate) fn send_chat_message(
+    send_chat_message: On<SendChatMessage>,
+    mut commands: Commands,
+    networking_state: Res<State<NetworkingState>>,
+    mut players: Query<
+        (Entity, &PlayerProfile, Option<&mut ChatInput>),
+        With<Player>,
+    >,
+    chat_history: Option<Single<&mut ChatHistory>>,
+) {
+    let Some(text) = sanitize_message(&send_chat_message.text) else {
+        return;
+    };
+
+    let Ok((player_entity, player_profile, chat_input)) = players.single_mut()
+    else {
+        return;
+    };
+
+    if *networking_state.get() == NetworkingState::Client {
+        match chat_input {
+            Some(mut chat_input) => {
+                chat_input.sequence += 1;
+                chat_input.text = text;
+            }
+            None => {
+                commands.entity(player_entity).insert((
+                    ChatInput { sequence: 0, text },
+                    ShareComponent::<ChatInput>::default(),
+                ));
+            }
+        }
+        return;
+    }
+
+    if let Some(name) = text.strip_prefix(KICK_COMMAND) {
+        commands.trigger(KickPlayer {
+            name: name.trim().into(),
+        });
+        return;
+    }
+
+    let Some(mut chat_history) = chat_history else {
+        return;
+    };
+
+    chat_history.push(ChatMessageSender::Player(player_profile.clone()), text);
+}
+
+type ReceivedChatInput = (
+    Entity,
+    &'static ChatInput,
+    &'static PlayerProfile,
+    Option<&'static HandledChatSequence>,
+);
+
+pub(crate) fn receive_chat_inputs(
+    mut commands: Commands,
+    chat_inputs: Query<
+        ReceivedChatInput,
+        (Changed<ChatInput>, Without<Player>),
+    >,
+    mut chat_history: Single<&mut ChatHistory>,
+) {
+    for (entity, chat_input, player_profile, handled_sequence) in &chat_inputs {
+        if handled_sequence.is_some_and(|handled_sequence| {
+            handled_sequence.0 >= chat_input.sequence
+        }) {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .insert(HandledChatSequence(chat_input.sequence));
+
+        let Some(text) = sanitize_message(&chat_input.text) else {
+            continue;
+        };
+
+        chat_history
+            .push(ChatMessageSender::Player(player_profile.sanitized()), text);
+    }
+}
+
+type JoinedPlayers = (Changed<PlayerProfile>, With<FromSession>);
+
+pub(crate) fn announce_joins_and_leaves(
+    joined_players: Query<(Entity, &PlayerProfile), JoinedPlayers>,
+    mut left_players: RemovedComponents<PlayerProfile>,
+    mut remote_player_names: ResMut<RemotePlayerNames>,
+    mut chat_history: Single<&mut ChatHistory>,
+) {
+    for (entity, player_profile) in &joined_players {
+        let name = player_profile.sanitized().name;
+
+        if remote_player_names.0.insert(entity, name.clone()).is_none() {
+            chat_history.push_system(format!("{} joined the game", name));
+        }
+    }
+
+    for entity in left_players.read() {
+        if let Some(name) = remote_player_names.0.remove(&entity) {
+            chat_history.push_system(format!("{} left the game", name));
+        }
+    }
+}
+
+pub(crate) fn kick_player(
+    kick_player: On<KickPlayer>,
+    mut commands: Commands,
+    networking_state: Res<State<NetworkingState>>,
+    players: Query<(Entity, &FromSession), With<PlayerInputBuffer>>,
+    mut remote_player_names: ResMut<RemotePlayerNames>,
+    chat_history: Option<Single<&mut ChatHistory>>,
+) {
+    let Some(mut chat_history) = chat_history else {
+        return;
+    };
+
+    if *networking_state.get() != NetworkingState::Host {
+        chat_history.push_system("Only the host can kick players".into());
+        return;
+    }
+
+    let named_players = players
+        .iter()
+        .filter(|(entity, _)| {
+            remote_player_names
+                .0
+                .get(entity)
+                .is_some_and(|name| *name == kick_player.name)
+        })
+        .collect::<Vec<_>>();
+
+    let [(entity, from_session)] = named_players[..] else {
+        chat_history.push_system(if named_players.is_empty() {
+            format!("There is no player named {}", kick_player.name)
+        } else {
+            format!(
+                "There are {} players named {}, nobody was kicked",
+                named_players.len(),
+                kick_player.name
+            )
+        });
+        return;
+    };
+
+    commands.trigger(KickSession {
+        session_id: from_session.session_id,
+        reason: KickReason::KickedByHost,
+    });
+    remote_player_names.0.remove(&entity);
+    commands.entity(entity).despawn();
+
+    chat_history
+        .push_system(format!("{} was kicked by the host", kick_player.name));
+}
+
+pub(crate) fn kick_session(
+    kick_session: On<KickSession>,
+    mut commands: Commands,
+    networking_state: Res<State<NetworkingState>>,
+    sessions: Query<(Entity, &mut Session<Sendables>)>,
+    mut kicked_sessions: ResMut<KickedSessions>,
+) {
+    if *networking_state.get() != NetworkingState::Host {
+        return;
+    }
+
+    kicked_sessions.0.insert(kick_session.session_id);
+
+    for (entity, mut session) in sessions {
+        if session.get_session_id() == kick_session.session_id {
+            session.send_event(Sendables::from(&PlayerKicked {
+                reason: kick_session.reason,
+            }));
+            commands.entity(entity).insert(ClosingSession(Timer::new(
+                KICKED_SESSION_CLOSE_DELAY,
+                TimerMode::Once,
+            )));
+        }
+    }
+}
+
+/// Dropping the session closes the connection to the client,
+/// whether or not it leaves on its own.
+pub(crate) fn close_kicked_sessions(
+    mut commands: Commands,
+    sessions: Query<(Entity, &mut ClosingSession)>,
+    time: Res<Time>,
+) {
+    for (entity, mut closing_session) in sessions {
+        if closing_session.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Kicked clients are told to leave, but the host keeps dropping their
+/// player in case they don't.
+pub(crate) fn remove_kicked_players(
+    mut commands: Commands,
+    players: Query<(Entity, &FromSession), With<PlayerInputBuffer>>,
+    kicked_sessions: Res<KickedSessions>,
+) {
+    for (entity, from_session) in &players {
+        if kicked_sessions.0.contains(&from_session.session_id) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub(crate) fn leave_when_kicked(
+    player_kicked: On<ReceivedEvent<PlayerKicked>>,
+    mut commands: Commands,
+    networking_state: Res<State<NetworkingState>>,
+    websocket_clients: Query<Entity, With<WebsocketClient<Sendables>>>,
+) {
+    // Only the host can kick someone.
+    if *networking_state.get() != NetworkingState::Client {
+        return;
+    }
+
+    let reason = player_kicked.reason;
+    warn!("Removed from the session: {:?}", reason);
+
+    for entity in &websocket_clients {
+        commands.entity(entity).despawn();
+    }
+
+    commands.trigger(ConnectionFailed {
+        message: reason.get_message().into(),
+    });
+}
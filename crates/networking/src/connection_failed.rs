@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+/// Triggered when a websocket client could not reach its server, the server
+/// removed it from the session, or a websocket server could not bind its
+/// address.
+#[derive(Event)]
+pub struct ConnectionFailed {
+    pub message: String,
+}
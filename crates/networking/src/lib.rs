@@ -1,8 +1,14 @@
+pub mod block_edits;
+pub mod chat;
+pub mod connection_failed;
 pub mod create_world;
 pub mod networking_plugin;
-mod networking_state;
+pub mod networking_state;
 pub mod sendables;
 pub mod start_self_session;
+#[cfg(feature = "steam")]
 pub mod start_steam_server;
 pub mod start_websocket_client;
 pub mod start_websocket_server;
+#[cfg(feature = "steam")]
+pub mod steam_networking_plugin;
@@ -1,20 +1,9 @@
 use bevy::{ecs::system::NonSendMarker, prelude::*};
 use bevy_hookup_core::{
     hookup_component_plugin::HookupComponentPlugin,
+    hookup_event_plugin::HookupEventPlugin,
     hookup_sendable_plugin::HookupSendablePlugin,
-    reshare_component_plugin::ReshareComponentPlugin,
-    reshare_entity_plugin::ReshareEntityPlugin,
-    share_component::ShareComponent,
-    sync_entity::SyncEntityOwner,
-    utils::{
-        buffer_object::BufferObject, buffer_plugin::BufferPlugin,
-        component_buffer::ComponentBuffer,
-    },
-};
-use bevy_hookup_messenger_steamworks::{
-    steamworks_client::SteamworksClient, steamworks_server::SteamworksServer,
-    steamworks_server_plugin::SteamworksServerPlugin,
-    steamworks_session_handler_plugin::SteamworksSessionHandlerPlugin,
+    share_component::ShareComponent, sync_entity::SyncEntityOwner,
 };
 use bevy_hookup_messenger_websocket::{
     websocket_client::WebsocketClient,
@@ -22,19 +11,42 @@ use bevy_hookup_messenger_websocket::{
     websocket_client_state::WebsocketClientState,
     websocket_server::WebsocketServer,
     websocket_server_plugin::WebsocketServerPlugin,
+    websocket_server_state::WebsocketServerState,
+};
+use player::{
+    network_movement::{
+        authoritative_movement::AuthoritativeMovementSystems,
+        player_input_buffer::PlayerInputBuffer,
+        player_snapshot::{AssignedPlayerId, PlayerSnapshot},
+    },
+    player_component::PlayerRotation,
+    player_profile::PlayerProfile,
 };
-use bevy_steamworks::{Client, LobbyType, SteamworksEvent};
-use physics::physics_position::PhysicsPosition;
-use player::player_component::PlayerRotation;
-use steamworks::CallbackResult;
 use world_generation::{
     generation_options::GenerationOptions, start_world_gen::StartWorldGen,
+    world_save::world_save_plugin::WorldSaveSettings,
 };
 
 use crate::{
-    create_world::CreateWorld, networking_state::NetworkingState,
-    sendables::Sendables, start_self_session::StartSelfSession,
-    start_steam_server::StartSteamServer,
+    block_edits::{
+        BlockEditAck, BlockEditRequests, ChunkColumnEdits,
+        ReceivedChunkColumnEdits, UnsharedBlockEdits, apply_chunk_column_edits,
+        edit_block, has_world_seed, receive_chunk_column_edits,
+        record_block_edit, remove_acknowledged_block_edits,
+        share_block_edit_acks, share_chunk_column_edits,
+        validate_block_edit_requests,
+    },
+    chat::{
+        ChatHistory, ChatInput, KickedSessions, PlayerKicked,
+        RemotePlayerNames, announce_joins_and_leaves, close_kicked_sessions,
+        kick_player, kick_session, leave_when_kicked, receive_chat_inputs,
+        remove_kicked_players, send_chat_message,
+    },
+    connection_failed::ConnectionFailed,
+    create_world::CreateWorld,
+    networking_state::NetworkingState,
+    sendables::Sendables,
+    start_self_session::StartSelfSession,
     start_websocket_client::StartWebsocketClient,
     start_websocket_server::StartWebsocketServer,
 };
@@ -43,81 +55,88 @@ pub struct NetworkingPlugin;
 
 impl Plugin for NetworkingPlugin {
     fn build(&self, app: &mut App) {
-        app
-        .register_type::<BufferObject<PhysicsPosition>>()
-        .register_type::<ComponentBuffer<PhysicsPosition, 4>>()
-        .add_plugins((
+        app.add_plugins((
             WebsocketClientPlugin::<Sendables>::default(),
             WebsocketServerPlugin::<Sendables>::default(),
-            SteamworksServerPlugin::<Sendables>::default(),
-            SteamworksSessionHandlerPlugin::<Sendables>::default(),
             HookupSendablePlugin::<Sendables>::default(),
             HookupComponentPlugin::<Sendables, GenerationOptions>::default(),
             HookupComponentPlugin::<Sendables, PlayerRotation>::default(),
-            BufferPlugin::<Sendables, PhysicsPosition, 4>::default(),
-            ReshareEntityPlugin::<Sendables>::default(),
-            ReshareComponentPlugin::<BufferObject<PhysicsPosition>>::default(),
-            ReshareComponentPlugin::<PlayerRotation>::default(),
+            HookupComponentPlugin::<Sendables, PlayerInputBuffer>::default(),
+            HookupComponentPlugin::<Sendables, PlayerSnapshot>::default(),
+            HookupComponentPlugin::<Sendables, AssignedPlayerId>::default(),
+            HookupComponentPlugin::<Sendables, BlockEditRequests>::default(),
+            HookupEventPlugin::<Sendables, ChunkColumnEdits>::default(),
+        ))
+        .add_plugins((
+            HookupComponentPlugin::<Sendables, PlayerProfile>::default(),
+            HookupComponentPlugin::<Sendables, ChatHistory>::default(),
+            HookupComponentPlugin::<Sendables, ChatInput>::default(),
+            HookupEventPlugin::<Sendables, PlayerKicked>::default(),
+            HookupComponentPlugin::<Sendables, BlockEditAck>::default(),
         ))
         .init_state::<NetworkingState>()
-        .add_systems(Update, (check_steamworks_events, client_on_connect))
+        // Only the host simulates players, everyone else follows its
+        // snapshots.
+        .configure_sets(
+            FixedUpdate,
+            AuthoritativeMovementSystems
+                .run_if(in_state(NetworkingState::Host)),
+        )
+        .init_resource::<RemotePlayerNames>()
+        .init_resource::<KickedSessions>()
+        .init_resource::<UnsharedBlockEdits>()
+        .init_resource::<ReceivedChunkColumnEdits>()
+        .add_systems(Update, (client_on_connect, server_on_start))
+        .add_systems(
+            Update,
+            (
+                (
+                    validate_block_edit_requests,
+                    share_block_edit_acks,
+                    share_chunk_column_edits,
+                )
+                    .chain()
+                    .run_if(in_state(NetworkingState::Host)),
+                apply_chunk_column_edits.run_if(
+                    in_state(NetworkingState::Client).and(has_world_seed),
+                ),
+                remove_acknowledged_block_edits
+                    .run_if(in_state(NetworkingState::Client)),
+                (
+                    receive_chat_inputs,
+                    announce_joins_and_leaves,
+                    close_kicked_sessions,
+                )
+                    .run_if(in_state(NetworkingState::Host)),
+            ),
+        )
+        // Players rejected as they join still get components inserted by
+        // the sync systems during the update.
+        .add_systems(
+            PostUpdate,
+            remove_kicked_players.run_if(in_state(NetworkingState::Host)),
+        )
+        .add_observer(edit_block)
+        .add_observer(record_block_edit)
+        .add_observer(receive_chunk_column_edits)
+        .add_observer(send_chat_message)
+        .add_observer(kick_player)
+        .add_observer(kick_session)
+        .add_observer(leave_when_kicked)
         .add_observer(start_self_session)
         .add_observer(create_world)
         .add_observer(start_websocket_server)
-        .add_observer(start_steam_server)
         .add_observer(start_websocket_client);
     }
 }
 
-fn check_steamworks_events(
-    mut events: MessageReader<SteamworksEvent>,
-    mut commands: Commands,
-    mut next_state: ResMut<NextState<NetworkingState>>,
-    state: Res<State<NetworkingState>>,
-    client: Res<Client>,
-) {
-    for event in events.read() {
-        match event {
-            SteamworksEvent::CallbackResult(callback_result) => {
-                match callback_result {
-                    CallbackResult::GameLobbyJoinRequested(a) => {
-                        if *state.get() != NetworkingState::Off {
-                            continue;
-                        }
-
-                        if let Err(err) = SteamworksClient::<Sendables>::create(
-                            &client,
-                            a.friend_steam_id,
-                            &mut commands,
-                        ) {
-                            error!(
-                                "Failed to create steamworks client: {}",
-                                err
-                            );
-                        } else {
-                            commands.trigger(StartWorldGen);
-                            next_state.set(NetworkingState::Client);
-                        }
-
-                        client.matchmaking().join_lobby(
-                            a.lobby_steam_id,
-                            |result| match result {
-                                Ok(_) => {
-                                    info!("Joined lobby!");
-                                }
-                                Err(_) => {
-                                    error!("Failed to join lobby!");
-                                }
-                            },
-                        );
-                        info!("Lobby join requested!");
-                    }
-                    _ => {}
-                }
-            }
-        }
-    }
-}
+/// The address a websocket client is connecting to, for error messages.
+#[derive(Component)]
+struct WebsocketClientAddress(String);
+
+/// The address a websocket server is binding, for error messages.
+#[derive(Component)]
+struct WebsocketServerAddress(String);
 
 fn start_self_session(_: On<StartSelfSession>, mut commands: Commands) {
     commands.trigger(StartWorldGen);
@@ -126,23 +145,30 @@ fn start_self_session(_: On<StartSelfSession>, mut commands: Commands) {
 fn start_websocket_client(
     event: On<StartWebsocketClient>,
     mut commands: Commands,
+    mut world_save_settings: ResMut<WorldSaveSettings>,
     _: NonSendMarker,
 ) {
-    commands.spawn(WebsocketClient::<Sendables>::new_with_host_and_port(
-        event.address.clone(),
-        1324,
+    let address = event.get_address();
+    info!("Connecting to {}", address);
+
+    // The host owns the world save, clients only mirror its edits.
+    world_save_settings.enabled = false;
+
+    commands.spawn((
+        WebsocketClient::<Sendables>::new(format!("ws://{}", address)),
+        WebsocketClientAddress(address),
     ));
 }
 
 fn client_on_connect(
     ws_state: Single<
-        (Entity, &WebsocketClientState),
+        (Entity, &WebsocketClientState, &WebsocketClientAddress),
         Changed<WebsocketClientState>,
     >,
     mut state: ResMut<NextState<NetworkingState>>,
     mut commands: Commands,
 ) {
-    let (entity, ws_state) = ws_state.into_inner();
+    let (entity, ws_state, address) = ws_state.into_inner();
 
     match ws_state {
         WebsocketClientState::Connected => {
@@ -150,52 +176,60 @@ fn client_on_connect(
             commands.trigger(StartWorldGen);
         }
         WebsocketClientState::Failed => {
-            warn!("Failed to connect!");
+            warn!("Failed to connect to {}!", address.0);
             commands.entity(entity).despawn();
+            commands.trigger(ConnectionFailed {
+                message: format!("Could not connect to {}", address.0),
+            });
         }
         _ => {}
     }
 }
 
 fn start_websocket_server(
-    _: On<StartWebsocketServer>,
+    event: On<StartWebsocketServer>,
     mut commands: Commands,
-    mut state: ResMut<NextState<NetworkingState>>,
     _: NonSendMarker,
 ) {
-    state.set(NetworkingState::Host);
-    commands.spawn(WebsocketServer::<Sendables>::new_with_port(1324));
-    commands.trigger(StartWorldGen);
+    let address = event.get_address();
+    info!("Starting websocket server on {}", address);
+
+    commands.spawn((
+        WebsocketServer::<Sendables>::new(address.clone()),
+        WebsocketServerAddress(address),
+    ));
 }
 
-fn start_steam_server(
-    _: On<StartSteamServer>,
-    client: Res<Client>,
-    mut commands: Commands,
+/// Only hosts once the server could bind its address. Otherwise the world
+/// created for it gets dropped again.
+fn server_on_start(
+    ws_state: Single<
+        (Entity, &WebsocketServerState, &WebsocketServerAddress),
+        Changed<WebsocketServerState>,
+    >,
+    worlds: Query<Entity, (With<GenerationOptions>, With<SyncEntityOwner>)>,
     mut state: ResMut<NextState<NetworkingState>>,
-    _: NonSendMarker,
+    mut commands: Commands,
 ) {
-    state.set(NetworkingState::Host);
-
-    client.matchmaking().create_lobby(
-        LobbyType::FriendsOnly,
-        12,
-        move |result| match result {
-            Ok(lobby_id) => {
-                info!("Successfully created lobby with id: {:?}", lobby_id);
-            }
-            Err(err) => {
-                error!("Failed creating lobby: {}", err);
-            }
-        },
-    );
+    let (entity, ws_state, address) = ws_state.into_inner();
 
-    commands.spawn(
-        SteamworksServer::<Sendables>::new(&client)
-            .expect("Couldn't create steamworks server"),
-    );
-
-    commands.trigger(StartWorldGen);
+    match ws_state {
+        WebsocketServerState::Ready => {
+            state.set(NetworkingState::Host);
+            commands.trigger(StartWorldGen);
+        }
+        WebsocketServerState::Failed => {
+            warn!("Failed to start websocket server on {}!", address.0);
+            commands.entity(entity).despawn();
+            for world in &worlds {
+                commands.entity(world).despawn();
+            }
+            commands.trigger(ConnectionFailed {
+                message: format!("Could not start a server on {}", address.0),
+            });
+        }
+        _ => {}
+    }
 }
 
 fn create_world(event: On<CreateWorld>, mut commands: Commands) {
@@ -203,5 +237,7 @@ fn create_world(event: On<CreateWorld>, mut commands: Commands) {
         SyncEntityOwner::new(),
         GenerationOptions::from_seed(event.seed),
         ShareComponent::<GenerationOptions>::default(),
+        ChatHistory::default(),
+        ShareComponent::<ChatHistory>::default(),
     ));
 }
@@ -1,8 +1,17 @@
-use bevy_hookup_core::utils::buffer_object::BufferObject;
 use bevy_hookup_macros::Sendable;
 
-use physics::physics_position::PhysicsPosition;
-use player::player_component::PlayerRotation;
+use crate::{
+    block_edits::{BlockEditAck, BlockEditRequests, ChunkColumnEdits},
+    chat::{ChatHistory, ChatInput, PlayerKicked},
+};
+use player::{
+    network_movement::{
+        player_input_buffer::PlayerInputBuffer,
+        player_snapshot::{AssignedPlayerId, PlayerSnapshot},
+    },
+    player_component::PlayerRotation,
+    player_profile::PlayerProfile,
+};
 use serde::{Deserialize, Serialize};
 use world_generation::generation_options::GenerationOptions;
 
@@ -13,5 +22,23 @@ pub enum Sendables {
     #[sendable]
     PlayerRotation(PlayerRotation),
     #[sendable]
-    BufferPhysicsPosition(BufferObject<PhysicsPosition>),
+    PlayerInputBuffer(PlayerInputBuffer),
+    #[sendable]
+    PlayerSnapshot(PlayerSnapshot),
+    #[sendable]
+    AssignedPlayerId(AssignedPlayerId),
+    #[sendable]
+    BlockEditRequests(BlockEditRequests),
+    #[sendable]
+    BlockEditAck(BlockEditAck),
+    #[sendable]
+    ChunkColumnEdits(ChunkColumnEdits),
+    #[sendable]
+    PlayerProfile(PlayerProfile),
+    #[sendable]
+    ChatHistory(ChatHistory),
+    #[sendable]
+    ChatInput(ChatInput),
+    #[sendable]
+    PlayerKicked(PlayerKicked),
 }
@@ -1,6 +1,66 @@
+use anyhow::anyhow;
 use bevy::prelude::*;
 
+use crate::start_websocket_server::DEFAULT_WEBSOCKET_PORT;
+
 #[derive(Event)]
 pub struct StartWebsocketClient {
-    pub address: String,
+    pub host: String,
+    pub port: u16,
+}
+
+impl StartWebsocketClient {
+    /// Parses `host` or `host:port`. IPv6 hosts need to be written in
+    /// brackets when a port is given, like `[::1]:1324`.
+    pub fn from_address(address: &str) -> Result<Self, anyhow::Error> {
+        let address = address.trim();
+
+        if address.is_empty() {
+            return Err(anyhow!("No server address given"));
+        }
+
+        let (host, port) = if let Some(bracketed) = address.strip_prefix('[') {
+            let (host, rest) = bracketed
+                .split_once(']')
+                .ok_or(anyhow!("Missing closing bracket in address"))?;
+
+            match rest.strip_prefix(':') {
+                Some(port) => (host, Some(port)),
+                None if rest.is_empty() => (host, None),
+                None => return Err(anyhow!("Invalid address {}", address)),
+            }
+        } else {
+            match address.split_once(':') {
+                // More than one colon is a plain IPv6 address without a port.
+                Some((host, port)) if !port.contains(':') => (host, Some(port)),
+                _ => (address, None),
+            }
+        };
+
+        let port = match port {
+            Some(port) => {
+                port.parse().map_err(|_| anyhow!("Invalid port {}", port))?
+            }
+            None => DEFAULT_WEBSOCKET_PORT,
+        };
+
+        Ok(Self {
+            host: host.into(),
+            port,
+        })
+    }
+
+    pub fn get_address(&self) -> String {
+        join_host_and_port(&self.host, self.port)
+    }
+}
+
+/// Joins a host and a port the way [`StartWebsocketClient::from_address`]
+/// parses them, so IPv6 hosts get their brackets back.
+pub fn join_host_and_port(host: &str, port: u16) -> String {
+    if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
 }
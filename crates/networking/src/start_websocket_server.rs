@@ -1,4 +1,27 @@
 use bevy::prelude::*;
 
+use crate::start_websocket_client::join_host_and_port;
+
+pub const DEFAULT_WEBSOCKET_PORT: u16 = 1324;
+pub const DEFAULT_BIND_ADDRESS: &'static str = "0.0.0.0";
+
 #[derive(Event)]
-pub struct StartWebsocketServer;
+pub struct StartWebsocketServer {
+    pub address: String,
+    pub port: u16,
+}
+
+impl StartWebsocketServer {
+    pub fn get_address(&self) -> String {
+        join_host_and_port(&self.address, self.port)
+    }
+}
+
+impl Default for StartWebsocketServer {
+    fn default() -> Self {
+        Self {
+            address: DEFAULT_BIND_ADDRESS.into(),
+            port: DEFAULT_WEBSOCKET_PORT,
+        }
+    }
+}
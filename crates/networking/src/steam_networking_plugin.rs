@@ -0,0 +1,109 @@
+use bevy::{ecs::system::NonSendMarker, prelude::*};
+use bevy_hookup_messenger_steamworks::{
+    steamworks_client::SteamworksClient, steamworks_server::SteamworksServer,
+    steamworks_server_plugin::SteamworksServerPlugin,
+    steamworks_session_handler_plugin::SteamworksSessionHandlerPlugin,
+};
+use bevy_steamworks::{Client, LobbyType, SteamworksEvent};
+use steamworks::CallbackResult;
+use world_generation::start_world_gen::StartWorldGen;
+
+use crate::{
+    networking_state::NetworkingState, sendables::Sendables,
+    start_steam_server::StartSteamServer,
+};
+
+/// Hosting and joining through Steam lobbies.
+/// Requires the `SteamworksPlugin` to be added before it.
+pub struct SteamNetworkingPlugin;
+
+impl Plugin for SteamNetworkingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            SteamworksServerPlugin::<Sendables>::default(),
+            SteamworksSessionHandlerPlugin::<Sendables>::default(),
+        ))
+        .add_systems(Update, check_steamworks_events)
+        .add_observer(start_steam_server);
+    }
+}
+
+fn check_steamworks_events(
+    mut events: MessageReader<SteamworksEvent>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<NetworkingState>>,
+    state: Res<State<NetworkingState>>,
+    client: Res<Client>,
+) {
+    for event in events.read() {
+        match event {
+            SteamworksEvent::CallbackResult(callback_result) => {
+                match callback_result {
+                    CallbackResult::GameLobbyJoinRequested(a) => {
+                        if *state.get() != NetworkingState::Off {
+                            continue;
+                        }
+
+                        if let Err(err) = SteamworksClient::<Sendables>::create(
+                            &client,
+                            a.friend_steam_id,
+                            &mut commands,
+                        ) {
+                            error!(
+                                "Failed to create steamworks client: {}",
+                                err
+                            );
+                        } else {
+                            commands.trigger(StartWorldGen);
+                            next_state.set(NetworkingState::Client);
+                        }
+
+                        client.matchmaking().join_lobby(
+                            a.lobby_steam_id,
+                            |result| match result {
+                                Ok(_) => {
+                                    info!("Joined lobby!");
+                                }
+                                Err(_) => {
+                                    error!("Failed to join lobby!");
+                                }
+                            },
+                        );
+                        info!("Lobby join requested!");
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn start_steam_server(
+    _: On<StartSteamServer>,
+    client: Res<Client>,
+    mut commands: Commands,
+    mut state: ResMut<NextState<NetworkingState>>,
+    _: NonSendMarker,
+) {
+    state.set(NetworkingState::Host);
+
+    client.matchmaking().create_lobby(
+        LobbyType::FriendsOnly,
+        12,
+        move |result| match result {
+            Ok(lobby_id) => {
+                info!("Successfully created lobby with id: {:?}", lobby_id);
+            }
+            Err(err) => {
+                error!("Failed creating lobby: {}", err);
+            }
+        },
+    );
+
+    commands.spawn(
+        SteamworksServer::<Sendables>::new(&client)
+            .expect("Couldn't create steamworks server"),
+    );
+
+    commands.trigger(StartWorldGen);
+}
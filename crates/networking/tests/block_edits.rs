@@ -0,0 +1,285 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{prelude::*, state::app::StatesPlugin, time::TimeUpdateStrategy};
+use bevy_hookup_core::{
+    from_session::FromSession,
+    hook_session::{SessionId, SessionMessenger},
+    session::{Session, SessionChannels},
+    session_action::SessionAction,
+};
+use networking::{
+    block_edits::{BlockEditAck, BlockEditRequest, BlockEditRequests},
+    create_world::CreateWorld,
+    networking_plugin::NetworkingPlugin,
+    networking_state::NetworkingState,
+    sendables::Sendables,
+};
+use player::network_movement::player_input_buffer::PlayerInputBuffer;
+use utils::file_utils::set_asset_root;
+use world_generation::{
+    chunk_generation::{
+        CHUNK_SIZE, block_registry::load_block_registry, block_type::BlockType,
+        chunk::Chunk, chunk_lod::ChunkLod, voxel_data::VoxelData,
+    },
+    chunk_loading::{
+        chunk_loader::ChunkLoader, chunk_tree::ChunkTreePos,
+        lod_position::LodPosition,
+    },
+    voxel_editing::{set_block::SetBlock, voxel_world::VoxelWorld},
+    world_save::voxel_delta_store::VoxelDeltaStore,
+};
+
+const ASSET_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+/// The loaded test chunk is stone up to this voxel height and air above.
+const GROUND_HEIGHT: i32 = 10;
+const PLAYER_POSITION: Vec3 = Vec3::new(5., 6., 5.);
+
+/// The blocks the host set, in order.
+#[derive(Resource, Default)]
+struct SetBlocks(Vec<(IVec3, BlockType)>);
+
+/// The blocks of every chunk column edit the host sent to a session.
+type SentChunkColumnEdits = Arc<Mutex<Vec<Vec<(IVec3, BlockType)>>>>;
+
+struct RecordingMessenger {
+    session_id: SessionId,
+    channels: SessionChannels<Sendables>,
+    sent_chunk_column_edits: SentChunkColumnEdits,
+}
+
+impl SessionMessenger<Sendables> for RecordingMessenger {
+    fn to_session(self) -> Session<Sendables> {
+        let channels = self.get_channels();
+        Session::new(Box::new(self), channels)
+    }
+
+    fn get_session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    fn get_channels(&self) -> SessionChannels<Sendables> {
+        self.channels.clone()
+    }
+
+    fn handle_actions(&mut self, actions: &Vec<SessionAction<Sendables>>) {
+        for action in actions {
+            if let SessionAction::SendEvent {
+                event_data: Sendables::ChunkColumnEdits(chunk_column_edits),
+            } = action
+            {
+                self.sent_chunk_column_edits
+                    .lock()
+                    .unwrap()
+                    .push(chunk_column_edits.blocks.clone());
+            }
+        }
+    }
+}
+
+/// A host without world generation, that has a single chunk loaded
+/// at the origin. Each update runs exactly one fixed step, which sends the
+/// messages of the sessions.
+fn create_host() -> App {
+    set_asset_root(ASSET_ROOT).unwrap();
+    load_block_registry().unwrap();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, NetworkingPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            Time::<Fixed>::default().timestep(),
+        ))
+        .insert_resource(VoxelDeltaStore::new(0))
+        .insert_resource(create_voxel_world())
+        .init_resource::<SetBlocks>()
+        .add_observer(
+            |set_block: On<SetBlock>, mut set_blocks: ResMut<SetBlocks>| {
+                set_blocks.0.push((set_block.voxel_pos, set_block.block));
+            },
+        );
+
+    app.world_mut()
+        .resource_mut::<NextState<NetworkingState>>()
+        .set(NetworkingState::Host);
+    app.world_mut().trigger(CreateWorld { seed: 0 });
+    app.update();
+
+    app
+}
+
+fn create_voxel_world() -> VoxelWorld {
+    let mut voxel_data = VoxelData::default();
+    for x in 0..CHUNK_SIZE as i32 + 2 {
+        for y in 0..=GROUND_HEIGHT {
+            for z in 0..CHUNK_SIZE as i32 + 2 {
+                voxel_data.set_block(IVec3::new(x, y, z), BlockType::STONE);
+            }
+        }
+    }
+
+    let chunk = Chunk {
+        tree_position: ChunkTreePos::new(IVec2::ZERO),
+        lod_position: LodPosition::new(ChunkLod::Full, 0, 0),
+        generate_above: false,
+        chunk_height: 0,
+        min_height: 0,
+    };
+
+    let mut voxel_world = VoxelWorld::default();
+    voxel_world.insert(Entity::PLACEHOLDER, &chunk, Arc::new(voxel_data));
+    voxel_world
+}
+
+fn spawn_remote_player(
+    app: &mut App,
+    position: Vec3,
+) -> (Entity, SentChunkColumnEdits) {
+    let session_id = SessionId::default();
+    let (sender, receiver) = crossbeam::channel::unbounded();
+    let sent_chunk_column_edits = SentChunkColumnEdits::default();
+
+    app.world_mut().spawn(
+        RecordingMessenger {
+            session_id,
+            channels: SessionChannels { sender, receiver },
+            sent_chunk_column_edits: sent_chunk_column_edits.clone(),
+        }
+        .to_session(),
+    );
+    let entity = app
+        .world_mut()
+        .spawn((
+            FromSession { session_id },
+            PlayerInputBuffer::new(position),
+            ChunkLoader {
+                load_range: 2,
+                unload_range: 3,
+                ..Default::default()
+            },
+            Transform::from_translation(position),
+        ))
+        .id();
+
+    (entity, sent_chunk_column_edits)
+}
+
+/// The host has no world generation, so the delta store gets the block
+/// by hand.
+fn set_block(app: &mut App, voxel_pos: IVec3, block: BlockType) {
+    app.world_mut()
+        .resource_mut::<VoxelDeltaStore>()
+        .set_block(voxel_pos, block);
+    app.world_mut().trigger(SetBlock { voxel_pos, block });
+}
+
+/// Messages get queued on the sessions during the update and only go out in
+/// the fixed step of the next one.
+fn update_and_send(app: &mut App) {
+    app.update();
+    app.update();
+}
+
+fn take_sent(
+    sent_chunk_column_edits: &SentChunkColumnEdits,
+) -> Vec<Vec<(IVec3, BlockType)>> {
+    std::mem::take(&mut sent_chunk_column_edits.lock().unwrap())
+}
+
+fn request_block_edits(
+    app: &mut App,
+    player: Entity,
+    edits: &[(IVec3, BlockType)],
+) {
+    let requests = edits
+        .iter()
+        .enumerate()
+        .map(|(sequence, (voxel_pos, block))| BlockEditRequest {
+            sequence: sequence as u64,
+            voxel_pos: *voxel_pos,
+            block: *block,
+        })
+        .collect::<Vec<_>>();
+
+    app.world_mut()
+        .entity_mut(player)
+        .insert(BlockEditRequests {
+            next_sequence: requests.len() as u64,
+            requests,
+        });
+}
+
+#[test]
+fn host_validates_block_edits() {
+    let mut app = create_host();
+    let (player, _) = spawn_remote_player(&mut app, PLAYER_POSITION);
+
+    let ground = IVec3::new(10, GROUND_HEIGHT, 10);
+    let air = IVec3::new(12, GROUND_HEIGHT + 5, 10);
+    request_block_edits(
+        &mut app,
+        player,
+        &[
+            // Too far away.
+            (IVec3::new(200, GROUND_HEIGHT, 10), BlockType::AIR),
+            // Not placeable.
+            (air, BlockType::WATER),
+            // In the chunk column next to the loaded one.
+            (IVec3::new(70, GROUND_HEIGHT, 10), BlockType::AIR),
+            // Occupied.
+            (ground, BlockType::STONE),
+            (ground, BlockType::AIR),
+            // Already broken by the previous request.
+            (ground, BlockType::AIR),
+            (air, BlockType::STONE),
+        ],
+    );
+    app.update();
+
+    assert_eq!(
+        app.world().resource::<SetBlocks>().0,
+        vec![(ground, BlockType::AIR), (air, BlockType::STONE)]
+    );
+
+    // Rejected requests get acknowledged as well.
+    let block_edit_ack = app
+        .world_mut()
+        .query::<&BlockEditAck>()
+        .single(app.world())
+        .unwrap();
+    assert_eq!(block_edit_ack.sequence, 6);
+}
+
+#[test]
+fn host_sends_chunk_column_edits_to_players_loading_them() {
+    let mut app = create_host();
+    let far_away = ChunkTreePos::new(IVec2::new(10, 0)).to_global_pos();
+    let (_, near_edits) = spawn_remote_player(&mut app, PLAYER_POSITION);
+    let (far_player, far_edits) =
+        spawn_remote_player(&mut app, far_away.extend(6.).xzy());
+
+    let first = IVec3::new(11, GROUND_HEIGHT, 10);
+    let second = IVec3::new(10, GROUND_HEIGHT, 10);
+    set_block(&mut app, first, BlockType::AIR);
+    update_and_send(&mut app);
+
+    assert_eq!(take_sent(&near_edits), vec![vec![(first, BlockType::AIR)]]);
+    assert!(take_sent(&far_edits).is_empty());
+
+    // Later edits only send the new blocks.
+    set_block(&mut app, second, BlockType::AIR);
+    update_and_send(&mut app);
+
+    assert_eq!(take_sent(&near_edits), vec![vec![(second, BlockType::AIR)]]);
+    assert!(take_sent(&far_edits).is_empty());
+
+    // Walking up to the column sends all of its edits.
+    app.world_mut()
+        .entity_mut(far_player)
+        .insert(Transform::from_translation(PLAYER_POSITION));
+    update_and_send(&mut app);
+
+    assert!(take_sent(&near_edits).is_empty());
+    assert_eq!(
+        take_sent(&far_edits),
+        vec![vec![(second, BlockType::AIR), (first, BlockType::AIR)]]
+    );
+}
@@ -0,0 +1,20 @@
+use networking::start_websocket_client::StartWebsocketClient;
+
+fn get_client_address(address: &str) -> String {
+    StartWebsocketClient::from_address(address)
+        .unwrap()
+        .get_address()
+}
+
+#[test]
+fn addresses_keep_their_port() {
+    assert_eq!(get_client_address("localhost:4000"), "localhost:4000");
+    assert_eq!(get_client_address("127.0.0.1"), "127.0.0.1:1324");
+}
+
+#[test]
+fn ipv6_addresses_keep_their_brackets() {
+    assert_eq!(get_client_address("[::1]:4000"), "[::1]:4000");
+    assert_eq!(get_client_address("[::1]"), "[::1]:1324");
+    assert_eq!(get_client_address("::1"), "[::1]:1324");
+}
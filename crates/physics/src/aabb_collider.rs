@@ -1,9 +1,13 @@
 use bevy::prelude::*;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
-use crate::{collider::Collider, collider_trait::ColliderTrait};
+use crate::{
+    collider::Collider,
+    collider_trait::{ColliderTrait, SweepHit},
+};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AabbCollider {
     pub size: Vec3,
     pub offset: Vec3,
@@ -157,6 +161,56 @@ impl AabbCollider {
         end_position
     }
 
+    /// Moves the box along `motion` and returns when it first touches the
+    /// other box, as a fraction of the motion. Boxes that already overlap
+    /// don't count as a hit, they get pushed apart instead.
+    pub fn sweep_aabb(
+        &self,
+        self_position: Vec3,
+        motion: Vec3,
+        other_collider: &AabbCollider,
+        other_position: Vec3,
+    ) -> Option<SweepHit> {
+        let relative_position = (self_position + self.offset)
+            - (other_position + other_collider.offset);
+        let half_size = (self.size + other_collider.size) / 2.;
+
+        let mut entry = Vec3::NEG_INFINITY;
+        let mut exit = Vec3::INFINITY;
+
+        for axis in 0..3 {
+            if motion[axis] == 0. {
+                if relative_position[axis].abs() >= half_size[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let first =
+                (-half_size[axis] - relative_position[axis]) / motion[axis];
+            let second =
+                (half_size[axis] - relative_position[axis]) / motion[axis];
+            entry[axis] = first.min(second);
+            exit[axis] = first.max(second);
+        }
+
+        let entry_time = entry.max_element();
+        let exit_time = exit.min_element();
+
+        if !(0. ..=1.).contains(&entry_time) || entry_time >= exit_time {
+            return None;
+        }
+
+        let axis = entry.max_position();
+        let mut normal = IVec3::ZERO;
+        normal[axis] = -motion[axis].signum() as i32;
+
+        Some(SweepHit {
+            time: entry_time,
+            normal,
+        })
+    }
+
     pub fn distance_squared(
         &self,
         self_position: Vec3,
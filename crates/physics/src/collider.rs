@@ -1,15 +1,17 @@
 use bevy::prelude::*;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     aabb_collider::AabbCollider, collider_trait::ColliderTrait,
-    compund_collider::CompoundCollider,
+    compund_collider::CompoundCollider, voxel_grid_collider::VoxelGridCollider,
 };
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub enum Collider {
     Aabb(AabbCollider),
     Compound(CompoundCollider),
+    VoxelGrid(VoxelGridCollider),
 }
 
 impl Collider {
@@ -28,6 +30,37 @@ impl Collider {
                 .collect_vec(),
         ))
     }
+
+    /// A grid of `size` voxels, starting at `offset`. Returns [`None`] if no
+    /// voxel is solid.
+    pub fn voxel_grid(
+        size: UVec3,
+        voxel_size: f32,
+        offset: Vec3,
+        is_solid: impl Fn(UVec3) -> bool,
+    ) -> Option<Self> {
+        let voxel_grid =
+            VoxelGridCollider::new(size, voxel_size, offset, is_solid);
+        (!voxel_grid.is_empty()).then_some(Self::VoxelGrid(voxel_grid))
+    }
+
+    /// Returns the lower and upper corner of the box around the collider.
+    pub fn get_bounds(&self, position: Vec3) -> (Vec3, Vec3) {
+        if let Collider::VoxelGrid(voxel_grid) = self {
+            return voxel_grid.get_bounds(position);
+        }
+
+        self.get_aabbs().iter().fold(
+            (Vec3::INFINITY, Vec3::NEG_INFINITY),
+            |(min, max), aabb| {
+                let center = position + aabb.offset;
+                (
+                    min.min(center - aabb.size / 2.),
+                    max.max(center + aabb.size / 2.),
+                )
+            },
+        )
+    }
 }
 
 impl ColliderTrait for Collider {
@@ -49,6 +82,11 @@ impl ColliderTrait for Collider {
                     other_collider,
                     other_position,
                 ),
+            Collider::VoxelGrid(voxel_grid) => voxel_grid.is_colliding_with(
+                self_position,
+                other_collider,
+                other_position,
+            ),
         }
     }
 
@@ -79,6 +117,14 @@ impl ColliderTrait for Collider {
                     step_height,
                     touching_sides,
                 ),
+            Collider::VoxelGrid(voxel_grid) => voxel_grid.restrict_movement(
+                end_position,
+                other_collider,
+                other_position,
+                other_colliders,
+                step_height,
+                touching_sides,
+            ),
         }
     }
 
@@ -88,6 +134,7 @@ impl ColliderTrait for Collider {
             Collider::Compound(compound_collider) => {
                 compound_collider.get_aabbs()
             }
+            Collider::VoxelGrid(voxel_grid) => voxel_grid.get_aabbs(),
         }
     }
 }
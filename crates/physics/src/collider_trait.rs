@@ -2,6 +2,15 @@ use bevy::math::{IVec3, Vec3};
 
 use crate::{aabb_collider::AabbCollider, collider::Collider};
 
+/// Where a moving collider first touches another one.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SweepHit {
+    /// The fraction of the motion done before touching.
+    pub time: f32,
+    /// The face of the other collider that got hit, pointing away from it.
+    pub normal: IVec3,
+}
+
 pub trait ColliderTrait {
     fn is_colliding_with(
         &self,
@@ -21,4 +30,30 @@ pub trait ColliderTrait {
     ) -> Vec3;
 
     fn get_aabbs<'a>(&'a self) -> Vec<&'a AabbCollider>;
+
+    /// Returns the earliest hit of any of the boxes of the collider moving
+    /// along `motion` with the boxes of the other collider.
+    fn sweep(
+        &self,
+        self_position: Vec3,
+        motion: Vec3,
+        other_collider: &impl ColliderTrait,
+        other_position: Vec3,
+    ) -> Option<SweepHit> {
+        let other_aabbs = other_collider.get_aabbs();
+
+        self.get_aabbs()
+            .iter()
+            .flat_map(|aabb| {
+                other_aabbs.iter().filter_map(move |other_aabb| {
+                    aabb.sweep_aabb(
+                        self_position,
+                        motion,
+                        other_aabb,
+                        other_position,
+                    )
+                })
+            })
+            .min_by(|hit1, hit2| hit1.time.total_cmp(&hit2.time))
+    }
 }
@@ -1,11 +1,13 @@
 use bevy::prelude::*;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     aabb_collider::AabbCollider, collider::Collider,
     collider_trait::ColliderTrait,
 };
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CompoundCollider {
     pub colliders: Vec<AabbCollider>,
     shell: AabbCollider,
@@ -0,0 +1,385 @@
+use std::collections::HashSet;
+
+use bevy::{ecs::query::QueryFilter, prelude::*};
+
+use crate::{
+    collider::Collider,
+    physics_collision::PhysicsCollision,
+    physics_friction::PhysicsFriction,
+    physics_impulse::ApplyImpulse,
+    physics_mass::PhysicsMass,
+    physics_object::DynamicPhysicsObject,
+    physics_position::PhysicsPosition,
+    physics_restitution::PhysicsRestitution,
+    physics_velocity::PhysicsVelocity,
+    static_colliders::StaticColliders,
+    update_physics::{
+        MIN_BOUNCE_SPEED, PhysicsProperties, move_dynamic_object,
+    },
+};
+
+/// How often overlapping pairs get pushed apart per step. Stacks need a few
+/// passes, so the support from below reaches the top.
+const SOLVER_ITERATIONS: usize = 4;
+/// Bodies closer than this count as touching, so bodies moving along
+/// together don't keep starting and ending their contact.
+const CONTACT_MARGIN: f32 = 0.001;
+
+/// The pairs of dynamic objects that touched in the last step, so collision
+/// events only get triggered when they start touching.
+#[derive(Resource, Default)]
+pub struct DynamicContacts(HashSet<(Entity, Entity)>);
+
+/// A dynamic object as the collisions between dynamic objects see it.
+#[derive(Clone)]
+pub struct DynamicBody {
+    pub entity: Entity,
+    pub collider: Collider,
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub touching_sides: IVec3,
+    pub inverse_mass: f32,
+    pub restitution: f32,
+    pub friction: f32,
+}
+
+/// Everything [`DynamicBody`] gets built from.
+pub type DynamicBodyData = (
+    Entity,
+    &'static Collider,
+    &'static PhysicsPosition,
+    &'static PhysicsVelocity,
+    &'static DynamicPhysicsObject,
+    PhysicsProperties,
+);
+
+type DynamicObjectData = (
+    Entity,
+    &'static Collider,
+    &'static mut PhysicsPosition,
+    &'static mut PhysicsVelocity,
+    &'static mut DynamicPhysicsObject,
+    PhysicsProperties,
+);
+
+/// The dynamic objects of the query, for movement simulated outside of
+/// [`resolve_dynamic_collisions`].
+pub fn get_dynamic_bodies<F: QueryFilter>(
+    dynamic_objects: &Query<DynamicBodyData, F>,
+) -> Vec<DynamicBody> {
+    dynamic_objects
+        .iter()
+        .map(
+            |(
+                entity,
+                collider,
+                position,
+                velocity,
+                dynamic_object,
+                (mass, _, restitution, friction),
+            )| {
+                DynamicBody::new(
+                    entity,
+                    collider,
+                    **position,
+                    **velocity,
+                    dynamic_object.touching_sides,
+                    (mass, restitution, friction),
+                )
+            },
+        )
+        .collect()
+}
+
+impl DynamicBody {
+    fn new(
+        entity: Entity,
+        collider: &Collider,
+        position: Vec3,
+        velocity: Vec3,
+        touching_sides: IVec3,
+        (mass, restitution, friction): (
+            Option<&PhysicsMass>,
+            Option<&PhysicsRestitution>,
+            Option<&PhysicsFriction>,
+        ),
+    ) -> Self {
+        Self {
+            entity,
+            collider: collider.clone(),
+            position,
+            velocity,
+            touching_sides,
+            inverse_mass: mass.copied().unwrap_or_default().get_inverse(),
+            restitution: restitution.map_or(0., |restitution| **restitution),
+            friction: friction.map_or(0., |friction| **friction),
+        }
+    }
+
+    fn get_bounds(&self) -> (Vec3, Vec3) {
+        self.collider.get_bounds(self.position)
+    }
+
+    /// Whether something static or resting on something static keeps the
+    /// body from moving along the axis in the direction of the sign.
+    fn is_blocked(&self, axis: usize, sign: i32) -> bool {
+        self.touching_sides[axis] == sign
+    }
+}
+
+/// Pushes overlapping dynamic objects apart by the boxes around them, heavier
+/// ones moving less, and exchanges their velocities along the contact.
+/// Objects resting on static colliders hold up the ones on top of them.
+pub fn resolve_dynamic_collisions(
+    static_colliders: StaticColliders,
+    mut dynamic_objects: Query<DynamicObjectData>,
+    mut contacts: ResMut<DynamicContacts>,
+    mut commands: Commands,
+) {
+    let mut bodies = dynamic_objects
+        .iter()
+        .map(
+            |(
+                entity,
+                collider,
+                position,
+                velocity,
+                dynamic_object,
+                (mass, _, restitution, friction),
+            )| {
+                DynamicBody::new(
+                    entity,
+                    collider,
+                    **position,
+                    **velocity,
+                    dynamic_object.touching_sides,
+                    (mass, restitution, friction),
+                )
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let mut new_contacts = HashSet::new();
+
+    for iteration in 0..SOLVER_ITERATIONS {
+        // Bottom up, so a body knows it's supported before the one on top of
+        // it gets resolved against it.
+        let mut order = (0..bodies.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            bodies[*a]
+                .get_bounds()
+                .0
+                .y
+                .total_cmp(&bodies[*b].get_bounds().0.y)
+        });
+
+        let mut resolved_any = false;
+
+        for (order_index, first) in order.iter().copied().enumerate() {
+            for second in order[order_index + 1..].iter().copied() {
+                // The rest starts above the top of the first body.
+                if bodies[second].get_bounds().0.y
+                    >= bodies[first].get_bounds().1.y + CONTACT_MARGIN
+                {
+                    break;
+                }
+
+                let Ok([a, b]) = bodies.get_disjoint_mut([first, second])
+                else {
+                    continue;
+                };
+                let Some(hit) = resolve_pair(a, b, &static_colliders) else {
+                    continue;
+                };
+                resolved_any = true;
+
+                let (a, b) = (&bodies[first], &bodies[second]);
+                let pair = if a.entity < b.entity {
+                    (a.entity, b.entity)
+                } else {
+                    (b.entity, a.entity)
+                };
+
+                if iteration == 0
+                    && new_contacts.insert(pair)
+                    && !contacts.0.contains(&pair)
+                {
+                    for (entity, other, normal) in [
+                        (a.entity, b.entity, -hit.normal),
+                        (b.entity, a.entity, hit.normal),
+                    ] {
+                        commands.trigger(PhysicsCollision {
+                            entity,
+                            other: Some(other),
+                            normal,
+                            speed: hit.speed,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !resolved_any {
+            break;
+        }
+    }
+
+    contacts.0 = new_contacts;
+
+    for body in bodies {
+        let Ok((_, _, mut position, mut velocity, mut dynamic_object, ..)) =
+            dynamic_objects.get_mut(body.entity)
+        else {
+            continue;
+        };
+
+        if **position != body.position {
+            **position = body.position;
+        }
+        if **velocity != body.velocity {
+            **velocity = body.velocity;
+        }
+        if dynamic_object.touching_sides != body.touching_sides {
+            dynamic_object.touching_sides = body.touching_sides;
+        }
+    }
+}
+
+struct PairHit {
+    /// Points from the first body to the second.
+    normal: Vec3,
+    speed: f32,
+}
+
+/// Pushes a body that moved on its own out of the other bodies, like
+/// [`resolve_dynamic_collisions`] would. The other bodies don't move, the
+/// impulses they got are returned instead.
+pub fn collide_with_bodies(
+    mover: &mut DynamicBody,
+    bodies: &[DynamicBody],
+    static_colliders: &StaticColliders,
+) -> Vec<ApplyImpulse> {
+    let mut impulses = Vec::new();
+
+    for body in bodies {
+        let mut other = body.clone();
+        if resolve_pair(mover, &mut other, static_colliders).is_none()
+            || body.inverse_mass == 0.
+        {
+            continue;
+        }
+
+        let impulse = (other.velocity - body.velocity) / body.inverse_mass;
+        if impulse != Vec3::ZERO {
+            impulses.push(ApplyImpulse {
+                entity: body.entity,
+                impulse,
+            });
+        }
+    }
+
+    impulses
+}
+
+/// Separates two bodies along the axis they overlap the least and returns the
+/// contact, or [`None`] if they don't touch.
+fn resolve_pair(
+    first: &mut DynamicBody,
+    second: &mut DynamicBody,
+    static_colliders: &StaticColliders,
+) -> Option<PairHit> {
+    let (first_min, first_max) = first.get_bounds();
+    let (second_min, second_max) = second.get_bounds();
+
+    let overlap = first_max.min(second_max) - first_min.max(second_min);
+    if overlap.min_element() <= -CONTACT_MARGIN {
+        return None;
+    }
+
+    let axis = overlap.min_position();
+    let sign =
+        if (second_min + second_max)[axis] > (first_min + first_max)[axis] {
+            1
+        } else {
+            -1
+        };
+    let mut normal = Vec3::ZERO;
+    normal[axis] = sign as f32;
+
+    // The first body gets pushed against the normal, the second along it.
+    let first_inverse_mass = if first.is_blocked(axis, -sign) {
+        0.
+    } else {
+        first.inverse_mass
+    };
+    let second_inverse_mass = if second.is_blocked(axis, sign) {
+        0.
+    } else {
+        second.inverse_mass
+    };
+    let inverse_mass_sum = first_inverse_mass + second_inverse_mass;
+    if inverse_mass_sum == 0. {
+        return None;
+    }
+
+    let penetration = overlap[axis];
+    for (body, inverse_mass, direction) in [
+        (&mut *first, first_inverse_mass, -normal),
+        (&mut *second, second_inverse_mass, normal),
+    ] {
+        if inverse_mass == 0. || penetration <= 0. {
+            continue;
+        }
+
+        let mut touching_sides = IVec3::ZERO;
+        move_dynamic_object(
+            &body.collider,
+            &mut body.position,
+            direction * penetration * inverse_mass / inverse_mass_sum,
+            0.,
+            &mut touching_sides,
+            static_colliders,
+        );
+    }
+
+    for (body, side) in [(&mut *first, sign), (&mut *second, -sign)] {
+        if body.touching_sides[axis] == 0 {
+            body.touching_sides[axis] = side;
+        }
+    }
+
+    let relative_velocity = second.velocity - first.velocity;
+    let normal_speed = relative_velocity.dot(normal);
+    if normal_speed >= 0. {
+        return Some(PairHit { normal, speed: 0. });
+    }
+
+    let restitution = if -normal_speed > MIN_BOUNCE_SPEED {
+        first.restitution.max(second.restitution)
+    } else {
+        0.
+    };
+    let impulse = -(1. + restitution) * normal_speed / inverse_mass_sum;
+    first.velocity -= normal * impulse * first_inverse_mass;
+    second.velocity += normal * impulse * second_inverse_mass;
+
+    // Friction works against the sliding that's left, but can't take away
+    // more than the pressing impulse allows.
+    let relative_velocity = second.velocity - first.velocity;
+    let sliding_velocity =
+        relative_velocity - normal * relative_velocity.dot(normal);
+    let sliding_speed = sliding_velocity.length();
+    if sliding_speed > 0. {
+        let friction = (first.friction * second.friction).sqrt();
+        let friction_impulse =
+            (sliding_speed / inverse_mass_sum).min(impulse * friction);
+        let direction = sliding_velocity / sliding_speed;
+        first.velocity += direction * friction_impulse * first_inverse_mass;
+        second.velocity -= direction * friction_impulse * second_inverse_mass;
+    }
+
+    Some(PairHit {
+        normal,
+        speed: -normal_speed,
+    })
+}
@@ -6,10 +6,19 @@ mod aabb_collider;
 pub mod collider;
 pub mod collider_trait;
 mod compund_collider;
+pub mod dynamic_collisions;
+pub mod physics_collision;
+pub mod physics_friction;
+pub mod physics_gravity;
+pub mod physics_impulse;
+pub mod physics_mass;
 pub mod physics_object;
 pub mod physics_plugin;
 pub mod physics_position;
 pub mod physics_previous_position;
+pub mod physics_restitution;
 pub mod physics_systems;
 pub mod physics_velocity;
-mod update_physics;
+pub mod static_colliders;
+pub mod update_physics;
+mod voxel_grid_collider;
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+/// Triggered when a dynamic object starts touching something, once for each
+/// of the two objects if both are dynamic.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PhysicsCollision {
+    pub entity: Entity,
+    /// The dynamic object it ran into, [`None`] for static colliders.
+    pub other: Option<Entity>,
+    /// Points away from what it ran into.
+    pub normal: Vec3,
+    /// How fast both were moving towards each other along the normal.
+    pub speed: f32,
+}
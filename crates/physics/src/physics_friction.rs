@@ -0,0 +1,6 @@
+use bevy::prelude::*;
+
+/// How strongly a dynamic object resists sliding along what it touches,
+/// relative to how hard it gets pressed against it.
+#[derive(Component, Clone, Copy, Default, Deref, DerefMut, Debug)]
+pub struct PhysicsFriction(pub f32);
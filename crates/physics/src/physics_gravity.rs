@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+/// Pulls a dynamic object down by this acceleration every physics step.
+/// Players handle falling in their movement, so they don't need it.
+#[derive(Component, Clone, Copy, Deref, DerefMut, Debug)]
+pub struct PhysicsGravity(pub f32);
+
+impl Default for PhysicsGravity {
+    fn default() -> Self {
+        Self(20.)
+    }
+}
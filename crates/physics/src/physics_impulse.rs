@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use crate::{
+    physics_mass::PhysicsMass, physics_object::DynamicPhysicsObject,
+    physics_position::PhysicsPosition, physics_velocity::PhysicsVelocity,
+};
+
+/// Pushes a dynamic object, like a knockback. Heavier objects get pushed
+/// less by the same impulse.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ApplyImpulse {
+    pub entity: Entity,
+    pub impulse: Vec3,
+}
+
+/// Pushes every dynamic object within the radius away from the position.
+/// The impulse gets weaker towards the edge of the radius.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Explosion {
+    pub position: Vec3,
+    pub radius: f32,
+    pub impulse: f32,
+}
+
+impl Explosion {
+    /// The impulse on an object of default mass at the position,
+    /// or [`None`] if it is out of reach.
+    pub fn get_impulse(&self, position: Vec3) -> Option<Vec3> {
+        let offset = position - self.position;
+        let distance = offset.length();
+        if distance >= self.radius {
+            return None;
+        }
+
+        let falloff = 1. - distance / self.radius;
+        Some(offset.normalize_or(Vec3::Y) * self.impulse * falloff)
+    }
+}
+
+pub fn apply_impulse(
+    apply_impulse: On<ApplyImpulse>,
+    mut dynamic_objects: Query<
+        (&mut PhysicsVelocity, Option<&PhysicsMass>),
+        With<DynamicPhysicsObject>,
+    >,
+) {
+    let Ok((mut velocity, mass)) =
+        dynamic_objects.get_mut(apply_impulse.entity)
+    else {
+        return;
+    };
+
+    **velocity +=
+        apply_impulse.impulse * mass.copied().unwrap_or_default().get_inverse();
+}
+
+pub fn apply_explosion(
+    explosion: On<Explosion>,
+    dynamic_objects: Query<
+        (&PhysicsPosition, &mut PhysicsVelocity, Option<&PhysicsMass>),
+        With<DynamicPhysicsObject>,
+    >,
+) {
+    for (position, mut velocity, mass) in dynamic_objects {
+        let Some(impulse) = explosion.get_impulse(**position) else {
+            continue;
+        };
+
+        **velocity += impulse * mass.copied().unwrap_or_default().get_inverse();
+    }
+}
@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+/// How hard a dynamic object is to push around. Objects without one weigh
+/// as much as [`PhysicsMass::default`].
+#[derive(Component, Clone, Copy, Deref, DerefMut, Debug)]
+pub struct PhysicsMass(pub f32);
+
+impl Default for PhysicsMass {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+impl PhysicsMass {
+    pub fn get_inverse(&self) -> f32 {
+        if self.0 > 0. { 1. / self.0 } else { 0. }
+    }
+}
@@ -14,4 +14,7 @@ pub struct StaticPhysicsObject;
 pub struct DynamicPhysicsObject {
     pub step_height: f32,
     pub touching_sides: IVec3,
+    /// Whether the object overlaps water. Physics doesn't know about blocks,
+    /// so the world sets this before every step.
+    pub in_water: bool,
 }
@@ -4,16 +4,33 @@ use bevy_hookup_core::utils::{
 };
 
 use crate::{
+    dynamic_collisions::{DynamicContacts, resolve_dynamic_collisions},
+    physics_impulse::{apply_explosion, apply_impulse},
     physics_position::PhysicsPosition,
     physics_previous_position::PhysicsPreviousPosition,
-    physics_systems::PhysicsSystems, update_physics::update_physics,
+    physics_systems::PhysicsSystems,
+    static_colliders::{
+        StaticColliderGrid, add_static_collider, remove_static_collider,
+    },
+    update_physics::update_physics,
 };
 
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(FixedUpdate, (update_physics.in_set(PhysicsSystems),))
+        app.init_resource::<StaticColliderGrid>()
+            .init_resource::<DynamicContacts>()
+            .add_observer(add_static_collider)
+            .add_observer(remove_static_collider)
+            .add_observer(apply_impulse)
+            .add_observer(apply_explosion)
+            .add_systems(
+                FixedUpdate,
+                (update_physics, resolve_dynamic_collisions)
+                    .chain()
+                    .in_set(PhysicsSystems),
+            )
             .add_systems(
                 Update,
                 (
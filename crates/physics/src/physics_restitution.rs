@@ -0,0 +1,6 @@
+use bevy::prelude::*;
+
+/// How much of its speed a dynamic object keeps when bouncing off something,
+/// from 0 for not bouncing at all to 1 for keeping all of it.
+#[derive(Component, Clone, Copy, Default, Deref, DerefMut, Debug)]
+pub struct PhysicsRestitution(pub f32);
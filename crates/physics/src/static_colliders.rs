@@ -0,0 +1,152 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{collider::Collider, physics_object::StaticPhysicsObject};
+
+/// The size of the cells static colliders get sorted into, about the size of
+/// a chunk.
+const CELL_SIZE: f32 = 32.;
+
+/// Sorts the static colliders into a grid of cells by their bounds, so
+/// movers only look at the ones around them. Static objects are expected to
+/// keep their position, moving one needs its collider to be inserted again.
+#[derive(Resource, Default)]
+pub struct StaticColliderGrid {
+    cells: HashMap<IVec3, Vec<Entity>>,
+    entity_cells: HashMap<Entity, Vec<IVec3>>,
+}
+
+impl StaticColliderGrid {
+    pub fn insert(&mut self, entity: Entity, min: Vec3, max: Vec3) {
+        self.remove(entity);
+
+        let (min_cell, max_cell) = get_cell_range(min, max);
+        let mut cells = Vec::new();
+
+        for z in min_cell.z..=max_cell.z {
+            for y in min_cell.y..=max_cell.y {
+                for x in min_cell.x..=max_cell.x {
+                    let cell = IVec3::new(x, y, z);
+                    self.cells.entry(cell).or_default().push(entity);
+                    cells.push(cell);
+                }
+            }
+        }
+
+        self.entity_cells.insert(entity, cells);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        let Some(cells) = self.entity_cells.remove(&entity) else {
+            return;
+        };
+
+        for cell in cells {
+            let Some(entities) = self.cells.get_mut(&cell) else {
+                continue;
+            };
+
+            entities.retain(|cell_entity| *cell_entity != entity);
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    /// Every entity in a cell the box between the two points touches, in a
+    /// stable order.
+    pub fn get_entities_in(&self, min: Vec3, max: Vec3) -> Vec<Entity> {
+        let (min_cell, max_cell) = get_cell_range(min, max);
+        let mut entities = Vec::new();
+
+        for z in min_cell.z..=max_cell.z {
+            for y in min_cell.y..=max_cell.y {
+                for x in min_cell.x..=max_cell.x {
+                    if let Some(cell_entities) =
+                        self.cells.get(&IVec3::new(x, y, z))
+                    {
+                        entities.extend(cell_entities);
+                    }
+                }
+            }
+        }
+
+        entities.sort();
+        entities.dedup();
+        entities
+    }
+}
+
+fn get_cell_range(min: Vec3, max: Vec3) -> (IVec3, IVec3) {
+    (
+        (min / CELL_SIZE).floor().as_ivec3(),
+        (max / CELL_SIZE).floor().as_ivec3(),
+    )
+}
+
+/// Looks up the static colliders around a mover through the
+/// [`StaticColliderGrid`].
+#[derive(SystemParam)]
+pub struct StaticColliders<'w, 's> {
+    grid: Res<'w, StaticColliderGrid>,
+    objects: Query<
+        'w,
+        's,
+        (&'static Collider, &'static Transform),
+        With<StaticPhysicsObject>,
+    >,
+}
+
+impl StaticColliders<'_, '_> {
+    /// The static colliders overlapping the box between the two points, with
+    /// their positions. Voxel grids are split into a box per solid voxel.
+    pub fn get_colliders_in(
+        &self,
+        min: Vec3,
+        max: Vec3,
+    ) -> Vec<(Cow<'_, Collider>, Vec3)> {
+        let mut colliders = Vec::new();
+
+        for entity in self.grid.get_entities_in(min, max) {
+            let Ok((collider, transform)) = self.objects.get(entity) else {
+                continue;
+            };
+
+            match collider {
+                Collider::VoxelGrid(voxel_grid) => colliders.extend(
+                    voxel_grid
+                        .get_voxel_colliders_in(transform.translation, min, max)
+                        .into_iter()
+                        .map(|(collider, position)| {
+                            (Cow::Owned(collider), position)
+                        }),
+                ),
+                _ => colliders
+                    .push((Cow::Borrowed(collider), transform.translation)),
+            }
+        }
+
+        colliders
+    }
+}
+
+pub fn add_static_collider(
+    insert: On<Insert, (Collider, StaticPhysicsObject)>,
+    objects: Query<(&Collider, &Transform), With<StaticPhysicsObject>>,
+    mut grid: ResMut<StaticColliderGrid>,
+) {
+    let Ok((collider, transform)) = objects.get(insert.entity) else {
+        return;
+    };
+
+    let (min, max) = collider.get_bounds(transform.translation);
+    grid.insert(insert.entity, min, max);
+}
+
+pub fn remove_static_collider(
+    remove: On<Remove, (Collider, StaticPhysicsObject)>,
+    mut grid: ResMut<StaticColliderGrid>,
+) {
+    grid.remove(remove.entity);
+}
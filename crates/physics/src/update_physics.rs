@@ -2,80 +2,365 @@ use bevy::prelude::*;
 use itertools::Itertools;
 
 use crate::{
-    collider::Collider,
-    collider_trait::ColliderTrait,
-    physics_object::{DynamicPhysicsObject, StaticPhysicsObject},
-    physics_position::PhysicsPosition,
+    collider::Collider, collider_trait::ColliderTrait,
+    physics_collision::PhysicsCollision, physics_friction::PhysicsFriction,
+    physics_gravity::PhysicsGravity, physics_mass::PhysicsMass,
+    physics_object::DynamicPhysicsObject, physics_position::PhysicsPosition,
     physics_previous_position::PhysicsPreviousPosition,
-    physics_velocity::PhysicsVelocity,
+    physics_restitution::PhysicsRestitution, physics_velocity::PhysicsVelocity,
+    static_colliders::StaticColliders,
 };
 
+/// How far around the path of a mover static colliders are looked up, so
+/// pushing it out of one doesn't move it into another unseen one.
+const BROAD_PHASE_MARGIN: f32 = 0.1;
+/// The longest distance a mover travels in one sub step, about half a block.
+const MAX_SUB_STEP_DISTANCE: f32 = 0.25;
+const MAX_SUB_STEPS: u32 = 32;
+/// The gap kept between a mover and the face it ran into, so touching faces
+/// don't count as overlapping.
+const CONTACT_SKIN: f32 = 0.001;
+/// How high above a ledge a mover gets put when stepping onto it.
+const STEP_UP_CLEARANCE: f32 = 0.01;
+/// Slower impacts don't bounce, so resting objects don't jitter.
+pub(crate) const MIN_BOUNCE_SPEED: f32 = 1.;
+
+/// The optional components that change how a dynamic object moves and
+/// responds to collisions.
+pub type PhysicsProperties = (
+    Option<&'static PhysicsMass>,
+    Option<&'static PhysicsGravity>,
+    Option<&'static PhysicsRestitution>,
+    Option<&'static PhysicsFriction>,
+);
+
 pub fn update_physics(
-    static_objects: Query<(&StaticPhysicsObject, &Collider, &Transform)>,
+    static_colliders: StaticColliders,
     dynamic_objects: Query<(
+        Entity,
         &Collider,
         &mut PhysicsPosition,
         &mut PhysicsVelocity,
         &mut PhysicsPreviousPosition,
         &mut DynamicPhysicsObject,
+        PhysicsProperties,
     )>,
     time: Res<Time>,
+    mut commands: Commands,
 ) {
-    let all_colliders = static_objects
-        .iter()
-        .map(|s| (s.1, s.2.translation))
-        .collect_vec();
-
     for (
+        entity,
         dynamic_collider,
         mut dynamic_position,
         mut dynamic_velocity,
         mut dynamic_previous_position,
         mut dynamic_object,
+        (_, gravity, restitution, friction),
     ) in dynamic_objects
     {
-        dynamic_object.touching_sides = IVec3::ZERO;
+        **dynamic_previous_position = **dynamic_position;
 
-        let velocity = **dynamic_velocity * time.delta_secs();
+        if let Some(gravity) = gravity {
+            dynamic_velocity.y -= **gravity * time.delta_secs();
+        }
 
-        let mut new_pos = **dynamic_position + velocity;
+        let previous_touching_sides = dynamic_object.touching_sides;
+        let previous_velocity = **dynamic_velocity;
+        let step_height = dynamic_object.step_height;
+        step_dynamic_object(
+            dynamic_collider,
+            &mut dynamic_position.0,
+            &mut dynamic_velocity,
+            step_height,
+            &mut dynamic_object.touching_sides,
+            &static_colliders,
+            time.delta_secs(),
+        );
 
-        let colliding_statics = static_objects.iter().filter(|so| {
-            dynamic_collider.is_colliding_with(new_pos, so.1, so.2.translation)
-        });
+        for axis in 0..3 {
+            let touching_side = dynamic_object.touching_sides[axis];
+            // Only count the sides it was moving towards.
+            if touching_side == 0
+                || previous_velocity[axis].signum() != touching_side as f32
+            {
+                continue;
+            }
 
-        for (_, static_collider, static_transform) in colliding_statics
-            .sorted_by(|a, b| {
-                a.2.translation
-                    .distance_squared(**dynamic_position)
-                    .total_cmp(
-                        &b.2.translation.distance_squared(**dynamic_position),
-                    )
-            })
-        {
-            new_pos = dynamic_collider.restrict_movement(
-                new_pos,
-                static_collider,
-                static_transform.translation,
-                &all_colliders,
-                dynamic_object.step_height,
-                &mut dynamic_object.touching_sides,
+            let impact_speed = previous_velocity[axis].abs();
+            apply_contact_response(
+                &mut dynamic_velocity,
+                axis,
+                previous_velocity[axis],
+                restitution.map_or(0., |restitution| **restitution),
+                friction.map_or(0., |friction| **friction),
             );
-        }
 
-        if dynamic_object.touching_sides.x != 0 {
-            dynamic_velocity.x = 0.;
+            if previous_touching_sides[axis] != touching_side {
+                let mut normal = Vec3::ZERO;
+                normal[axis] = -touching_side as f32;
+
+                commands.trigger(PhysicsCollision {
+                    entity,
+                    other: None,
+                    normal,
+                    speed: impact_speed,
+                });
+            }
         }
+    }
+}
+
+/// Bounces an object off a static collider it hit along the axis with the
+/// given velocity and slows down its sliding along it.
+fn apply_contact_response(
+    velocity: &mut Vec3,
+    axis: usize,
+    impact_velocity: f32,
+    restitution: f32,
+    friction: f32,
+) {
+    let impact_speed = impact_velocity.abs();
+    if impact_speed > MIN_BOUNCE_SPEED {
+        velocity[axis] = -impact_velocity * restitution;
+    }
 
-        if dynamic_object.touching_sides.y != 0 {
-            dynamic_velocity.y = 0.;
+    let mut sliding_velocity = *velocity;
+    sliding_velocity[axis] = 0.;
+    let sliding_speed = sliding_velocity.length();
+    if sliding_speed > 0. {
+        let slowed_speed = (sliding_speed - impact_speed * friction).max(0.);
+        let bounce = velocity[axis];
+        *velocity = sliding_velocity * (slowed_speed / sliding_speed);
+        velocity[axis] = bounce;
+    }
+}
+
+/// Moves a dynamic object along its velocity for one step, stopping it where
+/// it first touches a static collider and sliding along it. Fast objects move
+/// in several smaller steps, so they can step up onto ledges on their way.
+/// Only static colliders around its path are considered.
+///
+/// Everything that has to be simulated outside of [`update_physics`],
+/// like replaying player inputs, should go through this as well,
+/// so the result is the same.
+pub fn step_dynamic_object(
+    dynamic_collider: &Collider,
+    dynamic_position: &mut Vec3,
+    dynamic_velocity: &mut Vec3,
+    step_height: f32,
+    touching_sides: &mut IVec3,
+    static_colliders: &StaticColliders,
+    delta_secs: f32,
+) {
+    *touching_sides = IVec3::ZERO;
+
+    move_dynamic_object(
+        dynamic_collider,
+        dynamic_position,
+        *dynamic_velocity * delta_secs,
+        step_height,
+        touching_sides,
+        static_colliders,
+    );
+
+    if touching_sides.x != 0 {
+        dynamic_velocity.x = 0.;
+    }
+
+    if touching_sides.y != 0 {
+        dynamic_velocity.y = 0.;
+    }
+
+    if touching_sides.z != 0 {
+        dynamic_velocity.z = 0.;
+    }
+}
+
+/// Moves a dynamic object by `motion`, stopping and sliding at the static
+/// colliders in the way. Sides it runs into get added to `touching_sides`.
+pub(crate) fn move_dynamic_object(
+    dynamic_collider: &Collider,
+    dynamic_position: &mut Vec3,
+    motion: Vec3,
+    step_height: f32,
+    touching_sides: &mut IVec3,
+    static_colliders: &StaticColliders,
+) {
+    let (start_min, start_max) = dynamic_collider.get_bounds(*dynamic_position);
+    let (end_min, end_max) =
+        dynamic_collider.get_bounds(*dynamic_position + motion);
+    let nearby_colliders = static_colliders.get_colliders_in(
+        start_min.min(end_min) - BROAD_PHASE_MARGIN,
+        start_max.max(end_max) + Vec3::Y * step_height + BROAD_PHASE_MARGIN,
+    );
+    let static_colliders = nearby_colliders
+        .iter()
+        .map(|(collider, position)| (collider.as_ref(), *position))
+        .collect_vec();
+
+    let mut position = push_out_of_colliders(
+        dynamic_collider,
+        *dynamic_position,
+        step_height,
+        touching_sides,
+        &static_colliders,
+    );
+
+    let sub_steps = ((motion.length() / MAX_SUB_STEP_DISTANCE).ceil() as u32)
+        .clamp(1, MAX_SUB_STEPS);
+    let mut sub_step_motion = motion / sub_steps as f32;
+
+    for _ in 0..sub_steps {
+        position = sweep_and_slide(
+            dynamic_collider,
+            position,
+            &mut sub_step_motion,
+            step_height,
+            touching_sides,
+            &static_colliders,
+        );
+    }
+
+    *dynamic_position = position;
+}
+
+/// Pushes an object that already overlaps static colliders out of them,
+/// along the axis it overlaps them the least.
+fn push_out_of_colliders(
+    dynamic_collider: &Collider,
+    position: Vec3,
+    step_height: f32,
+    touching_sides: &mut IVec3,
+    static_colliders: &Vec<(&Collider, Vec3)>,
+) -> Vec3 {
+    let mut new_pos = position;
+
+    let colliding_statics =
+        static_colliders
+            .iter()
+            .filter(|(static_collider, static_position)| {
+                dynamic_collider.is_colliding_with(
+                    position,
+                    *static_collider,
+                    *static_position,
+                )
+            });
+
+    for (static_collider, static_position) in
+        colliding_statics.sorted_by(|a, b| {
+            a.1.distance_squared(position)
+                .total_cmp(&b.1.distance_squared(position))
+        })
+    {
+        new_pos = dynamic_collider.restrict_movement(
+            new_pos,
+            *static_collider,
+            *static_position,
+            static_colliders,
+            step_height,
+            touching_sides,
+        );
+    }
+
+    new_pos
+}
+
+/// Moves the object until it hits a static collider, then keeps going with
+/// the rest of the motion along the hit face. The blocked axis of the motion
+/// is cleared, so later sub steps don't run into the same face again.
+fn sweep_and_slide(
+    dynamic_collider: &Collider,
+    mut position: Vec3,
+    motion: &mut Vec3,
+    step_height: f32,
+    touching_sides: &mut IVec3,
+    static_colliders: &Vec<(&Collider, Vec3)>,
+) -> Vec3 {
+    let mut remaining = *motion;
+
+    // Every hit blocks an axis or steps up once, so this always ends.
+    for _ in 0..4 {
+        if remaining == Vec3::ZERO {
+            break;
         }
 
-        if dynamic_object.touching_sides.z != 0 {
-            dynamic_velocity.z = 0.;
+        let Some((hit, static_collider, static_position)) = static_colliders
+            .iter()
+            .filter_map(|(static_collider, static_position)| {
+                dynamic_collider
+                    .sweep(
+                        position,
+                        remaining,
+                        *static_collider,
+                        *static_position,
+                    )
+                    .map(|hit| (hit, *static_collider, *static_position))
+            })
+            .min_by(|(hit1, ..), (hit2, ..)| hit1.time.total_cmp(&hit2.time))
+        else {
+            return position + remaining;
+        };
+
+        let axis = hit.normal.abs().max_position();
+
+        position += remaining * hit.time;
+        position[axis] += hit.normal[axis] as f32 * CONTACT_SKIN;
+        remaining *= 1. - hit.time;
+
+        if axis != 1
+            && let Some(step_up_position) = try_step_up(
+                dynamic_collider,
+                position,
+                static_collider,
+                static_position,
+                step_height,
+                static_colliders,
+            )
+        {
+            position = step_up_position;
+            touching_sides.y = -1;
+            continue;
         }
 
-        **dynamic_previous_position = **dynamic_position;
-        **dynamic_position = new_pos;
+        touching_sides[axis] = -hit.normal[axis];
+        remaining[axis] = 0.;
+        motion[axis] = 0.;
     }
+
+    position
+}
+
+/// Returns the position on top of the hit collider, if it is low enough to
+/// step onto and nothing is in the way up there.
+fn try_step_up(
+    dynamic_collider: &Collider,
+    position: Vec3,
+    hit_collider: &Collider,
+    hit_position: Vec3,
+    step_height: f32,
+    static_colliders: &Vec<(&Collider, Vec3)>,
+) -> Option<Vec3> {
+    let (_, hit_max) = hit_collider.get_bounds(hit_position);
+    let (dynamic_min, _) = dynamic_collider.get_bounds(position);
+    let current_step_height = hit_max.y - dynamic_min.y;
+
+    if current_step_height <= 0. || current_step_height > step_height {
+        return None;
+    }
+
+    let step_up_position =
+        position + Vec3::Y * (current_step_height + STEP_UP_CLEARANCE);
+
+    let blocked =
+        static_colliders
+            .iter()
+            .any(|(static_collider, static_position)| {
+                dynamic_collider.is_colliding_with(
+                    step_up_position,
+                    *static_collider,
+                    *static_position,
+                )
+            });
+
+    (!blocked).then_some(step_up_position)
 }
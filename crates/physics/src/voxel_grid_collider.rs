@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    aabb_collider::AabbCollider, collider::Collider,
+    collider_trait::ColliderTrait,
+};
+
+/// A grid of equally sized voxels that are either solid or empty, like the
+/// blocks of a chunk. Movers only get tested against the voxels around them,
+/// so the size of the grid doesn't matter.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VoxelGridCollider {
+    size: UVec3,
+    voxel_size: f32,
+    /// The lower corner of the first voxel, relative to the collider.
+    offset: Vec3,
+    solid: Vec<u64>,
+}
+
+impl VoxelGridCollider {
+    pub fn new(
+        size: UVec3,
+        voxel_size: f32,
+        offset: Vec3,
+        is_solid: impl Fn(UVec3) -> bool,
+    ) -> Self {
+        let mut solid =
+            vec![0u64; (size.element_product() as usize).div_ceil(64)];
+
+        for z in 0..size.z {
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    let voxel = UVec3::new(x, y, z);
+                    if is_solid(voxel) {
+                        let index = Self::get_index(size, voxel);
+                        solid[index / 64] |= 1 << (index % 64);
+                    }
+                }
+            }
+        }
+
+        Self {
+            size,
+            voxel_size,
+            offset,
+            solid,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solid.iter().all(|bits| *bits == 0)
+    }
+
+    /// Voxels outside of the grid are never solid.
+    pub fn is_solid(&self, voxel: IVec3) -> bool {
+        if voxel.cmplt(IVec3::ZERO).any()
+            || voxel.as_uvec3().cmpge(self.size).any()
+        {
+            return false;
+        }
+
+        let index = Self::get_index(self.size, voxel.as_uvec3());
+        self.solid[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Returns the lower and upper corner of the whole grid.
+    pub fn get_bounds(&self, position: Vec3) -> (Vec3, Vec3) {
+        let min = position + self.offset;
+        (min, min + self.size.as_vec3() * self.voxel_size)
+    }
+
+    /// A box collider with its position for every solid voxel overlapping the
+    /// box between the two points.
+    pub fn get_voxel_colliders_in(
+        &self,
+        position: Vec3,
+        min: Vec3,
+        max: Vec3,
+    ) -> Vec<(Collider, Vec3)> {
+        let (min_voxel, max_voxel) = self.get_voxel_range(position, min, max);
+        let mut colliders = Vec::new();
+
+        for z in min_voxel.z..=max_voxel.z {
+            for y in min_voxel.y..=max_voxel.y {
+                for x in min_voxel.x..=max_voxel.x {
+                    let voxel = IVec3::new(x, y, z);
+                    if !self.is_solid(voxel) {
+                        continue;
+                    }
+
+                    colliders.push((
+                        Collider::aabb(
+                            Vec3::splat(self.voxel_size),
+                            Vec3::ZERO,
+                        ),
+                        position
+                            + self.offset
+                            + (voxel.as_vec3() + 0.5) * self.voxel_size,
+                    ));
+                }
+            }
+        }
+
+        colliders
+    }
+
+    fn get_voxel_range(
+        &self,
+        position: Vec3,
+        min: Vec3,
+        max: Vec3,
+    ) -> (IVec3, IVec3) {
+        let grid_start = position + self.offset;
+        let min_voxel = ((min - grid_start) / self.voxel_size).floor();
+        let max_voxel = ((max - grid_start) / self.voxel_size).ceil() - 1.;
+
+        (
+            min_voxel.max(Vec3::ZERO).as_ivec3(),
+            max_voxel.min(self.size.as_vec3() - 1.).as_ivec3(),
+        )
+    }
+
+    fn get_index(size: UVec3, voxel: UVec3) -> usize {
+        (voxel.x + voxel.y * size.x + voxel.z * size.x * size.y) as usize
+    }
+}
+
+impl ColliderTrait for VoxelGridCollider {
+    fn is_colliding_with(
+        &self,
+        self_position: Vec3,
+        other_collider: &impl ColliderTrait,
+        other_position: Vec3,
+    ) -> bool {
+        other_collider.get_aabbs().iter().any(|aabb| {
+            let center = other_position + aabb.offset;
+            let (min_voxel, max_voxel) = self.get_voxel_range(
+                self_position,
+                center - aabb.size / 2.,
+                center + aabb.size / 2.,
+            );
+
+            (min_voxel.z..=max_voxel.z).any(|z| {
+                (min_voxel.y..=max_voxel.y).any(|y| {
+                    (min_voxel.x..=max_voxel.x)
+                        .any(|x| self.is_solid(IVec3::new(x, y, z)))
+                })
+            })
+        })
+    }
+
+    /// Voxel grids are only used for static objects, so they never move.
+    fn restrict_movement(
+        &self,
+        end_position: Vec3,
+        _other_collider: &impl ColliderTrait,
+        _other_position: Vec3,
+        _other_colliders: &Vec<(&Collider, Vec3)>,
+        _step_height: f32,
+        _touching_sides: &mut IVec3,
+    ) -> Vec3 {
+        end_position
+    }
+
+    /// The voxels aren't stored as boxes, see
+    /// [`VoxelGridCollider::get_voxel_colliders_in`].
+    fn get_aabbs(&self) -> Vec<&AabbCollider> {
+        Vec::new()
+    }
+}
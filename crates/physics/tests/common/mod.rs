@@ -0,0 +1,38 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use physics::{
+    physics_plugin::PhysicsPlugin, physics_position::PhysicsPosition,
+    physics_velocity::PhysicsVelocity,
+};
+
+/// Each update of an app runs exactly one fixed step.
+pub fn create_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, PhysicsPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            Time::<Fixed>::default().timestep(),
+        ));
+    // The first update only starts the clock.
+    app.update();
+
+    app
+}
+
+pub fn run_steps(app: &mut App, steps: usize) {
+    for _ in 0..steps {
+        app.update();
+    }
+}
+
+pub fn get_position(app: &App, entity: Entity) -> Vec3 {
+    **app
+        .world()
+        .get::<PhysicsPosition>(entity)
+        .expect("Object has a position")
+}
+
+pub fn get_velocity(app: &App, entity: Entity) -> Vec3 {
+    **app
+        .world()
+        .get::<PhysicsVelocity>(entity)
+        .expect("Object has a velocity")
+}
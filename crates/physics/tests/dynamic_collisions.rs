@@ -0,0 +1,225 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{get_position, get_velocity, run_steps};
+use physics::{
+    collider::Collider,
+    physics_collision::PhysicsCollision,
+    physics_gravity::PhysicsGravity,
+    physics_impulse::{ApplyImpulse, Explosion},
+    physics_mass::PhysicsMass,
+    physics_object::{DynamicPhysicsObject, StaticPhysicsObject},
+    physics_position::PhysicsPosition,
+    physics_restitution::PhysicsRestitution,
+    physics_velocity::PhysicsVelocity,
+};
+
+/// The collisions triggered so far.
+#[derive(Resource, Default)]
+struct Collisions(Vec<PhysicsCollision>);
+
+fn create_app() -> App {
+    let mut app = common::create_app();
+    app.init_resource::<Collisions>().add_observer(
+        |collision: On<PhysicsCollision>,
+         mut collisions: ResMut<Collisions>| {
+            collisions.0.push(*collision);
+        },
+    );
+
+    app
+}
+
+fn spawn_floor(app: &mut App) {
+    app.world_mut().spawn((
+        StaticPhysicsObject,
+        Collider::aabb(Vec3::new(100., 1., 100.), Vec3::ZERO),
+        Transform::from_translation(Vec3::NEG_Y * 0.5),
+    ));
+}
+
+/// A unit cube with the given velocity.
+fn spawn_box(app: &mut App, position: Vec3, velocity: Vec3) -> Entity {
+    let entity = app
+        .world_mut()
+        .spawn((
+            DynamicPhysicsObject::default(),
+            PhysicsPosition(position),
+            Collider::aabb(Vec3::ONE, Vec3::ZERO),
+        ))
+        .id();
+
+    **app
+        .world_mut()
+        .get_mut::<PhysicsVelocity>(entity)
+        .expect("Box has a velocity") = velocity;
+
+    entity
+}
+
+#[test]
+fn boxes_do_not_pass_through_each_other() {
+    let mut app = create_app();
+    let left = spawn_box(&mut app, Vec3::NEG_X * 2., Vec3::X * 10.);
+    let right = spawn_box(&mut app, Vec3::X * 2., Vec3::NEG_X * 10.);
+
+    run_steps(&mut app, 30);
+
+    let left_position = get_position(&app, left);
+    let right_position = get_position(&app, right);
+    assert!(
+        right_position.x - left_position.x >= 1. - 1e-4,
+        "Boxes overlap at {} and {}",
+        left_position.x,
+        right_position.x
+    );
+    assert!(get_velocity(&app, left).length() < 1e-4);
+    assert!(get_velocity(&app, right).length() < 1e-4);
+}
+
+#[test]
+fn heavy_box_pushes_light_box() {
+    let mut app = create_app();
+    let heavy = spawn_box(&mut app, Vec3::ZERO, Vec3::X * 6.);
+    app.world_mut().entity_mut(heavy).insert(PhysicsMass(5.));
+    let light = spawn_box(&mut app, Vec3::X * 2., Vec3::ZERO);
+
+    run_steps(&mut app, 30);
+
+    // Both move on together, with the momentum of the heavy box.
+    let expected_speed = 6. * 5. / 6.;
+    assert!((get_velocity(&app, heavy).x - expected_speed).abs() < 1e-3);
+    assert!((get_velocity(&app, light).x - expected_speed).abs() < 1e-3);
+    assert!(get_position(&app, light).x > 2.5);
+}
+
+#[test]
+fn stacked_boxes_rest_on_each_other() {
+    let mut app = create_app();
+    spawn_floor(&mut app);
+    let stack = (0..3)
+        .map(|height| {
+            let entity = spawn_box(
+                &mut app,
+                Vec3::Y * (0.6 + height as f32 * 1.2),
+                Vec3::ZERO,
+            );
+            app.world_mut()
+                .entity_mut(entity)
+                .insert(PhysicsGravity::default());
+            entity
+        })
+        .collect::<Vec<_>>();
+
+    run_steps(&mut app, 200);
+
+    for (height, entity) in stack.into_iter().enumerate() {
+        let position = get_position(&app, entity);
+        let expected_height = 0.5 + height as f32;
+        assert!(
+            (position.y - expected_height).abs() < 0.02,
+            "Box {} rests at {} instead of {}",
+            height,
+            position.y,
+            expected_height
+        );
+        assert!(position.xz().length() < 1e-4, "Box {} slid away", height);
+
+        let dynamic_object = app
+            .world()
+            .get::<DynamicPhysicsObject>(entity)
+            .expect("Box is dynamic");
+        assert_eq!(dynamic_object.touching_sides.y, -1, "Box {}", height);
+    }
+}
+
+#[test]
+fn bouncy_box_bounces_off_floor() {
+    let mut app = create_app();
+    spawn_floor(&mut app);
+    let bouncy = spawn_box(&mut app, Vec3::Y * 2., Vec3::NEG_Y * 10.);
+    app.world_mut()
+        .entity_mut(bouncy)
+        .insert(PhysicsRestitution(0.5));
+
+    run_steps(&mut app, 15);
+
+    let velocity = get_velocity(&app, bouncy);
+    assert!(
+        (velocity.y - 5.).abs() < 1e-3,
+        "Box bounced with {}",
+        velocity.y
+    );
+}
+
+#[test]
+fn collisions_get_triggered_once_per_contact() {
+    let mut app = create_app();
+    spawn_floor(&mut app);
+    let falling = spawn_box(&mut app, Vec3::new(-5., 2., 0.), Vec3::ZERO);
+    app.world_mut()
+        .entity_mut(falling)
+        .insert(PhysicsGravity::default());
+    let resting = spawn_box(&mut app, Vec3::new(3., 0.5, 0.), Vec3::ZERO);
+    let pushing = spawn_box(&mut app, Vec3::new(1., 0.5, 0.), Vec3::X * 5.);
+
+    run_steps(&mut app, 100);
+
+    let collisions = &app.world().resource::<Collisions>().0;
+
+    let floor_hits = collisions
+        .iter()
+        .filter(|collision| collision.entity == falling)
+        .collect::<Vec<_>>();
+    assert_eq!(floor_hits.len(), 1);
+    assert_eq!(floor_hits[0].other, None);
+    assert_eq!(floor_hits[0].normal, Vec3::Y);
+    assert!(floor_hits[0].speed > 5.);
+
+    let box_hits = collisions
+        .iter()
+        .filter(|collision| collision.entity == resting)
+        .collect::<Vec<_>>();
+    assert_eq!(box_hits.len(), 1);
+    assert_eq!(box_hits[0].other, Some(pushing));
+    assert_eq!(box_hits[0].normal, Vec3::X);
+    assert!((box_hits[0].speed - 5.).abs() < 1e-3);
+}
+
+#[test]
+fn impulses_push_by_mass() {
+    let mut app = create_app();
+    let light = spawn_box(&mut app, Vec3::ZERO, Vec3::ZERO);
+    let heavy = spawn_box(&mut app, Vec3::X * 3., Vec3::ZERO);
+    app.world_mut().entity_mut(heavy).insert(PhysicsMass(4.));
+
+    app.world_mut().trigger(ApplyImpulse {
+        entity: light,
+        impulse: Vec3::Y * 8.,
+    });
+    app.world_mut().trigger(ApplyImpulse {
+        entity: heavy,
+        impulse: Vec3::Y * 8.,
+    });
+
+    assert_eq!(get_velocity(&app, light), Vec3::Y * 8.);
+    assert_eq!(get_velocity(&app, heavy), Vec3::Y * 2.);
+}
+
+#[test]
+fn explosions_push_away_from_center() {
+    let mut app = create_app();
+    let close = spawn_box(&mut app, Vec3::X, Vec3::ZERO);
+    let far = spawn_box(&mut app, Vec3::NEG_X * 3., Vec3::ZERO);
+    let outside = spawn_box(&mut app, Vec3::Z * 10., Vec3::ZERO);
+
+    app.world_mut().trigger(Explosion {
+        position: Vec3::ZERO,
+        radius: 4.,
+        impulse: 8.,
+    });
+
+    assert_eq!(get_velocity(&app, close), Vec3::X * 6.);
+    assert_eq!(get_velocity(&app, far), Vec3::NEG_X * 2.);
+    assert_eq!(get_velocity(&app, outside), Vec3::ZERO);
+}
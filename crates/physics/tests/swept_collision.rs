@@ -0,0 +1,183 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{create_app, get_position, get_velocity, run_steps};
+use physics::{
+    collider::Collider,
+    collider_trait::ColliderTrait,
+    physics_object::{DynamicPhysicsObject, StaticPhysicsObject},
+    physics_position::PhysicsPosition,
+    physics_velocity::PhysicsVelocity,
+};
+
+const STEP_HEIGHT: f32 = 0.6;
+
+fn spawn_box(app: &mut App, size: Vec3, position: Vec3) {
+    app.world_mut().spawn((
+        StaticPhysicsObject,
+        Collider::aabb(size, Vec3::ZERO),
+        Transform::from_translation(position),
+    ));
+}
+
+/// A unit cube that moves with the given velocity.
+fn spawn_mover(app: &mut App, position: Vec3, velocity: Vec3) -> Entity {
+    let mover = app
+        .world_mut()
+        .spawn((
+            DynamicPhysicsObject {
+                step_height: STEP_HEIGHT,
+                ..Default::default()
+            },
+            PhysicsPosition(position),
+            Collider::aabb(Vec3::ONE, Vec3::ZERO),
+        ))
+        .id();
+
+    **app
+        .world_mut()
+        .get_mut::<PhysicsVelocity>(mover)
+        .expect("Mover has a velocity") = velocity;
+
+    mover
+}
+
+#[test]
+fn sweep_reports_time_and_face_of_impact() {
+    let mover = Collider::aabb(Vec3::ONE, Vec3::ZERO);
+    let wall = Collider::aabb(Vec3::ONE, Vec3::ZERO);
+
+    let hit = mover
+        .sweep(Vec3::ZERO, Vec3::X * 4., &wall, Vec3::X * 3.)
+        .expect("Mover hits the wall");
+    assert!((hit.time - 0.5).abs() < 1e-5, "Hit at {}", hit.time);
+    assert_eq!(hit.normal, IVec3::NEG_X);
+
+    let hit = mover
+        .sweep(
+            Vec3::ZERO,
+            Vec3::new(2., -2., 0.),
+            &wall,
+            Vec3::new(2., -2.5, 0.),
+        )
+        .expect("Mover hits the top of the box");
+    assert_eq!(hit.normal, IVec3::Y);
+
+    assert_eq!(mover.sweep(Vec3::ZERO, Vec3::X, &wall, Vec3::X * 3.), None);
+    assert_eq!(
+        mover.sweep(Vec3::ZERO, Vec3::Z * 4., &wall, Vec3::X * 3.),
+        None
+    );
+    assert_eq!(
+        mover.sweep(Vec3::ZERO, Vec3::X * 4., &wall, Vec3::X * 0.5),
+        None,
+        "Overlapping boxes get pushed apart instead"
+    );
+}
+
+#[test]
+fn fast_mover_does_not_pass_through_thin_wall() {
+    let mut app = create_app();
+    spawn_box(&mut app, Vec3::new(0.1, 10., 10.), Vec3::X * 5.);
+    let mover = spawn_mover(&mut app, Vec3::ZERO, Vec3::X * 500.);
+
+    run_steps(&mut app, 3);
+
+    let position = get_position(&app, mover);
+    assert!(position.x < 4.5, "Mover passed the wall at {}", position.x);
+    assert!(position.x > 4.4, "Mover stopped early at {}", position.x);
+    assert_eq!(get_velocity(&app, mover), Vec3::ZERO);
+}
+
+#[test]
+fn fast_fall_lands_on_thin_floor() {
+    let mut app = create_app();
+    spawn_box(&mut app, Vec3::new(10., 0.1, 10.), Vec3::ZERO);
+    let mover = spawn_mover(&mut app, Vec3::Y * 20., Vec3::NEG_Y * 2000.);
+
+    run_steps(&mut app, 3);
+
+    let position = get_position(&app, mover);
+    assert!(
+        (position.y - 0.55).abs() < 0.01,
+        "Mover ended up at {}",
+        position.y
+    );
+    assert_eq!(get_velocity(&app, mover), Vec3::ZERO);
+}
+
+#[test]
+fn diagonal_mover_stops_in_corner() {
+    let mut app = create_app();
+    spawn_box(&mut app, Vec3::new(1., 10., 10.), Vec3::X * 3.);
+    spawn_box(&mut app, Vec3::new(10., 10., 1.), Vec3::Z * 3.);
+    let mover = spawn_mover(&mut app, Vec3::ZERO, Vec3::new(100., 0., 100.));
+
+    run_steps(&mut app, 3);
+
+    let position = get_position(&app, mover);
+    assert!(
+        (position.xz() - Vec2::splat(2.)).abs().max_element() < 0.01,
+        "Mover ended up at {}",
+        position
+    );
+    assert_eq!(get_velocity(&app, mover), Vec3::ZERO);
+}
+
+#[test]
+fn mover_slides_along_wall() {
+    let mut app = create_app();
+    spawn_box(&mut app, Vec3::new(1., 10., 100.), Vec3::X * 2.);
+    let mover = spawn_mover(&mut app, Vec3::ZERO, Vec3::new(60., 0., 60.));
+
+    run_steps(&mut app, 3);
+
+    let slide_distance =
+        60. * 3. * Time::<Fixed>::default().timestep().as_secs_f32();
+    let position = get_position(&app, mover);
+    assert!(
+        position.x < 1. && position.x > 0.99,
+        "Mover at {}",
+        position.x
+    );
+    assert!(
+        (position.z - slide_distance).abs() < 0.01,
+        "Mover slid to {}",
+        position.z
+    );
+
+    assert_eq!(get_velocity(&app, mover), Vec3::Z * 60.);
+}
+
+#[test]
+fn mover_steps_onto_low_block() {
+    let mut app = create_app();
+    spawn_box(&mut app, Vec3::new(100., 1., 100.), Vec3::NEG_Y * 0.5);
+    spawn_box(&mut app, Vec3::new(1., 0.5, 10.), Vec3::new(3., 0.25, 0.));
+    let mover = spawn_mover(&mut app, Vec3::Y * 0.501, Vec3::X * 30.);
+
+    run_steps(&mut app, 8);
+
+    let position = get_position(&app, mover);
+    assert!(position.x > 3., "Mover got stuck at {}", position.x);
+    assert!(position.y > 1., "Mover didn't step up, at {}", position.y);
+}
+
+#[test]
+fn mover_is_blocked_by_tall_block() {
+    let mut app = create_app();
+    spawn_box(&mut app, Vec3::new(100., 1., 100.), Vec3::NEG_Y * 0.5);
+    spawn_box(&mut app, Vec3::new(1., 1., 10.), Vec3::new(3., 0.5, 0.));
+    let mover = spawn_mover(&mut app, Vec3::Y * 0.501, Vec3::X * 30.);
+
+    run_steps(&mut app, 8);
+
+    let position = get_position(&app, mover);
+    assert!(position.x < 2., "Mover passed the block at {}", position.x);
+    assert!(
+        position.y < 0.6,
+        "Mover climbed the block to {}",
+        position.y
+    );
+    assert_eq!(get_velocity(&app, mover), Vec3::ZERO);
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use physics::{
+    collider::Collider, collider_trait::ColliderTrait,
+    physics_position::PhysicsPosition,
+};
+use world_generation::{
+    chunk_generation::{
+        VOXEL_SIZE, block_registry::get_block_registry, block_type::BlockType,
+    },
+    voxel_editing::{
+        edit_block::EditBlock,
+        voxel_ray_cast::{VoxelRayCast, get_voxel_point},
+    },
+};
+
+use crate::{
+    camera::player_camera::PlayerCamera, player_component::Player,
+    player_inputs::PlayerInputs,
+};
+
+/// How far past the player the camera can reach blocks.
+const BLOCK_REACH: f32 = 5.;
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct SelectedBlock(pub BlockType);
+
+impl Default for SelectedBlock {
+    fn default() -> Self {
+        Self(
+            get_block_registry()
+                .get_placeable_blocks()
+                .first()
+                .copied()
+                .unwrap_or(BlockType::AIR),
+        )
+    }
+}
+
+pub(super) fn select_block(
+    player_inputs: Res<PlayerInputs>,
+    mut selected_block: ResMut<SelectedBlock>,
+) {
+    let Some(index) = player_inputs.select_block else {
+        return;
+    };
+
+    if let Some(block) = get_block_registry().get_placeable_blocks().get(index)
+    {
+        **selected_block = *block;
+    }
+}
+
+pub(super) fn interact_with_blocks(
+    mut commands: Commands,
+    player_inputs: Res<PlayerInputs>,
+    selected_block: Res<SelectedBlock>,
+    camera: Single<(&Transform, &PlayerCamera)>,
+    player: Single<(&PhysicsPosition, &Collider), With<Player>>,
+    voxel_ray_cast: VoxelRayCast,
+) {
+    if !player_inputs.break_block && !player_inputs.place_block {
+        return;
+    }
+
+    let (camera_transform, player_camera) = camera.into_inner();
+
+    let Some(hit) = voxel_ray_cast.cast_ray(
+        Ray3d::new(camera_transform.translation, camera_transform.forward()),
+        player_camera.distance + BLOCK_REACH,
+    ) else {
+        return;
+    };
+
+    if player_inputs.break_block {
+        commands.trigger(EditBlock {
+            voxel_pos: hit.voxel_pos,
+            block: BlockType::AIR,
+        });
+        return;
+    }
+
+    let voxel_pos = hit.voxel_pos + hit.face.to_direction();
+
+    let (player_position, player_collider) = player.into_inner();
+    let block_collider =
+        Collider::aabb(Vec3::splat(VOXEL_SIZE), Vec3::splat(VOXEL_SIZE / 2.));
+
+    if player_collider.is_colliding_with(
+        **player_position,
+        &block_collider,
+        get_voxel_point(voxel_pos),
+    ) {
+        return;
+    }
+
+    commands.trigger(EditBlock {
+        voxel_pos,
+        block: **selected_block,
+    });
+}
@@ -5,7 +5,7 @@ use bevy::{
     prelude::*,
 };
 use utils::math::lerp_decay;
-use world_generation::chunk_generation::terrain_mesh::TerrainMesh;
+use world_generation::voxel_editing::voxel_ray_cast::VoxelRayCast;
 
 use crate::camera::{
     player_camera::PlayerCamera, player_camera_lerp::PlayerCameraLerp,
@@ -29,8 +29,7 @@ impl Plugin for PlayerCameraPlugin {
 
 fn update_camera_transform(
     camera: Single<(&mut Transform, &PlayerCamera)>,
-    terrain_meshes: Query<(), With<TerrainMesh>>,
-    mut ray_cast: MeshRayCast,
+    voxel_ray_cast: VoxelRayCast,
 ) {
     let (mut camera_transform, camera_component) = camera.into_inner();
 
@@ -42,19 +41,12 @@ fn update_camera_transform(
     camera_position =
         Quat::from_rotation_y(camera_component.yaw) * camera_position;
 
-    let ray = ray_cast
-        .cast_ray(
-            Ray3d::new(target, Dir3::new(camera_position).unwrap()),
-            &MeshRayCastSettings::default()
-                .always_early_exit()
-                .with_visibility(RayCastVisibility::Visible)
-                .with_filter(&|entity| terrain_meshes.contains(entity)),
-        )
-        .first();
-
-    if let Some((_, ray)) = ray
-        && ray.distance < camera_component.distance + 1.
-    {
+    let ray = voxel_ray_cast.cast_ray(
+        Ray3d::new(target, Dir3::new(camera_position).unwrap()),
+        camera_component.distance + 1.,
+    );
+
+    if let Some(ray) = ray {
         let dot = camera_position.normalize().dot(ray.normal.normalize());
         let offset = (1. - dot) * 0.5;
         camera_position *=
@@ -1,6 +1,10 @@
+pub mod block_interaction;
 pub mod camera;
+mod nameplate;
+pub mod network_movement;
 pub mod player_component;
 pub mod player_inputs;
-mod player_movement;
+pub mod player_movement;
 pub mod player_plugin;
+pub mod player_profile;
 pub mod player_state;
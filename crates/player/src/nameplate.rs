@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+use crate::{
+    camera::player_camera::PlayerCamera,
+    player_component::{Player, PlayerBody},
+    player_profile::PlayerProfile,
+};
+
+/// How far above the center of a player its name is drawn.
+const NAMEPLATE_HEIGHT: f32 = 1.4;
+
+type OtherPlayerBodies = (With<PlayerBody>, Without<Player>);
+
+/// Draws the names of all other players above their bodies.
+pub(super) fn render_nameplates(
+    mut contexts: EguiContexts,
+    camera: Single<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    players: Query<(&GlobalTransform, &PlayerProfile), OtherPlayerBodies>,
+) -> Result {
+    let (camera, camera_transform) = camera.into_inner();
+    let ctx = contexts.ctx_mut()?;
+    let painter = ctx.layer_painter(egui::LayerId::background());
+
+    for (player_transform, player_profile) in &players {
+        let Ok(viewport_position) = camera.world_to_viewport(
+            camera_transform,
+            player_transform.translation() + Vec3::Y * NAMEPLATE_HEIGHT,
+        ) else {
+            continue;
+        };
+
+        painter.text(
+            egui::pos2(viewport_position.x, viewport_position.y),
+            egui::Align2::CENTER_BOTTOM,
+            &player_profile.name,
+            egui::FontId::proportional(16.),
+            player_profile.get_egui_color(),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,439 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_hookup_core::{
+    from_session::FromSession, session_filter::SessionFilter,
+    share_component::ShareComponent, sync_entity::SyncEntityOwner,
+};
+use physics::{
+    collider::Collider,
+    collider_trait::ColliderTrait,
+    dynamic_collisions::{DynamicBodyData, get_dynamic_bodies},
+    physics_impulse::{ApplyImpulse, Explosion},
+    physics_mass::PhysicsMass,
+    physics_object::{DynamicPhysicsObject, StaticPhysicsObject},
+    physics_position::PhysicsPosition,
+    physics_velocity::PhysicsVelocity,
+    static_colliders::StaticColliders,
+};
+use world_generation::{
+    chunk_generation::water::water_overlap::is_in_water,
+    chunk_loading::chunk_loader::ChunkLoader,
+    voxel_editing::voxel_ray_cast::VoxelRayCast,
+};
+
+use crate::{
+    network_movement::{
+        movement_prediction::PredictionHistory,
+        movement_state::MovementState,
+        player_input_buffer::PlayerInputBuffer,
+        player_snapshot::{AssignedPlayerId, PlayerSnapshot},
+    },
+    player_component::{
+        PLAYER_STEP_HEIGHT, Player, PlayerRotation, find_spawn_point,
+        get_player_collider,
+    },
+    player_profile::PlayerProfile,
+};
+
+/// How many inputs of one player the host applies per physics step at most,
+/// so a player that fell behind can catch up without stalling the host.
+const MAX_INPUTS_PER_STEP: usize = 4;
+/// How far from the center of the world a client may ask to spawn, while
+/// the host doesn't have the terrain there to find the spawn point itself.
+const MAX_SPAWN_OFFSET: f32 = 8.;
+
+/// The systems only the host runs. They simulate the players of the clients
+/// and share the state of every player.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AuthoritativeMovementSystems;
+
+/// The movement the host simulates for a player controlled by a client.
+#[derive(Component)]
+pub struct AuthoritativeMovement {
+    pub last_sequence: Option<u64>,
+    pub state: MovementState,
+    pub rotation: Quat,
+}
+
+/// The snapshot entities the host shares, by the player they belong to.
+#[derive(Default)]
+pub(crate) struct SharedSnapshots {
+    snapshots: HashMap<Entity, Entity>,
+}
+
+/// The host tells players apart by the entity it simulates them on,
+/// so clients can't pick the id of someone else.
+pub fn get_player_id(entity: Entity) -> u64 {
+    entity.to_bits()
+}
+
+/// Where the host starts simulating a new player. The spawn point of the
+/// host wins, the position the client asked for only gets used while the
+/// terrain there isn't loaded, if it is close to the center and not inside
+/// of anything.
+fn get_spawn_position(
+    requested_position: Vec3,
+    collider: &Collider,
+    static_colliders: &StaticColliders,
+    voxel_ray_cast: &VoxelRayCast,
+) -> Option<Vec3> {
+    if let Some(spawn_point) = find_spawn_point(voxel_ray_cast) {
+        return Some(spawn_point);
+    }
+
+    if !requested_position.is_finite()
+        || requested_position.xz().length() > MAX_SPAWN_OFFSET
+    {
+        return None;
+    }
+
+    let (min, max) = collider.get_bounds(requested_position);
+    let is_blocked = static_colliders.get_colliders_in(min, max).iter().any(
+        |(static_collider, static_position)| {
+            collider.is_colliding_with(
+                requested_position,
+                static_collider.as_ref(),
+                *static_position,
+            )
+        },
+    );
+
+    (!is_blocked).then_some(requested_position)
+}
+
+/// Players of clients the host doesn't simulate yet.
+type NewRemotePlayers = (With<FromSession>, Without<AuthoritativeMovement>);
+
+pub(crate) fn add_authoritative_movement(
+    mut commands: Commands,
+    new_players: Query<(Entity, &PlayerInputBuffer), NewRemotePlayers>,
+    static_colliders: StaticColliders,
+    voxel_ray_cast: VoxelRayCast,
+) {
+    for (entity, player_input_buffer) in &new_players {
+        let collider = get_player_collider();
+
+        // The host needs the terrain around remote players for their
+        // collisions, even if it's far away from its own player.
+        commands.entity(entity).insert_if_new((
+            Transform::default(),
+            ChunkLoader {
+                load_range: 2,
+                unload_range: 3,
+                ..Default::default()
+            },
+        ));
+
+        // Waits for the terrain at the spawn point otherwise.
+        let Some(spawn_position) = get_spawn_position(
+            player_input_buffer.spawn_position,
+            &collider,
+            &static_colliders,
+            &voxel_ray_cast,
+        ) else {
+            continue;
+        };
+
+        commands.entity(entity).insert((
+            AuthoritativeMovement {
+                last_sequence: None,
+                state: MovementState {
+                    position: spawn_position,
+                    ..Default::default()
+                },
+                rotation: Quat::IDENTITY,
+            },
+            Transform::from_translation(spawn_position),
+            collider,
+        ));
+    }
+}
+
+type SimulatedPlayer = (
+    Entity,
+    &'static PlayerInputBuffer,
+    &'static Collider,
+    &'static mut AuthoritativeMovement,
+    &'static mut Transform,
+);
+
+/// Applies the inputs clients sent for their players in order,
+/// running the same movement and physics they predicted with.
+/// They collide with the bodies of the other players and every other
+/// dynamic object, and push the ones that can move.
+pub(crate) fn simulate_remote_players(
+    mut commands: Commands,
+    players: Query<SimulatedPlayer, Without<StaticPhysicsObject>>,
+    snapshots: Query<(Entity, &PlayerSnapshot), Without<Player>>,
+    dynamic_objects: Query<DynamicBodyData>,
+    static_colliders: StaticColliders,
+    voxel_ray_cast: VoxelRayCast,
+    time: Res<Time>,
+) {
+    let dynamic_bodies = get_dynamic_bodies(&dynamic_objects);
+
+    for (
+        entity,
+        player_input_buffer,
+        collider,
+        mut authoritative_movement,
+        mut transform,
+    ) in players
+    {
+        // The body the host shows for the player itself.
+        let own_body = snapshots
+            .iter()
+            .find(|(_, snapshot)| snapshot.player_id == get_player_id(entity))
+            .map(|(snapshot_entity, _)| snapshot_entity);
+        let other_bodies = dynamic_bodies
+            .iter()
+            .filter(|body| Some(body.entity) != own_body)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let last_sequence = authoritative_movement.last_sequence;
+        let input_frames = player_input_buffer
+            .frames
+            .iter()
+            .filter(|input_frame| {
+                last_sequence
+                    .is_none_or(|sequence| input_frame.sequence > sequence)
+            })
+            .take(MAX_INPUTS_PER_STEP);
+
+        for input_frame in input_frames {
+            // Clients resend every input until it is confirmed, so inputs
+            // only go missing if a client had to drop them from its history.
+            if authoritative_movement
+                .last_sequence
+                .is_some_and(|sequence| input_frame.sequence != sequence + 1)
+            {
+                warn!(
+                    "Inputs of a player before {} got lost",
+                    input_frame.sequence
+                );
+            }
+
+            let state = &mut authoritative_movement.state;
+            state.in_water =
+                is_in_water(collider, state.position, &voxel_ray_cast);
+
+            let movement_step = state.step(
+                &input_frame.input,
+                collider,
+                PLAYER_STEP_HEIGHT,
+                &static_colliders,
+                &other_bodies,
+                time.delta_secs(),
+            );
+
+            if let Some(rotation) = movement_step.rotation {
+                authoritative_movement.rotation = rotation;
+            }
+            for impulse in movement_step.impulses {
+                commands.trigger(impulse);
+            }
+            authoritative_movement.last_sequence = Some(input_frame.sequence);
+        }
+
+        if transform.translation != authoritative_movement.state.position {
+            transform.translation = authoritative_movement.state.position;
+        }
+    }
+}
+
+type ChangedRemotePlayers =
+    Or<(Changed<AuthoritativeMovement>, Changed<PlayerProfile>)>;
+
+/// Shares the simulated state of every remote player with everyone. Its
+/// owner uses it to correct its prediction, so only the others get the
+/// rotation and profile to show its body.
+pub(crate) fn publish_player_snapshots(
+    mut commands: Commands,
+    players: Query<
+        (
+            Entity,
+            &AuthoritativeMovement,
+            &FromSession,
+            Option<&PlayerProfile>,
+        ),
+        ChangedRemotePlayers,
+    >,
+    mut removed_players: RemovedComponents<AuthoritativeMovement>,
+    mut shared_snapshots: Local<SharedSnapshots>,
+) {
+    for entity in removed_players.read() {
+        if let Some(snapshot_entity) =
+            shared_snapshots.snapshots.remove(&entity)
+        {
+            commands.entity(snapshot_entity).despawn();
+        }
+    }
+
+    for (entity, authoritative_movement, from_session, player_profile) in
+        &players
+    {
+        let Some(sequence) = authoritative_movement.last_sequence else {
+            continue;
+        };
+
+        let snapshot = PlayerSnapshot {
+            player_id: get_player_id(entity),
+            sequence,
+            state: authoritative_movement.state,
+        };
+        let player_rotation = PlayerRotation(authoritative_movement.rotation);
+        let others = SessionFilter::Blacklist(vec![from_session.session_id]);
+
+        let snapshot_entity = match shared_snapshots.snapshots.get(&entity) {
+            Some(snapshot_entity) => {
+                commands
+                    .entity(*snapshot_entity)
+                    .insert((snapshot, player_rotation));
+                *snapshot_entity
+            }
+            None => {
+                let snapshot_entity = commands
+                    .spawn((
+                        SyncEntityOwner::new(),
+                        snapshot,
+                        ShareComponent::<PlayerSnapshot>::default(),
+                        AssignedPlayerId(get_player_id(entity)),
+                        ShareComponent::<AssignedPlayerId>::default()
+                            .with_read_filter(SessionFilter::Whitelist(vec![
+                                from_session.session_id,
+                            ])),
+                        player_rotation,
+                        ShareComponent::<PlayerRotation>::default()
+                            .with_read_filter(others.clone()),
+                        ShareComponent::<PlayerProfile>::default()
+                            .with_read_filter(others),
+                        Name::new("PlayerSnapshot"),
+                    ))
+                    .id();
+                shared_snapshots.snapshots.insert(entity, snapshot_entity);
+                snapshot_entity
+            }
+        };
+
+        if let Some(player_profile) = player_profile {
+            commands
+                .entity(snapshot_entity)
+                .insert(player_profile.sanitized());
+        }
+    }
+}
+
+/// The host doesn't predict its own player, it shares its state
+/// with every client instead.
+pub(crate) fn share_host_player(
+    mut commands: Commands,
+    players: Query<Entity, (With<Player>, With<PredictionHistory>)>,
+) {
+    for entity in &players {
+        commands
+            .entity(entity)
+            .remove::<(
+                PredictionHistory,
+                PlayerInputBuffer,
+                ShareComponent<PlayerInputBuffer>,
+            )>()
+            .insert((
+                ShareComponent::<PlayerSnapshot>::default(),
+                ShareComponent::<PlayerRotation>::default(),
+            ));
+    }
+}
+
+/// The own player of the host, once [`share_host_player`] set it up.
+type SharedHostPlayer = (
+    With<ShareComponent<PlayerSnapshot>>,
+    Without<PredictionHistory>,
+);
+
+type HostPlayerState = (
+    Entity,
+    &'static Player,
+    &'static PhysicsPosition,
+    &'static PhysicsVelocity,
+    &'static DynamicPhysicsObject,
+    Option<&'static mut PlayerSnapshot>,
+);
+
+/// Only writes the snapshot when the player moved, since every change gets
+/// sent to all clients.
+pub(crate) fn publish_host_player_snapshot(
+    mut commands: Commands,
+    players: Query<HostPlayerState, SharedHostPlayer>,
+) {
+    for (
+        entity,
+        player,
+        physics_position,
+        physics_velocity,
+        physics_object,
+        player_snapshot,
+    ) in players
+    {
+        let snapshot = PlayerSnapshot {
+            player_id: get_player_id(entity),
+            sequence: 0,
+            state: MovementState {
+                position: **physics_position,
+                velocity: **physics_velocity,
+                touching_sides: physics_object.touching_sides,
+                fly: player.fly,
+                in_water: physics_object.in_water,
+            },
+        };
+
+        match player_snapshot {
+            Some(mut player_snapshot) => {
+                player_snapshot.set_if_neq(snapshot);
+            }
+            None => {
+                commands.entity(entity).insert(snapshot);
+            }
+        }
+    }
+}
+
+/// Knockback on a remote player has to change the state the host simulates
+/// it with. The body the host shows for it can be pushed as well.
+pub(crate) fn apply_impulse_to_remote_players(
+    apply_impulse: On<ApplyImpulse>,
+    snapshots: Query<&PlayerSnapshot, Without<Player>>,
+    mut players: Query<(Entity, &mut AuthoritativeMovement)>,
+) {
+    let player_id = match snapshots.get(apply_impulse.entity) {
+        Ok(snapshot) => snapshot.player_id,
+        Err(_) => get_player_id(apply_impulse.entity),
+    };
+
+    let Some((_, mut authoritative_movement)) = players
+        .iter_mut()
+        .find(|(entity, _)| get_player_id(*entity) == player_id)
+    else {
+        return;
+    };
+
+    authoritative_movement.state.velocity +=
+        apply_impulse.impulse * PhysicsMass::default().get_inverse();
+}
+
+pub(crate) fn apply_explosion_to_remote_players(
+    explosion: On<Explosion>,
+    players: Query<&mut AuthoritativeMovement>,
+) {
+    for mut authoritative_movement in players {
+        let Some(impulse) =
+            explosion.get_impulse(authoritative_movement.state.position)
+        else {
+            continue;
+        };
+
+        authoritative_movement.state.velocity +=
+            impulse * PhysicsMass::default().get_inverse();
+    }
+}
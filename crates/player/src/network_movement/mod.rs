@@ -0,0 +1,6 @@
+pub mod authoritative_movement;
+pub mod movement_prediction;
+pub mod movement_state;
+pub mod network_movement_plugin;
+pub mod player_input_buffer;
+pub mod player_snapshot;
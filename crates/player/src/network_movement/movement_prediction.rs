@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use physics::{
+    collider::Collider,
+    dynamic_collisions::{DynamicBodyData, get_dynamic_bodies},
+    physics_object::DynamicPhysicsObject,
+    physics_position::PhysicsPosition,
+    physics_velocity::PhysicsVelocity,
+    static_colliders::StaticColliders,
+};
+use world_generation::{
+    chunk_generation::water::water_overlap::is_in_water,
+    voxel_editing::voxel_ray_cast::VoxelRayCast,
+};
+
+use crate::{
+    network_movement::{
+        movement_state::MovementState,
+        player_input_buffer::{InputFrame, PlayerInputBuffer},
+        player_snapshot::{LocalPlayerId, PlayerSnapshot},
+    },
+    player_component::{Player, PlayerRotation},
+    player_movement::MovementInput,
+};
+
+/// How many unconfirmed inputs are kept for replaying and sent to the host.
+/// Older ones are dropped, if the host stops answering.
+const PREDICTION_HISTORY_LENGTH: usize = 256;
+
+struct PredictedFrame {
+    input_frame: InputFrame,
+    state: MovementState,
+}
+
+/// The inputs the local player predicted its movement with,
+/// and where each of them ended up.
+#[derive(Component, Default)]
+pub struct PredictionHistory {
+    next_sequence: u64,
+    confirmed_sequence: Option<u64>,
+    pending_input: Option<InputFrame>,
+    frames: VecDeque<PredictedFrame>,
+}
+
+impl PredictionHistory {
+    pub(crate) fn push_input(&mut self, input: MovementInput) {
+        self.pending_input = Some(InputFrame {
+            sequence: self.next_sequence,
+            input,
+        });
+        self.next_sequence += 1;
+    }
+
+    fn push_state(&mut self, state: MovementState) {
+        let Some(input_frame) = self.pending_input.take() else {
+            return;
+        };
+
+        self.frames.push_back(PredictedFrame { input_frame, state });
+
+        if self.frames.len() > PREDICTION_HISTORY_LENGTH {
+            self.frames.pop_front();
+        }
+    }
+
+    /// The inputs the host did not confirm yet.
+    pub fn get_unconfirmed_frames(&self) -> impl Iterator<Item = &InputFrame> {
+        self.frames.iter().map(|frame| &frame.input_frame)
+    }
+
+    pub fn get_confirmed_sequence(&self) -> Option<u64> {
+        self.confirmed_sequence
+    }
+}
+
+/// Stores the state every predicted input resulted in,
+/// and queues the unconfirmed inputs up for the host.
+pub(crate) fn record_predicted_state(
+    players: Query<(
+        &mut PredictionHistory,
+        &mut PlayerInputBuffer,
+        &Player,
+        &PhysicsPosition,
+        &PhysicsVelocity,
+        &DynamicPhysicsObject,
+    )>,
+) {
+    for (
+        mut prediction_history,
+        mut player_input_buffer,
+        player,
+        physics_position,
+        physics_velocity,
+        physics_object,
+    ) in players
+    {
+        if prediction_history.pending_input.is_none() {
+            continue;
+        }
+
+        prediction_history.push_state(MovementState {
+            position: **physics_position,
+            velocity: **physics_velocity,
+            touching_sides: physics_object.touching_sides,
+            fly: player.fly,
+            in_water: physics_object.in_water,
+        });
+
+        player_input_buffer.frames = prediction_history
+            .get_unconfirmed_frames()
+            .copied()
+            .collect();
+    }
+}
+
+/// The local player with everything its prediction gets replayed on.
+type PredictedPlayer = (
+    &'static mut PredictionHistory,
+    &'static Collider,
+    &'static mut Player,
+    &'static mut PhysicsPosition,
+    &'static mut PhysicsVelocity,
+    &'static mut DynamicPhysicsObject,
+    &'static mut PlayerRotation,
+);
+
+/// Compares the predicted state with the one the host confirmed.
+/// If they differ, the player is reset to the confirmed state
+/// and every input after it is simulated again.
+pub(crate) fn reconcile_local_player(
+    snapshots: Query<&PlayerSnapshot, Changed<PlayerSnapshot>>,
+    players: Query<PredictedPlayer>,
+    local_player_id: LocalPlayerId,
+    dynamic_objects: Query<DynamicBodyData, Without<Player>>,
+    static_colliders: StaticColliders,
+    voxel_ray_cast: VoxelRayCast,
+    time: Res<Time>,
+) {
+    for (
+        mut prediction_history,
+        collider,
+        mut player,
+        mut physics_position,
+        mut physics_velocity,
+        mut physics_object,
+        mut player_rotation,
+    ) in players
+    {
+        let Some(player_id) = local_player_id.get() else {
+            continue;
+        };
+
+        let Some(snapshot) = snapshots
+            .iter()
+            .filter(|snapshot| snapshot.player_id == player_id)
+            .max_by_key(|snapshot| snapshot.sequence)
+        else {
+            continue;
+        };
+
+        if prediction_history
+            .confirmed_sequence
+            .is_some_and(|sequence| sequence >= snapshot.sequence)
+        {
+            continue;
+        }
+
+        prediction_history.confirmed_sequence = Some(snapshot.sequence);
+
+        let predicted_state = prediction_history
+            .frames
+            .iter()
+            .find(|frame| frame.input_frame.sequence == snapshot.sequence)
+            .map(|frame| frame.state);
+
+        prediction_history
+            .frames
+            .retain(|frame| frame.input_frame.sequence > snapshot.sequence);
+
+        if predicted_state.is_some_and(|predicted_state| {
+            predicted_state.is_close_to(&snapshot.state)
+        }) {
+            continue;
+        }
+
+        debug!(
+            "Reconciling player at input {} ({} inputs to replay)",
+            snapshot.sequence,
+            prediction_history.frames.len()
+        );
+
+        let mut state = snapshot.state;
+        // The pushes the player gave others were already predicted.
+        let dynamic_bodies = get_dynamic_bodies(&dynamic_objects);
+
+        for frame in &mut prediction_history.frames {
+            state.in_water =
+                is_in_water(collider, state.position, &voxel_ray_cast);
+
+            if let Some(rotation) = state
+                .step(
+                    &frame.input_frame.input,
+                    collider,
+                    physics_object.step_height,
+                    &static_colliders,
+                    &dynamic_bodies,
+                    time.delta_secs(),
+                )
+                .rotation
+            {
+                player_rotation.0 = rotation;
+            }
+
+            frame.state = state;
+        }
+
+        **physics_position = state.position;
+        **physics_velocity = state.velocity;
+        physics_object.touching_sides = state.touching_sides;
+        physics_object.in_water = state.in_water;
+        player.fly = state.fly;
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use physics::{
+    collider::Collider,
+    dynamic_collisions::{DynamicBody, collide_with_bodies},
+    physics_impulse::ApplyImpulse,
+    physics_mass::PhysicsMass,
+    static_colliders::StaticColliders,
+    update_physics::step_dynamic_object,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::player_movement::{MovementInput, apply_movement};
+
+/// Predicted and authoritative positions further apart than this
+/// count as a misprediction.
+const POSITION_TOLERANCE: f32 = 0.01;
+
+/// Everything the movement of a player depends on between two physics steps.
+#[derive(Clone, Copy, Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct MovementState {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub touching_sides: IVec3,
+    pub fly: bool,
+    /// Gets set from the world before every step.
+    pub in_water: bool,
+}
+
+/// What one step of a player changed besides its own state.
+pub struct MovementStep {
+    /// The direction the player should face, if it moved.
+    pub rotation: Option<Quat>,
+    /// The pushes the player gave the dynamic objects it ran into.
+    pub impulses: Vec<ApplyImpulse>,
+}
+
+impl MovementState {
+    /// Runs the player movement and a physics step for one input,
+    /// the same way `movement`, `update_physics` and
+    /// `resolve_dynamic_collisions` would.
+    pub fn step(
+        &mut self,
+        movement_input: &MovementInput,
+        collider: &Collider,
+        step_height: f32,
+        static_colliders: &StaticColliders,
+        dynamic_bodies: &[DynamicBody],
+        delta_secs: f32,
+    ) -> MovementStep {
+        let rotation = apply_movement(
+            movement_input,
+            &mut self.fly,
+            &mut self.velocity,
+            &mut self.touching_sides,
+            self.in_water,
+            delta_secs,
+        );
+
+        step_dynamic_object(
+            collider,
+            &mut self.position,
+            &mut self.velocity,
+            step_height,
+            &mut self.touching_sides,
+            static_colliders,
+            delta_secs,
+        );
+
+        // Players have the default mass and no restitution or friction.
+        let mut body = DynamicBody {
+            entity: Entity::PLACEHOLDER,
+            collider: collider.clone(),
+            position: self.position,
+            velocity: self.velocity,
+            touching_sides: self.touching_sides,
+            inverse_mass: PhysicsMass::default().get_inverse(),
+            restitution: 0.,
+            friction: 0.,
+        };
+        let impulses =
+            collide_with_bodies(&mut body, dynamic_bodies, static_colliders);
+
+        self.position = body.position;
+        self.velocity = body.velocity;
+        self.touching_sides = body.touching_sides;
+
+        MovementStep { rotation, impulses }
+    }
+
+    pub fn is_close_to(&self, other: &Self) -> bool {
+        self.position.distance(other.position) <= POSITION_TOLERANCE
+            && self.fly == other.fly
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use physics::physics_systems::PhysicsSystems;
+use world_generation::chunk_generation::water::water_overlap::update_in_water;
+
+use crate::{
+    network_movement::{
+        authoritative_movement::{
+            AuthoritativeMovementSystems, add_authoritative_movement,
+            apply_explosion_to_remote_players, apply_impulse_to_remote_players,
+            publish_host_player_snapshot, publish_player_snapshots,
+            share_host_player, simulate_remote_players,
+        },
+        movement_prediction::{reconcile_local_player, record_predicted_state},
+        player_snapshot::follow_player_snapshots,
+    },
+    player_inputs::PlayerInputs,
+    player_movement::movement,
+};
+
+/// Predicts the movement of the local player from its inputs and corrects it
+/// with the snapshots of the host. As the host, it simulates the players of
+/// all clients from the inputs they send.
+///
+/// The host systems are in [`AuthoritativeMovementSystems`], which the app
+/// only runs while it is the host.
+///
+/// Doesn't need any rendering, so it also works in headless apps.
+pub struct NetworkMovementPlugin;
+
+impl Plugin for NetworkMovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerInputs>()
+            .add_observer(apply_impulse_to_remote_players)
+            .add_observer(apply_explosion_to_remote_players)
+            .add_systems(
+                FixedUpdate,
+                (reconcile_local_player, movement)
+                    .chain()
+                    .after(update_in_water)
+                    .before(PhysicsSystems),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    record_predicted_state,
+                    follow_player_snapshots,
+                    (
+                        share_host_player,
+                        add_authoritative_movement,
+                        simulate_remote_players,
+                        publish_player_snapshots,
+                        publish_host_player_snapshot,
+                    )
+                        .chain()
+                        .in_set(AuthoritativeMovementSystems),
+                )
+                    .chain()
+                    .after(PhysicsSystems),
+            );
+    }
+}
@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::player_movement::MovementInput;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct InputFrame {
+    pub sequence: u64,
+    pub input: MovementInput,
+}
+
+/// Every input of a player the host did not confirm yet.
+/// Every update repeats the older ones, so a lost update leaves no gaps.
+#[derive(Component, Clone, Serialize, Deserialize, Debug)]
+pub struct PlayerInputBuffer {
+    /// Where the client asks to spawn. The host only accepts it close to
+    /// the spawn point.
+    pub spawn_position: Vec3,
+    pub frames: Vec<InputFrame>,
+}
+
+impl PlayerInputBuffer {
+    pub fn new(spawn_position: Vec3) -> Self {
+        Self {
+            spawn_position,
+            frames: Vec::new(),
+        }
+    }
+}
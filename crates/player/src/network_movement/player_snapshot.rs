@@ -0,0 +1,89 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_hookup_core::from_session::FromSession;
+use physics::{
+    physics_mass::PhysicsMass, physics_object::DynamicPhysicsObject,
+    physics_position::PhysicsPosition,
+    physics_previous_position::PhysicsPreviousPosition,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    network_movement::movement_state::MovementState,
+    player_component::{Player, get_player_collider},
+};
+
+/// The movement state the host simulated for a player,
+/// after it applied every input up to `sequence`.
+/// The own player of the host has no inputs to confirm, so its sequence
+/// stays at 0.
+#[derive(Component, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct PlayerSnapshot {
+    pub player_id: u64,
+    pub sequence: u64,
+    pub state: MovementState,
+}
+
+/// The id the host assigned to the player of a client. Only shared with
+/// that client, so it can tell its own snapshots apart.
+#[derive(Component, Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct AssignedPlayerId(pub u64);
+
+/// The id the host assigned to the local player, once it arrived.
+/// The host doesn't get one, as it assigns them.
+#[derive(SystemParam)]
+pub struct LocalPlayerId<'w, 's> {
+    assigned_ids: Query<'w, 's, &'static AssignedPlayerId, With<FromSession>>,
+}
+
+impl LocalPlayerId<'_, '_> {
+    pub fn get(&self) -> Option<u64> {
+        self.assigned_ids
+            .single()
+            .ok()
+            .map(|assigned_player_id| assigned_player_id.0)
+    }
+}
+
+type FollowedSnapshot = (
+    Entity,
+    &'static PlayerSnapshot,
+    Option<&'static mut PhysicsPosition>,
+    Option<&'static mut PhysicsPreviousPosition>,
+);
+
+/// Moves the players of everyone else to where the host last saw them.
+/// They get interpolated between the last two snapshots like any other
+/// physics object. Their bodies block everything else, but only move
+/// with the snapshots.
+pub(crate) fn follow_player_snapshots(
+    mut commands: Commands,
+    snapshots: Query<FollowedSnapshot, Without<Player>>,
+    local_player_id: LocalPlayerId,
+) {
+    let local_player_id = local_player_id.get();
+
+    for (entity, snapshot, physics_position, previous_position) in snapshots {
+        if local_player_id == Some(snapshot.player_id) {
+            continue;
+        }
+
+        let position = snapshot.state.position;
+
+        match (physics_position, previous_position) {
+            (Some(mut physics_position), Some(mut previous_position)) => {
+                **previous_position = **physics_position;
+                **physics_position = position;
+            }
+            _ => {
+                commands.entity(entity).insert((
+                    DynamicPhysicsObject::default(),
+                    PhysicsMass(0.),
+                    get_player_collider(),
+                    PhysicsPosition(position),
+                    PhysicsPreviousPosition(position),
+                    Transform::from_translation(position),
+                ));
+            }
+        }
+    }
+}
@@ -9,7 +9,6 @@ use bevy::{
 use bevy_egui::PrimaryEguiContext;
 use bevy_hookup_core::{
     share_component::ShareComponent, sync_entity::SyncEntityOwner,
-    utils::buffer_object::BufferObject,
 };
 use physics::{
     collider::Collider, physics_object::DynamicPhysicsObject,
@@ -21,6 +20,7 @@ use world_generation::{
     chunk_loading::{
         chunk_loader::ChunkLoader, visual_chunk_loader::VisualChunkLoader,
     },
+    voxel_editing::voxel_ray_cast::VoxelRayCast,
     world_ready::WorldReady,
 };
 
@@ -28,9 +28,31 @@ use crate::{
     camera::{
         player_camera::PlayerCamera, player_camera_target::PlayerCameraTarget,
     },
+    network_movement::{
+        movement_prediction::PredictionHistory,
+        player_input_buffer::PlayerInputBuffer,
+        player_snapshot::PlayerSnapshot,
+    },
+    player_profile::{LocalPlayerProfile, PlayerProfile},
     player_state::PlayerState,
 };
 
+pub const PLAYER_STEP_HEIGHT: f32 = 0.6;
+
+pub fn get_player_collider() -> Collider {
+    Collider::aabb(Vec3::new(0.8, 1.8, 0.8), Vec3::ZERO)
+}
+
+/// Where players spawn, on top of the terrain at the center of the world.
+/// Returns [`None`] while the terrain there isn't loaded.
+pub fn find_spawn_point(voxel_ray_cast: &VoxelRayCast) -> Option<Vec3> {
+    let ray = Ray3d::new(Vec3::Y * 5000., Dir3::NEG_Y);
+
+    voxel_ray_cast
+        .cast_ray(ray, 10000.)
+        .map(|hit| hit.point + Vec3::Y)
+}
+
 #[derive(Component)]
 pub struct Player {
     pub fly: bool,
@@ -44,45 +66,42 @@ pub struct PlayerRotation(pub Quat);
 #[derive(Component)]
 pub(super) struct PlayerBody;
 
+#[derive(Component)]
+pub(super) struct PlayerTorso;
+
 pub(super) fn spawn_player(
     _: On<WorldReady>,
     mut commands: Commands,
     mut player_state: ResMut<NextState<PlayerState>>,
-    mut ray_cast: MeshRayCast,
+    voxel_ray_cast: VoxelRayCast,
+    local_player_profile: Res<LocalPlayerProfile>,
     mut scattering_mediums: ResMut<Assets<ScatteringMedium>>,
 ) -> Result {
     player_state.set(PlayerState::Spawend);
 
-    let ray = Ray3d::new(Vec3::Y * 5000., Dir3::NEG_Y);
-    let Some((_, hit)) = ray_cast
-        .cast_ray(
-            ray,
-            &MeshRayCastSettings {
-                visibility: RayCastVisibility::Any,
-                ..Default::default()
-            },
-        )
-        .first()
-    else {
+    let Some(spawn_point) = find_spawn_point(&voxel_ray_cast) else {
         return Err("Could not spawn player!".into());
     };
 
-    let spawn_point = hit.point + Vec3::Y;
-
     // Player
     commands.spawn((
         DynamicPhysicsObject {
-            step_height: 0.6,
+            step_height: PLAYER_STEP_HEIGHT,
             ..Default::default()
         },
         PhysicsPosition(spawn_point),
         Transform::from_translation(spawn_point),
-        Collider::aabb(Vec3::new(0.8, 1.8, 0.8), Vec3::ZERO),
+        get_player_collider(),
         SyncEntityOwner::new(),
         Player { fly: false },
-        ShareComponent::<BufferObject<PhysicsPosition>>::default(),
         PlayerRotation::default(),
-        ShareComponent::<PlayerRotation>::default(),
+        PredictionHistory::default(),
+        (
+            PlayerInputBuffer::new(spawn_point),
+            ShareComponent::<PlayerInputBuffer>::default(),
+            local_player_profile.sanitized(),
+            ShareComponent::<PlayerProfile>::default(),
+        ),
         ChunkLoader::default(),
         PlayerCameraTarget,
         Name::new("Player"),
@@ -129,17 +148,27 @@ pub(super) fn spawn_player(
     Ok(())
 }
 
+/// The local player and the snapshots of everyone else get a body. The
+/// host doesn't share the rotation of the own snapshot of a client with it.
+type PlayersWithoutBody = (
+    With<PlayerRotation>,
+    Or<(With<Player>, With<PlayerSnapshot>)>,
+    Without<PlayerBody>,
+);
+
 pub(super) fn spawn_player_body(
     players_without_body: Query<
-        Entity,
-        (With<PlayerRotation>, Without<PlayerBody>),
+        (Entity, Option<&PlayerProfile>),
+        PlayersWithoutBody,
     >,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
 ) {
-    for player_entity in players_without_body {
+    for (player_entity, player_profile) in players_without_body {
+        let color = player_profile.cloned().unwrap_or_default().get_color();
+
         commands
             .entity(player_entity)
             .insert((PlayerBody, Name::new("PlayerBody"), Mesh3d::default()))
@@ -155,10 +184,26 @@ pub(super) fn spawn_player_body(
                         half_length: 0.3,
                         ..default()
                     }))),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+                    MeshMaterial3d(materials.add(color)),
                     Transform::from_xyz(0., -0.35, 0.),
+                    PlayerTorso,
                     Name::new("PlayerTorso"),
                 ));
             });
     }
 }
+
+/// Profiles can arrive after the body of a remote player was spawned.
+pub(super) fn update_player_colors(
+    players: Query<(&PlayerProfile, &Children), Changed<PlayerProfile>>,
+    torsos: Query<&MeshMaterial3d<StandardMaterial>, With<PlayerTorso>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (player_profile, children) in &players {
+        for torso_material in torsos.iter_many(children) {
+            if let Some(material) = materials.get_mut(torso_material) {
+                material.base_color = player_profile.get_color();
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy_egui::EguiContexts;
 
 #[derive(Resource, Default)]
 pub struct PlayerInputs {
@@ -11,12 +12,38 @@ pub struct PlayerInputs {
     pub fly: bool,
     pub up: bool,
     pub down: bool,
+    pub break_block: bool,
+    pub place_block: bool,
+    pub select_block: Option<usize>,
 }
 
+const BLOCK_SELECTION_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
 pub fn update_player_inputs(
     mut player_inputs: ResMut<PlayerInputs>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut contexts: EguiContexts,
 ) {
+    // Typing into a text field, like the chat, shouldn't move the player.
+    if contexts
+        .ctx_mut()
+        .is_ok_and(|ctx| ctx.wants_keyboard_input())
+    {
+        *player_inputs = PlayerInputs::default();
+        return;
+    }
+
     player_inputs.forward = keyboard_input.pressed(KeyCode::KeyW);
     player_inputs.backwards = keyboard_input.pressed(KeyCode::KeyS);
     player_inputs.left = keyboard_input.pressed(KeyCode::KeyA);
@@ -26,4 +53,9 @@ pub fn update_player_inputs(
     player_inputs.fly = keyboard_input.just_pressed(KeyCode::KeyF);
     player_inputs.up = keyboard_input.pressed(KeyCode::KeyE);
     player_inputs.down = keyboard_input.pressed(KeyCode::KeyQ);
+    player_inputs.break_block = mouse_input.just_pressed(MouseButton::Left);
+    player_inputs.place_block = mouse_input.just_pressed(MouseButton::Right);
+    player_inputs.select_block = BLOCK_SELECTION_KEYS
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key));
 }
@@ -4,13 +4,68 @@ use bevy::prelude::*;
 use physics::{
     physics_object::DynamicPhysicsObject, physics_velocity::PhysicsVelocity,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     camera::player_camera::PlayerCamera,
+    network_movement::movement_prediction::PredictionHistory,
     player_component::{Player, PlayerRotation},
     player_inputs::PlayerInputs,
 };
 
+/// How fast a player swims up while jumping in water.
+const SWIM_UP_SPEED: f32 = 4.;
+
+/// How fast a player sinks in water without swimming.
+const SINK_SPEED: f32 = 2.;
+
+/// How quickly water brings the vertical speed to swimming or sinking.
+const WATER_DRAG: f32 = 4.;
+
+/// How quickly the horizontal speed follows the inputs on the ground.
+/// Pushes like knockbacks fade out at the same rate.
+const GROUND_CONTROL: f32 = 20.;
+
+/// How quickly the horizontal speed follows the inputs in the air,
+/// so knockbacks carry a player further.
+const AIR_CONTROL: f32 = 4.;
+
+/// Everything from the inputs of a player that changes how it moves.
+/// Gets sent to the host, so it can run the same movement.
+#[derive(Clone, Copy, Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct MovementInput {
+    pub forward: bool,
+    pub backwards: bool,
+    pub left: bool,
+    pub right: bool,
+    pub jump: bool,
+    pub sprint: bool,
+    pub fly: bool,
+    pub up: bool,
+    pub down: bool,
+    pub yaw: Option<f32>,
+}
+
+impl MovementInput {
+    pub fn from_player_inputs(
+        player_inputs: &PlayerInputs,
+        yaw: Option<f32>,
+    ) -> Self {
+        Self {
+            forward: player_inputs.forward,
+            backwards: player_inputs.backwards,
+            left: player_inputs.left,
+            right: player_inputs.right,
+            jump: player_inputs.jump,
+            sprint: player_inputs.sprint,
+            fly: player_inputs.fly,
+            up: player_inputs.up,
+            down: player_inputs.down,
+            yaw,
+        }
+    }
+}
+
 pub(super) fn movement(
     player_input: Res<PlayerInputs>,
     mut players: Query<(
@@ -18,85 +73,151 @@ pub(super) fn movement(
         &mut PhysicsVelocity,
         &mut DynamicPhysicsObject,
         &mut PlayerRotation,
+        Option<&mut PredictionHistory>,
     )>,
     player_camera: Query<&PlayerCamera>,
     time: Res<Time>,
 ) {
+    let yaw = player_camera
+        .single()
+        .ok()
+        .map(|player_camera| player_camera.yaw);
+    let movement_input = MovementInput::from_player_inputs(&player_input, yaw);
+
     for (
         mut player,
         mut physics_velocity,
         mut physics_object,
         mut player_rotation,
+        prediction_history,
     ) in &mut players
     {
-        let mut move_direction = Vec3::ZERO;
-
-        let grounded = physics_object.touching_sides.y < 0;
+        let in_water = physics_object.in_water;
 
-        if player_input.fly {
-            player.fly = !player.fly;
+        if let Some(rotation) = apply_movement(
+            &movement_input,
+            &mut player.fly,
+            &mut physics_velocity,
+            &mut physics_object.touching_sides,
+            in_water,
+            time.delta_secs(),
+        ) {
+            player_rotation.0 = rotation;
         }
 
-        if grounded || player.fly {
-            physics_velocity.y = 0.;
+        if let Some(mut prediction_history) = prediction_history {
+            prediction_history.push_input(movement_input);
         }
+    }
+}
 
-        // Directional movement
-        if player_input.forward {
-            move_direction.z -= 1.;
-        }
-        if player_input.left {
-            move_direction.x -= 1.;
-        }
-        if player_input.backwards {
-            move_direction.z += 1.;
-        }
-        if player_input.right {
-            move_direction.x += 1.;
-        }
+/// Changes the velocity of a player for one physics step.
+/// Returns the direction the player should face, if it moved.
+pub fn apply_movement(
+    movement_input: &MovementInput,
+    fly: &mut bool,
+    velocity: &mut Vec3,
+    touching_sides: &mut IVec3,
+    in_water: bool,
+    delta_secs: f32,
+) -> Option<Quat> {
+    let mut move_direction = Vec3::ZERO;
+    let mut rotation = None;
 
-        if player.fly && player_input.up {
-            move_direction.y += 1.;
-        }
-        if player.fly && player_input.down {
-            move_direction.y -= 1.;
-        }
+    let grounded = touching_sides.y < 0;
+    let swimming = in_water && !*fly;
+
+    if movement_input.fly {
+        *fly = !*fly;
+    }
 
-        let mut movement_speed = if player_input.sprint { 15. } else { 7.5 };
+    if *fly {
+        velocity.y = 0.;
+    } else if grounded {
+        // Upward pushes lift the player off the ground.
+        velocity.y = velocity.y.max(0.);
+    }
 
-        if player.fly {
-            movement_speed *= 10.;
-        }
+    // Directional movement
+    if movement_input.forward {
+        move_direction.z -= 1.;
+    }
+    if movement_input.left {
+        move_direction.x -= 1.;
+    }
+    if movement_input.backwards {
+        move_direction.z += 1.;
+    }
+    if movement_input.right {
+        move_direction.x += 1.;
+    }
 
-        if let Ok(player_camera) = player_camera.single() {
-            // Rotate vector to camera
-            let rotation = Quat::from_rotation_y(player_camera.yaw);
-            move_direction = rotation
-                .mul_vec3(move_direction.normalize_or_zero() * movement_speed);
-
-            if move_direction.xz() != Vec2::ZERO {
-                player_rotation.0 = Quat::from_rotation_y(
-                    -move_direction.xz().to_angle() - PI * 0.5,
-                );
-            }
-        }
+    if *fly && movement_input.up {
+        move_direction.y += 1.;
+    }
+    if *fly && movement_input.down {
+        move_direction.y -= 1.;
+    }
+
+    let mut movement_speed = if movement_input.sprint { 15. } else { 7.5 };
 
-        // Jump if space pressed and the player is close enough to the ground
-        if !player.fly && grounded && player_input.jump {
-            physics_velocity.y += 10.;
-            physics_object.touching_sides.y = 0;
+    if *fly {
+        movement_speed *= 10.;
+    } else if swimming {
+        movement_speed *= 0.5;
+    }
+
+    if let Some(yaw) = movement_input.yaw {
+        // Rotate vector to camera
+        let yaw_rotation = Quat::from_rotation_y(yaw);
+        move_direction = yaw_rotation
+            .mul_vec3(move_direction.normalize_or_zero() * movement_speed);
+
+        if move_direction.xz() != Vec2::ZERO {
+            rotation = Some(Quat::from_rotation_y(
+                -move_direction.xz().to_angle() - PI * 0.5,
+            ));
         }
+    }
+
+    // Jump if space pressed and the player is close enough to the ground
+    if !*fly && !swimming && grounded && movement_input.jump {
+        velocity.y += 10.;
+        touching_sides.y = 0;
+    }
 
-        physics_velocity.x = move_direction.x;
-        physics_velocity.z = move_direction.z;
-        if !player.fly {
-            if grounded {
-                physics_velocity.y -= 100. * time.delta_secs();
-            } else {
-                physics_velocity.y -= (20. * time.delta_secs()).max(-160.);
-            }
+    if *fly {
+        velocity.x = move_direction.x;
+        velocity.z = move_direction.z;
+    } else {
+        let control = if grounded || swimming {
+            GROUND_CONTROL
         } else {
-            physics_velocity.y = move_direction.y;
+            AIR_CONTROL
+        };
+        let blend = (control * delta_secs).min(1.);
+        velocity.x += (move_direction.x - velocity.x) * blend;
+        velocity.z += (move_direction.z - velocity.z) * blend;
+    }
+
+    if swimming {
+        // Water pulls the player towards a slow sink, jumping swims up
+        let target_speed = if movement_input.jump || movement_input.up {
+            SWIM_UP_SPEED
+        } else {
+            -SINK_SPEED
+        };
+        velocity.y +=
+            (target_speed - velocity.y) * (WATER_DRAG * delta_secs).min(1.);
+    } else if !*fly {
+        if grounded {
+            velocity.y -= 100. * delta_secs;
+        } else {
+            velocity.y -= (20. * delta_secs).max(-160.);
         }
+    } else {
+        velocity.y = move_direction.y;
     }
+
+    rotation
 }
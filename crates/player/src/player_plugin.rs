@@ -1,15 +1,19 @@
 use std::f32::consts::PI;
 
 use bevy::prelude::*;
-use physics::physics_systems::PhysicsSystems;
+use bevy_egui::EguiPrimaryContextPass;
 
 use crate::{
+    block_interaction::{SelectedBlock, interact_with_blocks, select_block},
     camera::player_camera_plugin::PlayerCameraPlugin,
+    nameplate::render_nameplates,
+    network_movement::network_movement_plugin::NetworkMovementPlugin,
     player_component::{
         PlayerBody, PlayerRotation, spawn_player, spawn_player_body,
+        update_player_colors,
     },
-    player_inputs::{PlayerInputs, update_player_inputs},
-    player_movement::movement,
+    player_inputs::update_player_inputs,
+    player_profile::LocalPlayerProfile,
     player_state::PlayerState,
 };
 
@@ -17,13 +21,21 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(PlayerCameraPlugin)
+        app.add_plugins((PlayerCameraPlugin, NetworkMovementPlugin))
             .init_state::<PlayerState>()
-            .init_resource::<PlayerInputs>()
-            .add_systems(Update, spawn_player_body)
+            .init_resource::<SelectedBlock>()
+            .init_resource::<LocalPlayerProfile>()
+            .add_systems(Update, (spawn_player_body, update_player_colors))
             .add_systems(PreUpdate, update_player_inputs)
-            .add_systems(FixedUpdate, movement.before(PhysicsSystems))
-            .add_systems(Update, (rotate_body_smoothed, zoom))
+            .add_systems(
+                Update,
+                (
+                    rotate_body_smoothed,
+                    zoom,
+                    (select_block, interact_with_blocks).chain(),
+                ),
+            )
+            .add_systems(EguiPrimaryContextPass, render_nameplates)
             .add_observer(spawn_player);
     }
 }
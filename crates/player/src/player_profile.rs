@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_PLAYER_NAME_LENGTH: usize = 24;
+
+/// How a player shows up for everyone else in a session.
+#[derive(Component, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PlayerProfile {
+    pub name: String,
+    pub color: [f32; 3],
+}
+
+impl Default for PlayerProfile {
+    fn default() -> Self {
+        Self {
+            name: "Player".into(),
+            color: [0.8, 0.7, 0.6],
+        }
+    }
+}
+
+impl PlayerProfile {
+    /// Trims the name and cuts it to [`MAX_PLAYER_NAME_LENGTH`],
+    /// falling back to the default name if nothing is left.
+    pub fn sanitized(&self) -> Self {
+        let name = self
+            .name
+            .trim()
+            .chars()
+            .take(MAX_PLAYER_NAME_LENGTH)
+            .collect::<String>();
+
+        Self {
+            name: if name.is_empty() {
+                Self::default().name
+            } else {
+                name
+            },
+            color: self.color.map(|channel| channel.clamp(0., 1.)),
+        }
+    }
+
+    pub fn get_color(&self) -> Color {
+        Color::srgb(self.color[0], self.color[1], self.color[2])
+    }
+
+    pub fn get_egui_color(&self) -> egui::Color32 {
+        let [red, green, blue] = self
+            .color
+            .map(|channel| (channel.clamp(0., 1.) * 255.) as u8);
+
+        egui::Color32::from_rgb(red, green, blue)
+    }
+}
+
+/// The profile the local player spawns with.
+#[derive(Resource, Clone, Default, Deref, DerefMut)]
+pub struct LocalPlayerProfile(pub PlayerProfile);
@@ -0,0 +1,485 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_hookup_core::from_session::FromSession;
+use physics::{
+    collider::Collider,
+    physics_impulse::{ApplyImpulse, Explosion},
+    physics_object::{DynamicPhysicsObject, StaticPhysicsObject},
+    physics_plugin::PhysicsPlugin,
+    physics_position::PhysicsPosition,
+};
+use player::{
+    camera::player_camera::PlayerCamera,
+    network_movement::{
+        authoritative_movement::{
+            AuthoritativeMovement, AuthoritativeMovementSystems,
+        },
+        movement_prediction::PredictionHistory,
+        network_movement_plugin::NetworkMovementPlugin,
+        player_input_buffer::{InputFrame, PlayerInputBuffer},
+        player_snapshot::{AssignedPlayerId, PlayerSnapshot},
+    },
+    player_component::{
+        PLAYER_STEP_HEIGHT, Player, PlayerRotation, get_player_collider,
+    },
+    player_inputs::PlayerInputs,
+    player_movement::MovementInput,
+};
+
+const SPAWN_POSITION: Vec3 = Vec3::new(0., 1.5, 0.);
+
+/// Each update of an app runs exactly one fixed step.
+fn create_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, PhysicsPlugin, NetworkMovementPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            Time::<Fixed>::default().timestep(),
+        ));
+
+    app.world_mut().spawn((
+        StaticPhysicsObject,
+        Collider::aabb(Vec3::new(100., 1., 100.), Vec3::ZERO),
+        Transform::default(),
+    ));
+
+    app
+}
+
+/// A client and a host, connected by queues that delay every message
+/// by a couple of updates instead of a real session.
+struct TestSession {
+    client: App,
+    host: App,
+    client_player: Entity,
+    host_player: Entity,
+    latency: usize,
+    /// How many of the next messages to the host get lost.
+    dropped_to_host: usize,
+    to_host: VecDeque<PlayerInputBuffer>,
+    to_client: VecDeque<Vec<(PlayerSnapshot, Option<AssignedPlayerId>)>>,
+}
+
+impl TestSession {
+    fn new(latency: usize) -> Self {
+        let mut client = create_app();
+        let mut host = create_app();
+
+        // Like the networking plugin does on clients.
+        client.configure_sets(
+            FixedUpdate,
+            AuthoritativeMovementSystems.run_if(|| false),
+        );
+        client.world_mut().spawn(PlayerCamera::default());
+
+        let player_input_buffer = PlayerInputBuffer::new(SPAWN_POSITION);
+        let client_player = client
+            .world_mut()
+            .spawn((
+                Player { fly: false },
+                DynamicPhysicsObject {
+                    step_height: PLAYER_STEP_HEIGHT,
+                    ..Default::default()
+                },
+                PhysicsPosition(SPAWN_POSITION),
+                get_player_collider(),
+                PlayerRotation::default(),
+                PredictionHistory::default(),
+                player_input_buffer.clone(),
+            ))
+            .id();
+
+        let host_player = host
+            .world_mut()
+            .spawn((player_input_buffer, FromSession::default()))
+            .id();
+
+        Self {
+            client,
+            host,
+            client_player,
+            host_player,
+            latency,
+            dropped_to_host: 0,
+            to_host: VecDeque::new(),
+            to_client: VecDeque::new(),
+        }
+    }
+
+    fn update(&mut self) {
+        self.client.update();
+
+        let player_input_buffer = self
+            .client
+            .world()
+            .get::<PlayerInputBuffer>(self.client_player)
+            .unwrap()
+            .clone();
+        if self.dropped_to_host > 0 {
+            self.dropped_to_host -= 1;
+        } else {
+            self.to_host.push_back(player_input_buffer);
+        }
+
+        if self.to_host.len() > self.latency {
+            let player_input_buffer = self.to_host.pop_front().unwrap();
+            self.host
+                .world_mut()
+                .entity_mut(self.host_player)
+                .insert(player_input_buffer);
+        }
+
+        self.host.update();
+
+        let snapshots = self
+            .host
+            .world_mut()
+            .query::<(&PlayerSnapshot, Option<&AssignedPlayerId>)>()
+            .iter(self.host.world())
+            .map(|(snapshot, assigned_player_id)| {
+                (snapshot.clone(), assigned_player_id.copied())
+            })
+            .collect::<Vec<_>>();
+        self.to_client.push_back(snapshots);
+
+        if self.to_client.len() > self.latency {
+            for (snapshot, assigned_player_id) in
+                self.to_client.pop_front().unwrap()
+            {
+                let world = self.client.world_mut();
+                let existing = world
+                    .query::<(Entity, &PlayerSnapshot)>()
+                    .iter(world)
+                    .find(|(_, existing)| {
+                        existing.player_id == snapshot.player_id
+                    })
+                    .map(|(entity, _)| entity);
+
+                match existing {
+                    Some(entity) => {
+                        world.entity_mut(entity).insert(snapshot);
+                    }
+                    None => {
+                        let mut entity =
+                            world.spawn((snapshot, FromSession::default()));
+                        if let Some(assigned_player_id) = assigned_player_id {
+                            entity.insert(assigned_player_id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn set_forward(&mut self, forward: bool) {
+        self.client
+            .world_mut()
+            .resource_mut::<PlayerInputs>()
+            .forward = forward;
+    }
+
+    fn drop_to_host(&mut self, count: usize) {
+        self.dropped_to_host = count;
+    }
+
+    fn client_position(&self) -> Vec3 {
+        **self
+            .client
+            .world()
+            .get::<PhysicsPosition>(self.client_player)
+            .unwrap()
+    }
+
+    fn host_position(&self) -> Vec3 {
+        self.host
+            .world()
+            .get::<AuthoritativeMovement>(self.host_player)
+            .unwrap()
+            .state
+            .position
+    }
+
+    fn unconfirmed_input_count(&self) -> usize {
+        self.client
+            .world()
+            .get::<PredictionHistory>(self.client_player)
+            .unwrap()
+            .get_unconfirmed_frames()
+            .count()
+    }
+}
+
+#[test]
+fn host_follows_predicted_movement() {
+    let mut session = TestSession::new(3);
+
+    session.set_forward(true);
+    for _ in 0..60 {
+        session.update();
+    }
+
+    // The client runs ahead of the host by the round trip.
+    assert!(session.client_position().z < session.host_position().z);
+
+    session.set_forward(false);
+    for _ in 0..20 {
+        session.update();
+    }
+
+    assert!(session.client_position().z < -1.);
+    assert!(
+        session.client_position().distance(session.host_position()) < 0.001
+    );
+}
+
+#[test]
+fn client_gets_corrected_by_host() {
+    let mut session = TestSession::new(3);
+
+    // A wall the client doesn't know about stops the player on the host.
+    session.host.world_mut().spawn((
+        StaticPhysicsObject,
+        Collider::aabb(Vec3::new(100., 10., 1.), Vec3::ZERO),
+        Transform::from_xyz(0., 0., -3.),
+    ));
+
+    session.set_forward(true);
+    for _ in 0..120 {
+        session.update();
+    }
+
+    session.set_forward(false);
+    for _ in 0..20 {
+        session.update();
+    }
+
+    assert!(session.host_position().z > -3.);
+    assert!(
+        session.client_position().distance(session.host_position()) < 0.001
+    );
+    assert!(session.unconfirmed_input_count() < 10);
+}
+
+#[test]
+fn host_gets_inputs_of_dropped_messages() {
+    let mut session = TestSession::new(3);
+    let mut lossless_session = TestSession::new(3);
+
+    session.set_forward(true);
+    lossless_session.set_forward(true);
+    for update in 0..60 {
+        // Half a second of messages to the host gets lost.
+        if update == 20 {
+            session.drop_to_host(30);
+        }
+        session.update();
+        lossless_session.update();
+    }
+
+    session.set_forward(false);
+    lossless_session.set_forward(false);
+    for _ in 0..40 {
+        session.update();
+        lossless_session.update();
+    }
+
+    // The host only walks as far as the client if it got every input.
+    assert!(
+        session
+            .host_position()
+            .distance(lossless_session.host_position())
+            < 0.001
+    );
+    assert!(
+        session.client_position().distance(session.host_position()) < 0.001
+    );
+}
+
+#[test]
+fn host_only_accepts_spawn_positions_near_the_center() {
+    let mut app = create_app();
+
+    let far_away = app
+        .world_mut()
+        .spawn((
+            PlayerInputBuffer::new(Vec3::new(1000., 1.5, 0.)),
+            FromSession::default(),
+        ))
+        .id();
+    let in_the_ground = app
+        .world_mut()
+        .spawn((PlayerInputBuffer::new(Vec3::ZERO), FromSession::default()))
+        .id();
+    let near_the_center = app
+        .world_mut()
+        .spawn((
+            PlayerInputBuffer::new(SPAWN_POSITION),
+            FromSession::default(),
+        ))
+        .id();
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let world = app.world();
+    assert!(world.get::<AuthoritativeMovement>(far_away).is_none());
+    assert!(world.get::<AuthoritativeMovement>(in_the_ground).is_none());
+    assert_eq!(
+        world
+            .get::<AuthoritativeMovement>(near_the_center)
+            .unwrap()
+            .state
+            .position,
+        SPAWN_POSITION
+    );
+}
+
+fn spawn_remote_player(app: &mut App, spawn_position: Vec3) -> Entity {
+    app.world_mut()
+        .spawn((
+            PlayerInputBuffer::new(spawn_position),
+            FromSession::default(),
+        ))
+        .id()
+}
+
+/// Sends the next input of a remote player, like its client would.
+fn send_input(app: &mut App, player: Entity, input: MovementInput) {
+    let mut player_input_buffer = app
+        .world_mut()
+        .get_mut::<PlayerInputBuffer>(player)
+        .unwrap();
+    let sequence = player_input_buffer
+        .frames
+        .last()
+        .map_or(0, |input_frame| input_frame.sequence + 1);
+    player_input_buffer.frames = vec![InputFrame { sequence, input }];
+}
+
+fn get_authoritative_position(app: &App, player: Entity) -> Vec3 {
+    app.world()
+        .get::<AuthoritativeMovement>(player)
+        .unwrap()
+        .state
+        .position
+}
+
+#[test]
+fn remote_players_do_not_overlap() {
+    let mut app = create_app();
+    let left_player = spawn_remote_player(&mut app, Vec3::new(-2., 1.5, 0.));
+    let right_player = spawn_remote_player(&mut app, Vec3::new(2., 1.5, 0.));
+
+    // Both walk into each other.
+    for _ in 0..120 {
+        send_input(
+            &mut app,
+            left_player,
+            MovementInput {
+                right: true,
+                yaw: Some(0.),
+                ..Default::default()
+            },
+        );
+        send_input(
+            &mut app,
+            right_player,
+            MovementInput {
+                left: true,
+                yaw: Some(0.),
+                ..Default::default()
+            },
+        );
+        app.update();
+    }
+
+    let left_position = get_authoritative_position(&app, left_player);
+    let right_position = get_authoritative_position(&app, right_player);
+    let player_width = get_player_collider().get_bounds(Vec3::ZERO).1.x * 2.;
+
+    assert!(left_position.x < right_position.x);
+    assert!(right_position.x - left_position.x >= player_width - 0.01);
+}
+
+#[test]
+fn knockback_reaches_remote_players() {
+    let mut app = create_app();
+    let player = spawn_remote_player(&mut app, SPAWN_POSITION);
+    send_input(&mut app, player, MovementInput::default());
+    app.update();
+    app.update();
+
+    let get_velocity = |app: &App| {
+        app.world()
+            .get::<AuthoritativeMovement>(player)
+            .unwrap()
+            .state
+            .velocity
+    };
+
+    let velocity = get_velocity(&app);
+    app.world_mut().trigger(ApplyImpulse {
+        entity: player,
+        impulse: Vec3::X * 8.,
+    });
+    assert!(get_velocity(&app).x > velocity.x);
+
+    let velocity = get_velocity(&app);
+    let position = get_authoritative_position(&app, player);
+    app.world_mut().trigger(Explosion {
+        position: position + Vec3::X,
+        radius: 2.,
+        impulse: 8.,
+    });
+    assert!(get_velocity(&app).x < velocity.x);
+}
+
+/// How often the snapshot of the own player of the host changed.
+#[derive(Resource, Default)]
+struct HostSnapshotChanges(usize);
+
+#[test]
+fn host_player_snapshot_only_changes_when_it_moves() {
+    let mut app = create_app();
+    app.init_resource::<HostSnapshotChanges>().add_systems(
+        FixedPostUpdate,
+        |snapshots: Query<(), Changed<PlayerSnapshot>>,
+         mut changes: ResMut<HostSnapshotChanges>| {
+            changes.0 += snapshots.iter().count();
+        },
+    );
+    app.world_mut().spawn(PlayerCamera::default());
+    let player = app
+        .world_mut()
+        .spawn((
+            Player { fly: false },
+            DynamicPhysicsObject {
+                step_height: PLAYER_STEP_HEIGHT,
+                ..Default::default()
+            },
+            PhysicsPosition(SPAWN_POSITION),
+            get_player_collider(),
+            PlayerRotation::default(),
+            PredictionHistory::default(),
+        ))
+        .id();
+
+    // Lets the player land.
+    for _ in 0..60 {
+        app.update();
+    }
+    app.world_mut().resource_mut::<HostSnapshotChanges>().0 = 0;
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<HostSnapshotChanges>().0, 0);
+
+    app.world_mut().trigger(ApplyImpulse {
+        entity: player,
+        impulse: Vec3::X * 8.,
+    });
+    app.update();
+    assert_eq!(app.world().resource::<HostSnapshotChanges>().0, 1);
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use player::player_movement::{MovementInput, apply_movement};
+
+const DELTA_SECS: f32 = 1. / 64.;
+
+fn run_movement(velocity: &mut Vec3, touching_sides: IVec3, steps: usize) {
+    let mut fly = false;
+    for _ in 0..steps {
+        let mut touching_sides = touching_sides;
+        apply_movement(
+            &MovementInput::default(),
+            &mut fly,
+            velocity,
+            &mut touching_sides,
+            false,
+            DELTA_SECS,
+        );
+    }
+}
+
+#[test]
+fn knockback_fades_out() {
+    let mut velocity = Vec3::X * 10.;
+
+    run_movement(&mut velocity, IVec3::ZERO, 1);
+    assert!(velocity.x > 9., "Knockback got lost: {}", velocity);
+
+    run_movement(&mut velocity, IVec3::NEG_Y, 60);
+    assert!(velocity.x.abs() < 0.01, "Knockback kept going: {}", velocity);
+}
+
+#[test]
+fn upward_knockback_leaves_the_ground() {
+    let mut velocity = Vec3::Y * 10.;
+
+    run_movement(&mut velocity, IVec3::NEG_Y, 1);
+
+    assert!(velocity.y > 5., "Knockback got lost: {}", velocity);
+}
@@ -5,24 +5,47 @@ use bevy::{
 };
 use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
 use bevy_panorbit_camera::PanOrbitCameraPlugin;
+#[cfg(feature = "steam")]
 use bevy_steamworks::SteamworksPlugin;
 use debug_tools::debug_plugin::SpellhavenDebugPlugin;
 use main_menu::main_menu_plugin::MainMenuPlugin;
 use networking::networking_plugin::NetworkingPlugin;
+#[cfg(feature = "steam")]
+use networking::steam_networking_plugin::SteamNetworkingPlugin;
 use physics::physics_plugin::PhysicsPlugin;
 use player::player_plugin::PlayerPlugin;
 use ui::game_ui_plugin::GameUiPlugin;
+use utils::file_utils::get_asset_root;
 use world_generation::{
     terrain_material::TerrainMaterial,
     world_generation_plugin::WorldGenerationPlugin,
 };
 
+#[cfg(feature = "steam")]
+const STEAM_APP_ID: u32 = 4251410;
+
 pub struct GamePlugins;
 
 impl PluginGroup for GamePlugins {
     fn build(self) -> PluginGroupBuilder {
-        PluginGroupBuilder::start::<Self>()
-            .add(SteamworksPlugin::init_app(4251410).unwrap())
+        let mut builder = PluginGroupBuilder::start::<Self>();
+
+        // Steam has to be initialized before the render plugins. Without a
+        // running Steam client the game falls back to websocket multiplayer.
+        #[cfg(feature = "steam")]
+        let steam_initialized = match SteamworksPlugin::init_app(STEAM_APP_ID) {
+            Ok(steamworks_plugin) => {
+                builder = builder.add(steamworks_plugin);
+                true
+            }
+            Err(err) => {
+                // Logging is not set up yet at this point.
+                eprintln!("Failed initializing Steam: {}", err);
+                false
+            }
+        };
+
+        builder = builder
             .add_group(
                 DefaultPlugins
                     .set(WindowPlugin {
@@ -33,7 +56,11 @@ impl PluginGroup for GamePlugins {
                         }),
                         ..default()
                     })
-                    .set(ImagePlugin::default_nearest()),
+                    .set(ImagePlugin::default_nearest())
+                    .set(AssetPlugin {
+                        file_path: get_asset_root().to_string_lossy().into(),
+                        ..default()
+                    }),
             )
             .add(PanOrbitCameraPlugin)
             .add(WorldGenerationPlugin)
@@ -48,6 +75,13 @@ impl PluginGroup for GamePlugins {
             .add(NetworkingPlugin)
             .add(MaterialPlugin::<
                 ExtendedMaterial<StandardMaterial, TerrainMaterial>,
-            >::default())
+            >::default());
+
+        #[cfg(feature = "steam")]
+        if steam_initialized {
+            builder = builder.add(SteamNetworkingPlugin);
+        }
+
+        builder
     }
 }
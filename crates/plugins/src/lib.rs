@@ -1,4 +1,7 @@
 extern crate bevy;
 
 pub mod game_plugins;
+pub mod server_config;
+pub mod server_plugin;
+pub mod server_plugins;
 pub mod terrain_editor_plugin;
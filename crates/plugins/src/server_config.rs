@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use anyhow::{Context, anyhow};
+use bevy::prelude::*;
+use networking::start_websocket_server::{
+    DEFAULT_BIND_ADDRESS, DEFAULT_WEBSOCKET_PORT,
+};
+use serde::{Deserialize, Serialize};
+use utils::file_utils::read_ron_from_file;
+
+pub const SERVER_CONFIG_FILE_PATH: &'static str = "server.ron";
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub seed: u64,
+    pub address: String,
+    pub port: u16,
+    pub max_players: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            address: DEFAULT_BIND_ADDRESS.into(),
+            port: DEFAULT_WEBSOCKET_PORT,
+            max_players: 8,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Reads the config file passed with `--config`, or `server.ron` if it
+    /// exists, and applies the remaining command line flags on top of it.
+    pub fn from_args(args: &[String]) -> Result<Self, anyhow::Error> {
+        let config_path = args
+            .iter()
+            .position(|arg| arg == "--config")
+            .map(|index| {
+                args.get(index + 1)
+                    .cloned()
+                    .ok_or(anyhow!("Missing value for --config"))
+            })
+            .transpose()?;
+
+        let mut server_config = match config_path {
+            Some(config_path) => read_ron_from_file(&config_path)
+                .with_context(|| format!("Failed reading {}", config_path))?,
+            None if Path::new(SERVER_CONFIG_FILE_PATH).exists() => {
+                read_ron_from_file(SERVER_CONFIG_FILE_PATH).with_context(
+                    || format!("Failed reading {}", SERVER_CONFIG_FILE_PATH),
+                )?
+            }
+            None => Self::default(),
+        };
+
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let value =
+                args.next().ok_or(anyhow!("Missing value for {}", flag))?;
+
+            match flag.as_str() {
+                "--config" => {}
+                "--seed" => server_config.seed = value.parse()?,
+                "--address" => server_config.address = value.clone(),
+                "--port" => server_config.port = value.parse()?,
+                "--max-players" => server_config.max_players = value.parse()?,
+                _ => return Err(anyhow!("Unknown flag {}", flag)),
+            }
+        }
+
+        Ok(server_config)
+    }
+}
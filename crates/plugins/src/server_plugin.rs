@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_hookup_core::from_session::FromSession;
+use networking::{
+    chat::{KickReason, KickSession},
+    connection_failed::ConnectionFailed,
+    create_world::CreateWorld,
+    start_websocket_server::StartWebsocketServer,
+};
+use player::network_movement::player_input_buffer::PlayerInputBuffer;
+use world_generation::{
+    chunk_generation::chunk_mesh_mode::ChunkMeshMode,
+    world_save::save_world::SaveWorld,
+};
+
+use crate::server_config::ServerConfig;
+
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Runs a dedicated server that only keeps the world around its players
+/// loaded, so their colliders exist without anything being rendered.
+pub struct ServerPlugin {
+    pub server_config: ServerConfig,
+}
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.server_config.clone())
+            .insert_resource(ChunkMeshMode::ColliderOnly)
+            .add_systems(Startup, start_server)
+            .add_systems(Update, (add_server_players, autosave))
+            .add_observer(exit_on_connection_failed);
+    }
+}
+
+#[derive(Component)]
+pub struct ServerPlayer;
+
+fn start_server(server_config: Res<ServerConfig>, mut commands: Commands) {
+    info!(
+        "Starting server on {}:{} with seed {}",
+        server_config.address, server_config.port, server_config.seed
+    );
+
+    commands.trigger(StartWebsocketServer {
+        address: server_config.address.clone(),
+        port: server_config.port,
+    });
+    commands.trigger(CreateWorld {
+        seed: server_config.seed,
+    });
+}
+
+/// Without a bound address nobody can join, so the server stops.
+fn exit_on_connection_failed(
+    connection_failed: On<ConnectionFailed>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    error!("{}", connection_failed.message);
+    app_exit.write(AppExit::error());
+}
+
+fn add_server_players(
+    mut commands: Commands,
+    added_players: Query<(Entity, &FromSession), Added<PlayerInputBuffer>>,
+    server_players: Query<(), With<ServerPlayer>>,
+    server_config: Res<ServerConfig>,
+) {
+    let mut player_count = server_players.iter().count();
+
+    for (entity, from_session) in &added_players {
+        if player_count >= server_config.max_players {
+            warn!(
+                "Server is full, rejecting player ({} max)",
+                server_config.max_players
+            );
+            // Tells the client to leave, the player gets dropped along with
+            // the other kicked ones.
+            commands.trigger(KickSession {
+                session_id: from_session.session_id,
+                reason: KickReason::ServerFull,
+            });
+            continue;
+        }
+
+        player_count += 1;
+        info!(
+            "Player joined ({}/{})",
+            player_count, server_config.max_players
+        );
+
+        commands
+            .entity(entity)
+            .insert(ServerPlayer)
+            .insert_if_new(Transform::default());
+    }
+}
+
+fn autosave(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: Local<Option<Timer>>,
+) {
+    let timer = timer.get_or_insert_with(|| {
+        Timer::new(AUTOSAVE_INTERVAL, TimerMode::Repeating)
+    });
+
+    if timer.tick(time.delta()).just_finished() {
+        commands.trigger(SaveWorld);
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use bevy::{
+    app::{PluginGroupBuilder, ScheduleRunnerPlugin},
+    log::LogPlugin,
+    prelude::*,
+    state::app::StatesPlugin,
+};
+use networking::networking_plugin::NetworkingPlugin;
+use physics::physics_plugin::PhysicsPlugin;
+use player::network_movement::network_movement_plugin::NetworkMovementPlugin;
+use utils::file_utils::get_asset_root;
+use world_generation::world_generation_plugin::WorldGenerationPlugin;
+
+use crate::{server_config::ServerConfig, server_plugin::ServerPlugin};
+
+const SERVER_TICK_RATE: f64 = 60.;
+
+/// Everything a dedicated server needs, without Steam, a window or rendering.
+pub struct ServerPlugins {
+    pub server_config: ServerConfig,
+}
+
+impl PluginGroup for ServerPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add_group(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(
+                Duration::from_secs_f64(1. / SERVER_TICK_RATE),
+            )))
+            .add(LogPlugin::default())
+            .add(AssetPlugin {
+                file_path: get_asset_root().to_string_lossy().into(),
+                ..default()
+            })
+            .add(StatesPlugin)
+            .add(WorldGenerationPlugin)
+            .add(PhysicsPlugin)
+            // Only the movement of the players, their camera and input need
+            // a window.
+            .add(NetworkMovementPlugin)
+            .add(NetworkingPlugin)
+            .add(ServerPlugin {
+                server_config: self.server_config,
+            })
+    }
+}
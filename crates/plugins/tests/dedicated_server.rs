@@ -0,0 +1,363 @@
+use std::{thread, time::Duration};
+
+use bevy::{log::LogPlugin, prelude::*, state::app::StatesPlugin};
+use bevy_hookup_core::{
+    from_session::FromSession, session::Session,
+    share_component::ShareComponent, sync_entity::SyncEntityOwner,
+};
+use bevy_hookup_messenger_websocket::websocket_server_state::WebsocketServerState;
+use networking::{
+    chat::{KickReason, KickSession},
+    connection_failed::ConnectionFailed,
+    networking_plugin::NetworkingPlugin,
+    networking_state::NetworkingState,
+    sendables::Sendables,
+    start_websocket_client::StartWebsocketClient,
+};
+use physics::{
+    physics_object::DynamicPhysicsObject, physics_plugin::PhysicsPlugin,
+    physics_position::PhysicsPosition,
+};
+use player::{
+    camera::player_camera::PlayerCamera,
+    network_movement::{
+        authoritative_movement::AuthoritativeMovement,
+        movement_prediction::PredictionHistory,
+        network_movement_plugin::NetworkMovementPlugin,
+        player_input_buffer::PlayerInputBuffer,
+        player_snapshot::{AssignedPlayerId, PlayerSnapshot},
+    },
+    player_component::{
+        PLAYER_STEP_HEIGHT, Player, PlayerRotation, get_player_collider,
+    },
+};
+use plugins::{
+    server_config::ServerConfig, server_plugin::ServerPlayer,
+    server_plugins::ServerPlugins,
+};
+use tokio::runtime::Runtime;
+use utils::file_utils::set_asset_root;
+use world_generation::{
+    chunk_generation::{
+        chunk_mesh_mode::ChunkMeshMode, disk_cache_settings::DiskCacheSettings,
+    },
+    chunk_loading::chunk_loader::ChunkLoader,
+    generation_options::GenerationOptions,
+    world_generation_plugin::WorldGenerationPlugin,
+    world_save::world_save_plugin::WorldSaveSettings,
+};
+
+const ASSET_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+const HOST: &str = "127.0.0.1";
+const SPAWN_POSITION: Vec3 = Vec3::new(0., 200., 0.);
+const UPDATE_INTERVAL: Duration = Duration::from_millis(10);
+/// Enough updates for the apps to connect and exchange a few messages.
+const MAX_UPDATES: usize = 1000;
+
+/// The message of the last failed connection of a client.
+#[derive(Resource, Default)]
+struct ConnectionFailedMessage(Option<String>);
+
+/// Keeps the test from writing saves and caches to disk.
+fn disable_disk_caches(app: &mut App) {
+    app.insert_resource(WorldSaveSettings {
+        enabled: false,
+        ..default()
+    })
+    .insert_resource(DiskCacheSettings {
+        enabled: false,
+        ..default()
+    });
+}
+
+fn build_server(port: u16, max_players: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        ServerPlugins {
+            server_config: ServerConfig {
+                address: HOST.into(),
+                port,
+                max_players,
+                ..default()
+            },
+        }
+        .build()
+        .disable::<LogPlugin>(),
+    );
+    disable_disk_caches(&mut app);
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+fn create_server(port: u16, max_players: usize) -> App {
+    let mut app = build_server(port, max_players);
+
+    let listening = update_until(&mut [&mut app], |apps| {
+        apps[0]
+            .world_mut()
+            .query::<&WebsocketServerState>()
+            .iter(apps[0].world())
+            .any(|state| matches!(state, WebsocketServerState::Ready))
+    });
+    assert!(listening, "The server did not start listening");
+
+    app
+}
+
+/// A client without a window, that spawns its player like the game does
+/// once it is connected.
+fn create_client(port: u16) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        AssetPlugin {
+            file_path: ASSET_ROOT.into(),
+            ..default()
+        },
+        WorldGenerationPlugin,
+        PhysicsPlugin,
+        NetworkMovementPlugin,
+        NetworkingPlugin,
+    ))
+    .insert_resource(ChunkMeshMode::ColliderOnly)
+    .init_resource::<ConnectionFailedMessage>()
+    .add_observer(
+        |connection_failed: On<ConnectionFailed>,
+         mut message: ResMut<ConnectionFailedMessage>| {
+            message.0 = Some(connection_failed.message.clone());
+        },
+    )
+    .add_systems(
+        Update,
+        spawn_client_player.run_if(in_state(NetworkingState::Client)),
+    );
+    disable_disk_caches(&mut app);
+    app.finish();
+    app.cleanup();
+
+    app.world_mut().trigger(StartWebsocketClient {
+        host: HOST.into(),
+        port,
+    });
+    app
+}
+
+fn spawn_client_player(mut commands: Commands, mut spawned: Local<bool>) {
+    if *spawned {
+        return;
+    }
+    *spawned = true;
+
+    commands.spawn(PlayerCamera::default());
+    commands.spawn((
+        Player { fly: false },
+        DynamicPhysicsObject {
+            step_height: PLAYER_STEP_HEIGHT,
+            ..Default::default()
+        },
+        PhysicsPosition(SPAWN_POSITION),
+        Transform::from_translation(SPAWN_POSITION),
+        get_player_collider(),
+        SyncEntityOwner::new(),
+        PlayerRotation::default(),
+        PredictionHistory::default(),
+        PlayerInputBuffer::new(SPAWN_POSITION),
+        ShareComponent::<PlayerInputBuffer>::default(),
+    ));
+}
+
+/// Updates all apps until `condition` holds, giving the websocket tasks
+/// time to run in between.
+fn update_until(
+    apps: &mut [&mut App],
+    mut condition: impl FnMut(&mut [&mut App]) -> bool,
+) -> bool {
+    for _ in 0..MAX_UPDATES {
+        for app in apps.iter_mut() {
+            app.update();
+        }
+
+        if condition(apps) {
+            return true;
+        }
+
+        thread::sleep(UPDATE_INTERVAL);
+    }
+
+    false
+}
+
+fn get_local_player_id(client: &mut App) -> Option<u64> {
+    client
+        .world_mut()
+        .query_filtered::<&AssignedPlayerId, With<FromSession>>()
+        .iter(client.world())
+        .next()
+        .map(|assigned_player_id| assigned_player_id.0)
+}
+
+fn has_snapshot(client: &mut App) -> bool {
+    let Some(player_id) = get_local_player_id(client) else {
+        return false;
+    };
+
+    client
+        .world_mut()
+        .query::<&PlayerSnapshot>()
+        .iter(client.world())
+        .any(|snapshot| snapshot.player_id == player_id)
+}
+
+fn count_server_players(server: &mut App) -> usize {
+    server
+        .world_mut()
+        .query_filtered::<(), (
+            With<ServerPlayer>,
+            With<ChunkLoader>,
+            With<AuthoritativeMovement>,
+        )>()
+        .iter(server.world())
+        .count()
+}
+
+fn count_server_sessions(server: &mut App) -> usize {
+    server
+        .world_mut()
+        .query_filtered::<(), With<Session<Sendables>>>()
+        .iter(server.world())
+        .count()
+}
+
+#[test]
+fn server_simulates_connected_players() {
+    set_asset_root(ASSET_ROOT).unwrap();
+    let runtime = Runtime::new().unwrap();
+    let _runtime_guard = runtime.enter();
+
+    let mut server = create_server(41324, 8);
+    let mut client = create_client(41324);
+
+    let simulated = update_until(&mut [&mut server, &mut client], |apps| {
+        count_server_players(apps[0]) == 1 && has_snapshot(apps[1])
+    });
+
+    assert!(
+        simulated,
+        "The server did not send a snapshot of the connected player"
+    );
+}
+
+#[test]
+fn full_server_rejects_players() {
+    set_asset_root(ASSET_ROOT).unwrap();
+    let runtime = Runtime::new().unwrap();
+    let _runtime_guard = runtime.enter();
+
+    let mut server = create_server(41325, 1);
+    let mut first_client = create_client(41325);
+
+    let joined = update_until(&mut [&mut server, &mut first_client], |apps| {
+        count_server_players(apps[0]) == 1
+    });
+    assert!(joined, "The first player did not join");
+
+    let mut second_client = create_client(41325);
+    let rejected = update_until(
+        &mut [&mut server, &mut first_client, &mut second_client],
+        |apps| {
+            apps[2]
+                .world()
+                .resource::<ConnectionFailedMessage>()
+                .0
+                .is_some()
+        },
+    );
+
+    assert!(rejected, "The second player was not rejected");
+    assert_eq!(
+        second_client
+            .world()
+            .resource::<ConnectionFailedMessage>()
+            .0
+            .as_deref(),
+        Some("The server is full")
+    );
+    assert_eq!(count_server_players(&mut server), 1);
+    assert!(
+        first_client
+            .world()
+            .resource::<ConnectionFailedMessage>()
+            .0
+            .is_none()
+    );
+}
+
+#[test]
+fn server_closes_kicked_sessions() {
+    set_asset_root(ASSET_ROOT).unwrap();
+    let runtime = Runtime::new().unwrap();
+    let _runtime_guard = runtime.enter();
+
+    let mut server = create_server(41326, 8);
+    let mut client = create_client(41326);
+
+    let joined = update_until(&mut [&mut server, &mut client], |apps| {
+        count_server_players(apps[0]) == 1
+    });
+    assert!(joined, "The player did not join");
+
+    let session_id = server
+        .world_mut()
+        .query_filtered::<&FromSession, With<ServerPlayer>>()
+        .single(server.world())
+        .unwrap()
+        .session_id;
+    server.world_mut().trigger(KickSession {
+        session_id,
+        reason: KickReason::KickedByHost,
+    });
+
+    // The client isn't updated anymore, so it can't leave on its own.
+    let closed = update_until(&mut [&mut server], |apps| {
+        count_server_sessions(apps[0]) == 0
+    });
+
+    assert!(closed, "The server did not close the kicked session");
+    assert_eq!(count_server_players(&mut server), 0);
+}
+
+#[test]
+fn server_exits_when_its_port_is_taken() {
+    set_asset_root(ASSET_ROOT).unwrap();
+    let runtime = Runtime::new().unwrap();
+    let _runtime_guard = runtime.enter();
+
+    let mut server = create_server(41327, 8);
+    let mut second_server = build_server(41327, 8);
+
+    let exited = update_until(&mut [&mut server, &mut second_server], |apps| {
+        apps[1].should_exit().is_some()
+    });
+
+    assert!(exited, "The second server did not exit");
+    assert_eq!(second_server.should_exit(), Some(AppExit::error()));
+    assert_ne!(
+        *second_server
+            .world()
+            .resource::<State<NetworkingState>>()
+            .get(),
+        NetworkingState::Host
+    );
+    // The world created for it is dropped again.
+    assert_eq!(
+        second_server
+            .world_mut()
+            .query::<&GenerationOptions>()
+            .iter(second_server.world())
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use networking::chat::{
+    ChatHistory, ChatMessageSender, MAX_CHAT_MESSAGE_LENGTH, SendChatMessage,
+};
+
+/// How many of the latest messages are shown while the input is closed.
+const CLOSED_MESSAGE_COUNT: usize = 8;
+
+#[derive(Default)]
+pub struct ChatWindowState {
+    open: bool,
+    input: String,
+}
+
+pub fn render_chat_window(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut chat_window_state: Local<ChatWindowState>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    chat_history: Single<&ChatHistory>,
+) -> Result {
+    let ctx = contexts.ctx_mut()?;
+
+    if !chat_window_state.open && keyboard_input.just_pressed(KeyCode::Enter) {
+        chat_window_state.open = true;
+        return Ok(());
+    }
+
+    if chat_window_state.open && keyboard_input.just_pressed(KeyCode::Escape) {
+        chat_window_state.open = false;
+        chat_window_state.input.clear();
+    }
+
+    let message_count = if chat_window_state.open {
+        chat_history.messages.len()
+    } else {
+        CLOSED_MESSAGE_COUNT
+    };
+    let skipped_messages =
+        chat_history.messages.len().saturating_sub(message_count);
+
+    egui::Window::new("Chat")
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(10., -10.))
+        .default_width(400.)
+        .title_bar(false)
+        .resizable(false)
+        .frame(if chat_window_state.open {
+            egui::Frame::window(&ctx.style())
+        } else {
+            egui::Frame::NONE
+        })
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(200.)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for message in
+                        chat_history.messages.iter().skip(skipped_messages)
+                    {
+                        ui.horizontal_wrapped(|ui| match &message.sender {
+                            ChatMessageSender::Player(player_profile) => {
+                                ui.colored_label(
+                                    player_profile.get_egui_color(),
+                                    format!("{}:", player_profile.name),
+                                );
+                                ui.label(&message.text);
+                            }
+                            ChatMessageSender::System => {
+                                ui.colored_label(
+                                    egui::Color32::YELLOW,
+                                    &message.text,
+                                );
+                            }
+                        });
+                    }
+                });
+
+            if !chat_window_state.open {
+                return;
+            }
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut chat_window_state.input)
+                    .char_limit(MAX_CHAT_MESSAGE_LENGTH)
+                    .hint_text("Press Enter to send")
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+
+            if ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                commands.trigger(SendChatMessage {
+                    text: std::mem::take(&mut chat_window_state.input),
+                });
+                chat_window_state.open = false;
+            }
+        });
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
 use bevy::{diagnostic::FrameTimeDiagnosticsPlugin, prelude::*};
+use bevy_egui::EguiPrimaryContextPass;
+use player::player_component::Player;
 use world_generation::world_ready::WorldReady;
 
 use crate::{
+    chat_window::render_chat_window,
     fps_text::{FpsText, update_fps_ui},
+    player_list::render_player_list,
     task_text::{ChunkTaskText, CountryTaskText, update_task_ui},
     triangle_count_text::{TriangleText, update_triangle_ui},
 };
@@ -16,6 +20,11 @@ impl Plugin for GameUiPlugin {
                 Update,
                 (update_fps_ui, update_task_ui, update_triangle_ui),
             )
+            .add_systems(
+                EguiPrimaryContextPass,
+                (render_chat_window, render_player_list)
+                    .run_if(any_with_component::<Player>),
+            )
             .add_observer(spawn_ui);
     }
 }
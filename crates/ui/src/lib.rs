@@ -1,4 +1,6 @@
+mod chat_window;
 mod fps_text;
 pub mod game_ui_plugin;
+mod player_list;
 mod task_text;
 mod triangle_count_text;
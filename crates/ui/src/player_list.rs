@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use bevy_hookup_core::from_session::FromSession;
+use networking::networking_state::NetworkingState;
+use player::{player_component::Player, player_profile::PlayerProfile};
+
+/// The host also has the snapshots it shares of every client,
+/// so only their players are counted.
+type ListedPlayers = Or<(With<Player>, With<FromSession>)>;
+
+/// Lists everyone in the session while Tab is held.
+pub fn render_player_list(
+    mut contexts: EguiContexts,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    networking_state: Res<State<NetworkingState>>,
+    players: Query<(&PlayerProfile, Has<Player>), ListedPlayers>,
+) -> Result {
+    if !keyboard_input.pressed(KeyCode::Tab) {
+        return Ok(());
+    }
+
+    let mut players = players.iter().collect::<Vec<_>>();
+    players.sort_by(|(a, a_is_local), (b, b_is_local)| {
+        b_is_local.cmp(a_is_local).then_with(|| a.name.cmp(&b.name))
+    });
+
+    egui::Window::new(format!("Players ({})", players.len()))
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0., 40.))
+        .collapsible(false)
+        .resizable(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            for (player_profile, is_local) in players {
+                let name = if is_local {
+                    format!("{} (you)", player_profile.name)
+                } else {
+                    player_profile.name.clone()
+                };
+
+                ui.colored_label(player_profile.get_egui_color(), name);
+            }
+
+            if *networking_state.get() == NetworkingState::Host {
+                ui.separator();
+                ui.weak("Kick a player with /kick <name>");
+            }
+        });
+
+    Ok(())
+}
@@ -1,12 +1,95 @@
-use std::{fs::File, io::Read};
+use std::{
+    fs::{File, create_dir_all, rename},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
-use serde::Deserialize;
+use anyhow::anyhow;
+
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+/// The directory assets are read from, unless [`set_asset_root`] was called.
+pub const DEFAULT_ASSET_ROOT: &str = "assets";
+
+static ASSET_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Tells apart the temporary files written at the same time.
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Points every asset read at another directory. It can only be set once,
+/// before the first asset gets read.
+pub fn set_asset_root(
+    asset_root: impl Into<PathBuf>,
+) -> Result<(), anyhow::Error> {
+    let asset_root = asset_root.into();
+    let current_root = ASSET_ROOT.get_or_init(|| asset_root.clone());
+
+    if *current_root != asset_root {
+        return Err(anyhow!(
+            "The asset root is already set to {:?}",
+            current_root
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn get_asset_root() -> &'static Path {
+    ASSET_ROOT.get_or_init(|| PathBuf::from(DEFAULT_ASSET_ROOT))
+}
+
+/// The path of an asset relative to the asset root.
+pub fn get_asset_path(path: impl AsRef<Path>) -> PathBuf {
+    get_asset_root().join(path)
+}
 
 pub fn read_ron_from_file<T: for<'a> Deserialize<'a>>(
-    filepath: &str,
+    filepath: impl AsRef<Path>,
 ) -> Result<T, anyhow::Error> {
     let mut file = File::open(filepath)?;
     let mut file_content = String::new();
     file.read_to_string(&mut file_content)?;
     Ok(ron::from_str::<T>(&file_content)?)
 }
+
+/// Creates the missing parent directories and replaces the file through
+/// [`write_bytes_to_file`], so a crash while writing keeps the old file.
+pub fn write_ron_to_file<T: Serialize>(
+    filepath: &str,
+    value: &T,
+) -> Result<(), anyhow::Error> {
+    if let Some(parent) = Path::new(filepath).parent() {
+        create_dir_all(parent)?;
+    }
+
+    let ron_string =
+        ron::ser::to_string_pretty(value, PrettyConfig::default())?;
+    write_bytes_to_file(filepath, ron_string.as_bytes())
+}
+
+/// Writes the bytes next to the file first and then replaces it, so a file
+/// that is only partly written never gets read. The temporary file is named
+/// after the process too, since several processes can share a directory.
+pub fn write_bytes_to_file(
+    filepath: impl AsRef<Path>,
+    bytes: &[u8],
+) -> Result<(), anyhow::Error> {
+    let filepath = filepath.as_ref();
+    let temp_filepath = filepath.with_extension(format!(
+        "{}.{}.tmp",
+        process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let mut file = File::create(&temp_filepath)?;
+    file.write_all(bytes)?;
+    file.flush()?;
+    rename(&temp_filepath, filepath)?;
+    Ok(())
+}
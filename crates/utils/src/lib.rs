@@ -10,4 +10,5 @@ pub mod div_floor;
 pub mod file_utils;
 pub mod math;
 pub mod rotation;
+pub mod stable_hash;
 pub mod vec_utils;
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chunk_generation::{
+    block_type::BlockType, structures::structure_generators::StructureKind,
+};
+
+/// One region of the climate map with its own blocks and structures.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Biome {
+    pub name: String,
+    /// Where the biome sits on the climate map.
+    pub temperature: f64,
+    pub humidity: f64,
+    /// Tinted blocks take their tint from the grass hue noise.
+    pub surface_block: BlockType,
+    pub subsurface_block: BlockType,
+    /// Voxels of the subsurface block below the surface block.
+    pub subsurface_depth: i32,
+    /// Slopes steeper than this are bare stone.
+    pub max_steepness: f64,
+    /// Added to the grass hue, blended across biome borders.
+    pub grass_hue_offset: f64,
+    pub structures: Vec<StructureKind>,
+    /// Chance for a structure to be placed where its noise allows one.
+    pub structure_density: f64,
+}
@@ -0,0 +1,53 @@
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+use crate::chunk_generation::biomes::biome::Biome;
+
+pub const BIOME_MAP_FILE_PATH: &str = "biomes.ron";
+
+/// All biomes of the world, placed on a temperature/humidity climate map.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BiomeMap {
+    /// How much further away on the climate map than the closest biome
+    /// another biome can be and still get mixed into a column.
+    pub blend_distance: f64,
+    /// How much colder it gets per voxel of terrain height.
+    pub temperature_falloff: f64,
+    pub biomes: Vec<Biome>,
+}
+
+impl BiomeMap {
+    /// The share every biome has at a point of the climate map.
+    /// Only biomes with a share above zero are returned, summing up to one.
+    pub fn get_weights(&self, climate: DVec2) -> Vec<(usize, f64)> {
+        let distances = self
+            .biomes
+            .iter()
+            .map(|biome| {
+                climate.distance(DVec2::new(biome.temperature, biome.humidity))
+            })
+            .collect::<Vec<_>>();
+
+        let min_distance =
+            distances.iter().copied().fold(f64::INFINITY, f64::min);
+
+        let mut weights = distances
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, distance)| {
+                let weight = 1.
+                    - (distance - min_distance)
+                        / self.blend_distance.max(f64::EPSILON);
+                (weight > 0.).then_some((index, weight))
+            })
+            .collect::<Vec<_>>();
+
+        let total_weight =
+            weights.iter().map(|(_, weight)| weight).sum::<f64>();
+        for (_, weight) in &mut weights {
+            *weight /= total_weight;
+        }
+
+        weights
+    }
+}
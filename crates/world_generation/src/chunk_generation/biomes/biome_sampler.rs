@@ -0,0 +1,156 @@
+use bevy::math::DVec2;
+
+use crate::{
+    chunk_generation::{
+        biomes::{biome::Biome, biome_map::BiomeMap},
+        block_type::BlockType,
+        noise::{noise_function::NoiseFunction, noise_result::NoiseResult},
+        structures::structure_generators::StructureKind,
+    },
+    generation_options::GenerationOptions,
+};
+
+type BoxedNoise = Box<dyn NoiseFunction<NoiseResult, [f64; 2]> + Send + Sync>;
+
+/// The blocks making up the top of a terrain column.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BiomeSurface {
+    pub surface_block: BlockType,
+    pub subsurface_block: BlockType,
+    pub subsurface_depth: i32,
+}
+
+/// Looks up the biome of terrain columns. Positions and heights are in
+/// voxels of the full LOD.
+pub struct BiomeSampler<'a> {
+    biome_map: &'a BiomeMap,
+    temperature_noise: BoxedNoise,
+    humidity_noise: BoxedNoise,
+    grass_hue_noise: BoxedNoise,
+}
+
+impl<'a> BiomeSampler<'a> {
+    pub fn new(generation_options: &'a GenerationOptions) -> Self {
+        let terrain_noise_group = &generation_options.terrain_noise_group;
+
+        Self {
+            biome_map: &generation_options.biome_map,
+            temperature_noise: terrain_noise_group
+                .temperature
+                .get_noise_fn(&mut generation_options.get_seeded_rng()),
+            humidity_noise: terrain_noise_group
+                .humidity
+                .get_noise_fn(&mut generation_options.get_seeded_rng()),
+            grass_hue_noise: terrain_noise_group
+                .grass_hue
+                .get_noise_fn(&mut generation_options.get_seeded_rng()),
+        }
+    }
+
+    /// Temperature and humidity of a column. Higher terrain is colder.
+    pub fn get_climate(&self, position: [f64; 2], height: f32) -> DVec2 {
+        DVec2::new(
+            self.temperature_noise.get(position).value
+                - height as f64 * self.biome_map.temperature_falloff,
+            self.humidity_noise.get(position).value,
+        )
+    }
+
+    pub fn get_biome(&self, position: [f64; 2], height: f32) -> &'a Biome {
+        let weights = self
+            .biome_map
+            .get_weights(self.get_climate(position, height));
+
+        self.pick_biome(position, &weights)
+    }
+
+    pub fn get_surface(
+        &self,
+        position: [f64; 2],
+        height: f32,
+        steepness: f64,
+    ) -> BiomeSurface {
+        let weights = self
+            .biome_map
+            .get_weights(self.get_climate(position, height));
+        let biome = self.pick_biome(position, &weights);
+
+        if steepness >= biome.max_steepness {
+            return BiomeSurface {
+                surface_block: BlockType::STONE,
+                subsurface_block: BlockType::STONE,
+                subsurface_depth: 0,
+            };
+        }
+
+        let surface_block = match biome.surface_block {
+            block if block.is_tinted() => {
+                let hue_offset = weights
+                    .iter()
+                    .map(|(index, weight)| {
+                        self.biome_map.biomes[*index].grass_hue_offset * weight
+                    })
+                    .sum::<f64>();
+                let hue = self.grass_hue_noise.get(position).value + hue_offset;
+
+                block.with_tint(hue.clamp(0., 255.) as u8)
+            }
+            block => block,
+        };
+
+        BiomeSurface {
+            surface_block,
+            subsurface_block: biome.subsurface_block,
+            subsurface_depth: biome.subsurface_depth,
+        }
+    }
+
+    /// Whether the biome at the center of a structure wants it there.
+    pub fn allows_structure(
+        &self,
+        center: [f64; 2],
+        height: f32,
+        structure_kind: StructureKind,
+    ) -> bool {
+        let biome = self.get_biome(center, height);
+
+        biome.structures.contains(&structure_kind)
+            && get_column_random(center, structure_kind as u64 + 1)
+                < biome.structure_density
+    }
+
+    /// Picks one of the blended biomes per column, so borders dither
+    /// into each other instead of forming a hard line.
+    fn pick_biome(
+        &self,
+        position: [f64; 2],
+        weights: &[(usize, f64)],
+    ) -> &'a Biome {
+        let mut threshold = get_column_random(position, 0);
+
+        for (index, weight) in weights {
+            if threshold < *weight {
+                return &self.biome_map.biomes[*index];
+            }
+            threshold -= weight;
+        }
+
+        let (index, _) = weights.last().expect("Biome map has no biomes");
+        &self.biome_map.biomes[*index]
+    }
+}
+
+/// A random value from 0 to 1 that stays the same for a column.
+fn get_column_random(position: [f64; 2], salt: u64) -> f64 {
+    let mut hash = (position[0].floor() as i64 as u64)
+        .wrapping_mul(0x9E3779B97F4A7C15)
+        ^ (position[1].floor() as i64 as u64).wrapping_mul(0xC2B2AE3D27D4EB4F)
+        ^ salt.wrapping_mul(0x165667B19E3779F9);
+
+    // Finalizer of SplitMix64
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D049BB133111EB);
+    hash ^= hash >> 31;
+
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
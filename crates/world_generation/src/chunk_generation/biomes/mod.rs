@@ -0,0 +1,3 @@
+pub mod biome;
+pub mod biome_map;
+pub mod biome_sampler;
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use anyhow::{Context, anyhow};
+use bevy::color::Color;
+use serde::{Deserialize, Serialize};
+use utils::file_utils::{get_asset_path, read_ron_from_file};
+
+use crate::chunk_generation::{
+    block_type::{BlockFace, BlockId, BlockType},
+    mesh_type::MeshType,
+};
+
+pub const BLOCK_REGISTRY_FILE_PATH: &str = "blocks.ron";
+
+static BLOCK_REGISTRY: OnceLock<BlockRegistry> = OnceLock::new();
+
+/// Loads and checks the block asset, unless that already happened.
+/// Has to run before the first block gets looked up, the world generation
+/// plugin does it while it gets built.
+pub fn load_block_registry() -> Result<&'static BlockRegistry, anyhow::Error> {
+    if let Some(block_registry) = BLOCK_REGISTRY.get() {
+        return Ok(block_registry);
+    }
+
+    let path = get_asset_path(BLOCK_REGISTRY_FILE_PATH);
+    let block_registry = read_ron_from_file(&path)
+        .and_then(BlockRegistry::new)
+        .with_context(|| {
+            format!("Failed loading the block registry {}", path.display())
+        })?;
+
+    Ok(BLOCK_REGISTRY.get_or_init(|| block_registry))
+}
+
+/// Every block the game knows about, once [`load_block_registry`] ran.
+pub fn get_block_registry() -> &'static BlockRegistry {
+    BLOCK_REGISTRY
+        .get()
+        .expect("The block registry has to be loaded before blocks are used")
+}
+
+/// The blocks world generation places by itself. They have to be the first
+/// entries of the registry, in this order.
+const BUILT_IN_BLOCKS: [(BlockType, &str); 10] = [
+    (BlockType::AIR, "air"),
+    (BlockType::STONE, "stone"),
+    (BlockType::GRASS, "grass"),
+    (BlockType::LOG, "log"),
+    (BlockType::PINE_LOG, "pine_log"),
+    (BlockType::SNOW, "snow"),
+    (BlockType::LEAF, "leaf"),
+    (BlockType::PINE_NEEDLE, "pine_needle"),
+    (BlockType::DIRT, "dirt"),
+    (BlockType::WATER, "water"),
+];
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BlockDefinition {
+    pub name: String,
+    pub appearance: BlockAppearance,
+    /// Texture layers drawn over the colour on close chunks.
+    #[serde(default)]
+    pub textures: Option<FaceTextures>,
+    pub mesh_type: MeshType,
+    /// Whether physics objects collide with the block.
+    pub collision: bool,
+    /// Which neighbouring faces the block hides.
+    pub covering: BlockCovering,
+    /// How hard the block is to break.
+    pub hardness: f32,
+    /// Whether players can pick the block to place it.
+    pub placeable: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum BlockAppearance {
+    Color(Color),
+    /// Mixes between both colours by the tint of the block, like the hue of
+    /// grass.
+    Tinted(Color, Color),
+}
+
+/// Layers of the block texture array, per face of the block.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct FaceTextures {
+    pub top: u32,
+    pub side: u32,
+    pub bottom: u32,
+}
+
+impl FaceTextures {
+    pub fn get_layer(&self, face: BlockFace) -> u32 {
+        match face {
+            BlockFace::Top => self.top,
+            BlockFace::Bottom => self.bottom,
+            _ => self.side,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum BlockCovering {
+    /// Hides the faces of every block next to it.
+    All,
+    /// Only hides the faces of the same block, like water next to water.
+    SameBlock,
+    /// Never hides a face.
+    None,
+}
+
+pub struct BlockRegistry {
+    blocks: Vec<BlockDefinition>,
+    ids_by_name: HashMap<String, BlockId>,
+}
+
+impl BlockRegistry {
+    pub fn new(blocks: Vec<BlockDefinition>) -> Result<Self, anyhow::Error> {
+        if blocks.len() > BlockId::MAX as usize + 1 {
+            return Err(anyhow!(
+                "The block registry can't hold more than {} blocks",
+                BlockId::MAX as usize + 1
+            ));
+        }
+
+        for (block, name) in BUILT_IN_BLOCKS {
+            if blocks.get(block.id() as usize).map(|block| &block.name[..])
+                != Some(name)
+            {
+                return Err(anyhow!(
+                    "Block {} has to be registered with the id {}",
+                    name,
+                    block.id()
+                ));
+            }
+        }
+
+        let ids_by_name = blocks
+            .iter()
+            .enumerate()
+            .map(|(id, block)| (block.name.clone(), id as BlockId))
+            .collect();
+
+        Ok(Self {
+            blocks,
+            ids_by_name,
+        })
+    }
+
+    pub fn get(&self, id: BlockId) -> &BlockDefinition {
+        &self.blocks[id as usize]
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<BlockId> {
+        self.ids_by_name.get(name).copied()
+    }
+
+    /// The blocks players can place, in the order of the registry. Tinted
+    /// blocks get placed with the colour between both of theirs.
+    pub fn get_placeable_blocks(&self) -> Vec<BlockType> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| block.placeable)
+            .map(|(id, block)| {
+                let block_type = BlockType::from_id(id as BlockId);
+                match block.appearance {
+                    BlockAppearance::Tinted(..) => block_type.with_tint(128),
+                    _ => block_type,
+                }
+            })
+            .collect()
+    }
+}
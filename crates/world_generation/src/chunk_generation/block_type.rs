@@ -1,19 +1,59 @@
-use bevy::color::{Color, Mix};
+use bevy::{
+    color::{Color, Mix},
+    math::IVec3,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::chunk_generation::mesh_type::MeshType;
+use crate::chunk_generation::{
+    block_registry::{
+        BlockAppearance, BlockCovering, BlockDefinition, get_block_registry,
+    },
+    mesh_type::MeshType,
+};
 
+/// The id of a block in the [`BlockRegistry`](super::block_registry::BlockRegistry).
+pub type BlockId = u8;
+
+/// A block in the world. Everything else about it is looked up in the
+/// block registry, so it stays as small as possible in `VoxelData`.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
-pub enum BlockType {
-    Air,
-    Stone,
-    Grass(u8),
-    Log,
-    PineLog,
-    Snow,
-    Leaf,
-    PineNeedle,
-    Dirt,
+#[serde(into = "NamedBlock", try_from = "NamedBlock")]
+pub struct BlockType {
+    id: BlockId,
+    /// Picks the colour of tinted blocks, zero for all others.
+    tint: u8,
+}
+
+/// Blocks are saved and sent by name, so adding blocks to the registry
+/// doesn't change the meaning of existing saves.
+#[derive(Serialize, Deserialize)]
+struct NamedBlock {
+    name: String,
+    tint: u8,
+}
+
+impl From<BlockType> for NamedBlock {
+    fn from(block: BlockType) -> Self {
+        Self {
+            name: block.get_definition().name.clone(),
+            tint: block.tint,
+        }
+    }
+}
+
+impl TryFrom<NamedBlock> for BlockType {
+    type Error = String;
+
+    fn try_from(named_block: NamedBlock) -> Result<Self, Self::Error> {
+        let id = get_block_registry()
+            .get_id(&named_block.name)
+            .ok_or_else(|| format!("Unknown block {}", named_block.name))?;
+
+        Ok(Self {
+            id,
+            tint: named_block.tint,
+        })
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
@@ -26,45 +66,113 @@ pub enum BlockFace {
     Left,
 }
 
-impl BlockType {
-    pub fn is_covering_for(&self, other: &BlockType) -> bool {
-        if self == other {
-            return true;
+impl BlockFace {
+    pub fn from_direction(direction: IVec3) -> Option<Self> {
+        match direction {
+            IVec3::Y => Some(Self::Top),
+            IVec3::NEG_Y => Some(Self::Bottom),
+            IVec3::Z => Some(Self::Front),
+            IVec3::NEG_Z => Some(Self::Back),
+            IVec3::X => Some(Self::Right),
+            IVec3::NEG_X => Some(Self::Left),
+            _ => None,
         }
+    }
 
+    pub fn to_direction(&self) -> IVec3 {
         match self {
-            BlockType::Air => false,
-            _ => true,
+            Self::Top => IVec3::Y,
+            Self::Bottom => IVec3::NEG_Y,
+            Self::Front => IVec3::Z,
+            Self::Back => IVec3::NEG_Z,
+            Self::Right => IVec3::X,
+            Self::Left => IVec3::NEG_X,
         }
     }
+}
+
+impl BlockType {
+    pub const AIR: Self = Self::from_id(0);
+    pub const STONE: Self = Self::from_id(1);
+    pub const GRASS: Self = Self::from_id(2);
+    pub const LOG: Self = Self::from_id(3);
+    pub const PINE_LOG: Self = Self::from_id(4);
+    pub const SNOW: Self = Self::from_id(5);
+    pub const LEAF: Self = Self::from_id(6);
+    pub const PINE_NEEDLE: Self = Self::from_id(7);
+    pub const DIRT: Self = Self::from_id(8);
+    pub const WATER: Self = Self::from_id(9);
+
+    pub const fn from_id(id: BlockId) -> Self {
+        Self { id, tint: 0 }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        get_block_registry().get_id(name).map(Self::from_id)
+    }
+
+    pub fn id(&self) -> BlockId {
+        self.id
+    }
 
+    pub fn tint(&self) -> u8 {
+        self.tint
+    }
+
+    pub fn with_tint(self, tint: u8) -> Self {
+        Self { tint, ..self }
+    }
+
+    pub fn get_definition(&self) -> &'static BlockDefinition {
+        get_block_registry().get(self.id)
+    }
+
+    pub fn is_tinted(&self) -> bool {
+        matches!(
+            self.get_definition().appearance,
+            BlockAppearance::Tinted(..)
+        )
+    }
+
+    pub fn is_covering_for(&self, other: &BlockType) -> bool {
+        match self.get_definition().covering {
+            BlockCovering::All => true,
+            BlockCovering::SameBlock => self.id == other.id,
+            BlockCovering::None => false,
+        }
+    }
+
+    /// Whether the block darkens the corners next to it.
+    pub fn is_occluding(&self) -> bool {
+        self.get_mesh_type() == MeshType::Opaque
+    }
+
+    /// Textures only add detail on top of this colour, so far chunks look the
+    /// same without them.
     pub fn get_color(&self) -> Color {
-        match self {
-            BlockType::Air => Color::NONE,
-            BlockType::Stone => Color::linear_rgb(0.2, 0.2, 0.2),
-            // BlockType::Grass(hue) => Color::oklch(0.7764, 0.1708, 65.16)
-            //     .mix(&Color::oklch(0.7764, 0.146, 227.64), *hue as f32 / 255.),
-            BlockType::Grass(hue) => Color::hsl(33., 0.7, 0.5)
-                .mix(&Color::hsl(170., 0.66, 0.5), *hue as f32 / 255.),
-            BlockType::Log => Color::linear_rgb(0.3, 0.15, 0.0),
-            BlockType::Snow => Color::linear_rgb(0.9, 0.9, 0.9),
-            BlockType::Leaf => Color::linear_rgb(0.2, 0.5, 0.2),
-            BlockType::Dirt => Color::linear_rgb(0.3, 0.2, 0.0),
-            BlockType::PineLog => Color::linear_rgb(0.0075, 0.002, 0.0),
-            BlockType::PineNeedle => Color::linear_rgb(0.003, 0.015, 0.002),
+        match self.get_definition().appearance {
+            BlockAppearance::Color(color) => color,
+            BlockAppearance::Tinted(from, to) => {
+                from.mix(&to, self.tint as f32 / 255.)
+            }
         }
     }
 
+    pub fn get_texture_layer(&self, face: BlockFace) -> Option<u32> {
+        self.get_definition()
+            .textures
+            .map(|textures| textures.get_layer(face))
+    }
+
     pub fn get_mesh_type(&self) -> MeshType {
-        match self {
-            _ => MeshType::Opaque,
-        }
+        self.get_definition().mesh_type
     }
 
     pub fn is_colliding(&self) -> bool {
-        match self {
-            BlockType::Air => false,
-            _ => true,
-        }
+        self.get_definition().collision
+    }
+
+    pub fn get_hardness(&self) -> f32 {
+        self.get_definition().hardness
     }
 }
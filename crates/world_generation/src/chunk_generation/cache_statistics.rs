@@ -0,0 +1,62 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Counters of a cache that can be shared between the chunk tasks and read
+/// by the diagnostics every frame.
+#[derive(Default)]
+pub struct CacheStatistics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    entries: AtomicUsize,
+    memory_size: AtomicUsize,
+}
+
+impl CacheStatistics {
+    pub const fn new() -> Self {
+        Self {
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            entries: AtomicUsize::new(0),
+            memory_size: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn add_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add_entry(&self, memory_size: usize) {
+        self.entries.fetch_add(1, Ordering::Relaxed);
+        self.memory_size.fetch_add(memory_size, Ordering::Relaxed);
+    }
+
+    pub fn remove_entry(&self, memory_size: usize) {
+        self.entries.fetch_sub(1, Ordering::Relaxed);
+        self.memory_size.fetch_sub(memory_size, Ordering::Relaxed);
+    }
+
+    pub fn get_entries(&self) -> usize {
+        self.entries.load(Ordering::Relaxed)
+    }
+
+    /// The estimated amount of bytes the entries take up.
+    pub fn get_memory_size(&self) -> usize {
+        self.memory_size.load(Ordering::Relaxed)
+    }
+
+    /// Returns the share of lookups that were hits since the last call, or
+    /// [`None`] if there were no lookups.
+    pub fn take_hit_rate(&self) -> Option<f64> {
+        let hits = self.hits.swap(0, Ordering::Relaxed);
+        let misses = self.misses.swap(0, Ordering::Relaxed);
+
+        if hits + misses == 0 {
+            return None;
+        }
+
+        Some(hits as f64 / (hits + misses) as f64)
+    }
+}
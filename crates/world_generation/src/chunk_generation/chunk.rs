@@ -10,4 +10,5 @@ pub struct Chunk {
     pub lod_position: LodPosition,
     pub generate_above: bool,
     pub chunk_height: i32,
+    pub min_height: i32,
 }
@@ -1,16 +1,32 @@
 use bevy::prelude::*;
+use physics::physics_systems::PhysicsSystems;
 
 use crate::{
     chunk_generation::{
+        chunk_mesh_cache::setup_chunk_mesh_cache,
+        chunk_mesh_mode::ChunkMeshMode,
         chunk_start::queue_chunk_tasks,
-        chunk_task::{set_generated_chunks, ChunkTaskPool},
+        chunk_task::{ChunkTaskPool, set_generated_chunks},
         chunk_triangles::ChunkTriangles,
         country::{
-            cache_generation_task::{set_generated_caches, CacheTaskPool},
+            cache_generation_task::{CacheTaskPool, set_generated_caches},
             country_cache::CountryCache,
+            country_disk_cache::setup_country_disk_cache,
         },
+        disk_cache_settings::DiskCacheSettings,
+        generation_cache_diagnostics::GenerationCacheDiagnosticsPlugin,
+        settlements::building_model_loading::{
+            building_models_loaded, load_building_models,
+            set_building_templates,
+        },
+        structures::{
+            structure_model::{StructureModel, StructureModelLoader},
+            vox_structure::{VoxStructure, VoxStructureLoader},
+        },
+        water::water_overlap::update_in_water,
     },
     chunk_loading::chunk_loader_plugin::ChunkLoaderPlugin,
+    terrain_material::load_block_textures,
     world_generation_state::WorldGenerationState,
 };
 
@@ -18,22 +34,38 @@ pub struct ChunkGenerationPlugin;
 
 impl Plugin for ChunkGenerationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ChunkLoaderPlugin)
+        app.add_plugins((ChunkLoaderPlugin, GenerationCacheDiagnosticsPlugin))
+            .init_asset::<VoxStructure>()
+            .init_asset::<StructureModel>()
+            .init_asset_loader::<VoxStructureLoader>()
+            .init_asset_loader::<StructureModelLoader>()
             .init_resource::<ChunkTriangles>()
             .init_resource::<ChunkTaskPool>()
             .init_resource::<CacheTaskPool>()
             .init_resource::<CountryCache>()
+            .init_resource::<ChunkMeshMode>()
+            .init_resource::<DiskCacheSettings>()
             .register_type::<ChunkTriangles>()
+            .add_systems(Startup, load_block_textures)
             .add_systems(
                 Update,
                 (
-                    queue_chunk_tasks.run_if(
-                        in_state(WorldGenerationState::InitialGeneration)
-                            .or(in_state(WorldGenerationState::Active)),
+                    (load_building_models, set_building_templates).chain(),
+                    // The caches are keyed by the building templates too.
+                    (setup_chunk_mesh_cache, setup_country_disk_cache)
+                        .after(set_building_templates)
+                        .before(queue_chunk_tasks)
+                        .run_if(building_models_loaded),
+                    queue_chunk_tasks.after(set_building_templates).run_if(
+                        building_models_loaded.and(
+                            in_state(WorldGenerationState::InitialGeneration)
+                                .or(in_state(WorldGenerationState::Active)),
+                        ),
                     ),
                     set_generated_chunks,
                     set_generated_caches,
                 ),
-            );
+            )
+            .add_systems(FixedUpdate, update_in_water.before(PhysicsSystems));
     }
 }
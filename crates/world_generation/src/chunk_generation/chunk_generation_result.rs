@@ -1,5 +1,7 @@
+use std::sync::Arc;
+
 use crate::{
-    chunk_generation::mesh_generation::MeshResult,
+    chunk_generation::{mesh_generation::MeshResult, voxel_data::VoxelData},
     chunk_loading::{chunk_tree::ChunkTreePos, lod_position::LodPosition},
 };
 
@@ -10,4 +12,8 @@ pub struct ChunkGenerationResult {
     pub chunk_tree_position: ChunkTreePos,
     pub chunk_stack_offset: i32,
     pub chunk_min_height: i32,
+    /// Only kept for full detail chunks, so they can be edited later.
+    pub voxel_data: Option<Arc<VoxelData>>,
+    /// The revision of the `VoxelDeltaStore` the chunk was generated with.
+    pub delta_revision: u64,
 }
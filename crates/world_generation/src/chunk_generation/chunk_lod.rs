@@ -1,4 +1,6 @@
 pub const MAX_LOD: ChunkLod = ChunkLod::TwoFiftySix;
+/// Farther chunks only use block colours, as textures would blur into noise.
+pub const MAX_TEXTURED_LOD: ChunkLod = ChunkLod::Half;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum ChunkLod {
@@ -0,0 +1,318 @@
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+    prelude::*,
+};
+use physics::collider::Collider;
+use serde::{Deserialize, Serialize};
+use utils::{
+    file_utils::{get_asset_path, write_bytes_to_file},
+    stable_hash::get_stable_hash,
+};
+
+use crate::{
+    chunk_generation::{
+        block_registry::BLOCK_REGISTRY_FILE_PATH,
+        chunk_generation_result::ChunkGenerationResult,
+        chunk_mesh_mode::ChunkMeshMode, disk_cache_settings::DiskCacheSettings,
+        mesh_generation::MeshResult, voxel_data::VoxelData,
+    },
+    chunk_loading::{chunk_tree::ChunkTreePos, lod_position::LodPosition},
+    generation_options::GenerationOptions,
+};
+
+/// The folder of the chunk mesh cache inside the cache directory.
+pub const CHUNK_MESH_CACHE_DIRECTORY: &str = "chunk_meshes";
+
+/// Goes into the settings hash, so bumping it starts a new folder. Needed
+/// whenever meshing, voxel generation or the layout of cached chunks changes.
+pub const CHUNK_MESH_CACHE_VERSION: u32 = 1;
+
+/// Generated chunks on disk, so they don't have to be generated from noise
+/// again in later sessions or when the chunk tree merges and splits.
+///
+/// Every world gets a folder named after its seed and one inside it for each
+/// mesh mode, so a client and a server sharing the cache keep their own
+/// chunks. In there is one folder for the hash of everything the chunks are
+/// generated from. When any of it changes, like the terrain noise or the
+/// block registry, the hash changes as well and the old folder gets deleted.
+#[derive(Resource, Clone)]
+pub struct ChunkMeshCache {
+    directory: Arc<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedChunk {
+    opaque_mesh: Option<CachedMesh>,
+    transparent_mesh: Option<CachedMesh>,
+    cutout_mesh: Option<CachedMesh>,
+    collider: Option<Collider>,
+    /// Only kept for full detail chunks, like in [`ChunkGenerationResult`].
+    voxel_data: Option<VoxelData>,
+    min_height: i32,
+    generate_above: bool,
+}
+
+/// The vertex buffers of a chunk mesh.
+#[derive(Serialize, Deserialize)]
+struct CachedMesh {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    colors: Vec<[f32; 4]>,
+    texture_layers: Option<Vec<[f32; 2]>>,
+    indices: Vec<u32>,
+}
+
+impl ChunkMeshCache {
+    pub fn new(
+        cache_directory: &str,
+        generation_options: &GenerationOptions,
+        chunk_mesh_mode: ChunkMeshMode,
+    ) -> Result<Self, anyhow::Error> {
+        let mode_directory = Path::new(cache_directory)
+            .join(CHUNK_MESH_CACHE_DIRECTORY)
+            .join(generation_options.seed.to_string())
+            .join(chunk_mesh_mode.get_name());
+        let settings_hash = get_settings_hash(generation_options)?;
+        let directory = mode_directory.join(format!("{:016x}", settings_hash));
+
+        remove_stale_caches(&mode_directory, &directory)?;
+        fs::create_dir_all(&directory)?;
+
+        Ok(Self {
+            directory: Arc::new(directory),
+        })
+    }
+
+    /// Returns [`None`] if the chunk wasn't cached yet or its file is broken.
+    pub fn load(
+        &self,
+        chunk_pos: LodPosition,
+        tree_pos: ChunkTreePos,
+        stack_offset: i32,
+        delta_revision: u64,
+    ) -> Option<ChunkGenerationResult> {
+        let path = self.get_chunk_path(chunk_pos, tree_pos, stack_offset);
+
+        let mut bytes = Vec::new();
+        File::open(&path).ok()?.read_to_end(&mut bytes).ok()?;
+
+        let cached_chunk = match bincode::serde::decode_from_slice::<
+            CachedChunk,
+            _,
+        >(&bytes, bincode::config::standard())
+        {
+            Ok((cached_chunk, _)) => cached_chunk,
+            Err(err) => {
+                warn!("Removing broken cached chunk {:?}: {}", path, err);
+                let _ = fs::remove_file(&path);
+                return None;
+            }
+        };
+
+        Some(ChunkGenerationResult {
+            mesh_result: MeshResult {
+                opaque_mesh: cached_chunk
+                    .opaque_mesh
+                    .map(CachedMesh::into_mesh),
+                transparent_mesh: cached_chunk
+                    .transparent_mesh
+                    .map(CachedMesh::into_mesh),
+                cutout_mesh: cached_chunk
+                    .cutout_mesh
+                    .map(CachedMesh::into_mesh),
+                collider: cached_chunk.collider,
+            },
+            generate_above: cached_chunk.generate_above,
+            chunk_pos,
+            chunk_tree_position: tree_pos,
+            chunk_stack_offset: stack_offset,
+            chunk_min_height: cached_chunk.min_height,
+            voxel_data: cached_chunk.voxel_data.map(Arc::new),
+            delta_revision,
+        })
+    }
+
+    pub fn store(
+        &self,
+        chunk_generation_result: &ChunkGenerationResult,
+    ) -> Result<(), anyhow::Error> {
+        let mesh_result = &chunk_generation_result.mesh_result;
+        let cached_chunk = CachedChunk {
+            opaque_mesh: CachedMesh::from_mesh(
+                mesh_result.opaque_mesh.as_ref(),
+            ),
+            transparent_mesh: CachedMesh::from_mesh(
+                mesh_result.transparent_mesh.as_ref(),
+            ),
+            cutout_mesh: CachedMesh::from_mesh(
+                mesh_result.cutout_mesh.as_ref(),
+            ),
+            collider: mesh_result.collider.clone(),
+            voxel_data: chunk_generation_result.voxel_data.as_deref().cloned(),
+            min_height: chunk_generation_result.chunk_min_height,
+            generate_above: chunk_generation_result.generate_above,
+        };
+
+        let bytes = bincode::serde::encode_to_vec(
+            &cached_chunk,
+            bincode::config::standard(),
+        )?;
+
+        let path = self.get_chunk_path(
+            chunk_generation_result.chunk_pos,
+            chunk_generation_result.chunk_tree_position,
+            chunk_generation_result.chunk_stack_offset,
+        );
+
+        write_bytes_to_file(path, &bytes)
+    }
+
+    fn get_chunk_path(
+        &self,
+        chunk_pos: LodPosition,
+        tree_pos: ChunkTreePos,
+        stack_offset: i32,
+    ) -> PathBuf {
+        self.directory.join(format!(
+            "{}_{}_{}_{}_{}_{}.bin",
+            tree_pos.x,
+            tree_pos.y,
+            chunk_pos.lod.usize(),
+            chunk_pos.relative_position.x,
+            chunk_pos.relative_position.y,
+            stack_offset
+        ))
+    }
+}
+
+impl CachedMesh {
+    /// Returns [`None`] for missing meshes and meshes that weren't built
+    /// by the chunk mesh generation.
+    fn from_mesh(mesh: Option<&Mesh>) -> Option<Self> {
+        let mesh = mesh?;
+
+        let (
+            Some(VertexAttributeValues::Float32x3(positions)),
+            Some(VertexAttributeValues::Float32x3(normals)),
+            Some(VertexAttributeValues::Float32x2(uvs)),
+            Some(VertexAttributeValues::Float32x4(colors)),
+            Some(Indices::U32(indices)),
+        ) = (
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+            mesh.attribute(Mesh::ATTRIBUTE_UV_0),
+            mesh.attribute(Mesh::ATTRIBUTE_COLOR),
+            mesh.indices(),
+        )
+        else {
+            return None;
+        };
+
+        let texture_layers = match mesh.attribute(Mesh::ATTRIBUTE_UV_1) {
+            Some(VertexAttributeValues::Float32x2(texture_layers)) => {
+                Some(texture_layers.clone())
+            }
+            _ => None,
+        };
+
+        Some(Self {
+            positions: positions.clone(),
+            normals: normals.clone(),
+            uvs: uvs.clone(),
+            colors: colors.clone(),
+            texture_layers,
+            indices: indices.clone(),
+        })
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::all(),
+        );
+
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+
+        if let Some(texture_layers) = self.texture_layers {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, texture_layers);
+        }
+
+        mesh.insert_indices(Indices::U32(self.indices));
+        mesh
+    }
+}
+
+/// A hash of everything cached chunks depend on, which stays the same
+/// between sessions and builds.
+fn get_settings_hash(
+    generation_options: &GenerationOptions,
+) -> Result<u64, anyhow::Error> {
+    let generation_options_hash = generation_options.get_stable_hash()?;
+    let block_registry = fs::read(get_asset_path(BLOCK_REGISTRY_FILE_PATH))?;
+
+    Ok(get_stable_hash([
+        &CHUNK_MESH_CACHE_VERSION.to_le_bytes()[..],
+        &generation_options_hash.to_le_bytes(),
+        &block_registry,
+    ]))
+}
+
+/// Deletes the caches of the world and mesh mode that were made with other
+/// settings.
+fn remove_stale_caches(
+    mode_directory: &Path,
+    current_directory: &Path,
+) -> Result<(), anyhow::Error> {
+    if !mode_directory.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(mode_directory)? {
+        let path = entry?.path();
+        if path != current_directory && path.is_dir() {
+            info!("Removing stale chunk cache {:?}", path);
+            fs::remove_dir_all(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn setup_chunk_mesh_cache(
+    mut commands: Commands,
+    added_generation_options: Query<
+        &GenerationOptions,
+        Added<GenerationOptions>,
+    >,
+    disk_cache_settings: Res<DiskCacheSettings>,
+    chunk_mesh_mode: Res<ChunkMeshMode>,
+) {
+    for generation_options in &added_generation_options {
+        commands.remove_resource::<ChunkMeshCache>();
+
+        if !disk_cache_settings.enabled {
+            continue;
+        }
+
+        match ChunkMeshCache::new(
+            &disk_cache_settings.cache_directory,
+            generation_options,
+            *chunk_mesh_mode,
+        ) {
+            Ok(chunk_mesh_cache) => commands.insert_resource(chunk_mesh_cache),
+            Err(err) => error!("Failed setting up chunk mesh cache: {}", err),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::chunk_generation::{
+    chunk_lod::ChunkLod,
+    mesh_generation::{MeshResult, generate_collider, generate_mesh},
+    voxel_data::VoxelData,
+};
+
+/// Decides which parts of a chunk get built after its voxels are generated.
+/// A headless server has nothing to render, so it only needs the colliders.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChunkMeshMode {
+    #[default]
+    Render,
+    ColliderOnly,
+}
+
+impl ChunkMeshMode {
+    /// Names the folder of the chunk mesh cache for this mode.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ChunkMeshMode::Render => "render",
+            ChunkMeshMode::ColliderOnly => "collider_only",
+        }
+    }
+
+    pub fn generate_mesh(
+        &self,
+        voxel_data: &VoxelData,
+        chunk_lod: ChunkLod,
+    ) -> MeshResult {
+        match self {
+            ChunkMeshMode::Render => generate_mesh(voxel_data, chunk_lod),
+            ChunkMeshMode::ColliderOnly => {
+                generate_collider(voxel_data, chunk_lod)
+            }
+        }
+    }
+}
@@ -1,19 +1,26 @@
+use std::sync::Arc;
+
 use bevy::prelude::*;
 use itertools::Itertools;
 
 use crate::{
     chunk_generation::{
         chunk_generation_result::ChunkGenerationResult,
+        chunk_lod::ChunkLod,
+        chunk_mesh_cache::ChunkMeshCache,
+        chunk_mesh_mode::ChunkMeshMode,
         chunk_task::{ChunkTask, ChunkTaskPool},
         country::{
             cache_generation_task::CacheTaskPool, country_cache::CountryCache,
             country_cache_position::CountryPosition, country_data::CountryData,
         },
-        mesh_generation::generate_mesh,
         voxel_generation::generate_voxels,
     },
     chunk_loading::{chunk_tree::ChunkTreePos, lod_position::LodPosition},
     generation_options::GenerationOptions,
+    world_save::voxel_delta_store::{
+        ChunkDelta, VoxelDeltaStore, apply_voxel_deltas,
+    },
 };
 
 #[derive(Component)]
@@ -31,6 +38,9 @@ pub fn queue_chunk_tasks(
     chunk_tasks: Query<(), With<ChunkTask>>,
     chunk_task_pool: Res<ChunkTaskPool>,
     cache_task_pool: Res<CacheTaskPool>,
+    voxel_delta_store: Res<VoxelDeltaStore>,
+    chunk_mesh_mode: Res<ChunkMeshMode>,
+    chunk_mesh_cache: Option<Res<ChunkMeshCache>>,
 ) {
     let current_task_count = chunk_tasks.iter().count();
 
@@ -67,14 +77,48 @@ pub fn queue_chunk_tasks(
         let lod_pos = chunk_start.chunk_lod_pos;
         let tree_pos = chunk_start.chunk_tree_pos;
         let stack_height = chunk_start.chunk_stack_offset;
+        let chunk_deltas =
+            voxel_delta_store.get_deltas_for_chunk(chunk_pos, lod_pos.lod);
+        let delta_revision = voxel_delta_store.get_revision();
+        let chunk_mesh_mode = *chunk_mesh_mode;
+        // Edited chunks differ from what gets cached, so they skip the cache.
+        let chunk_mesh_cache = chunk_mesh_cache
+            .as_deref()
+            .filter(|_| chunk_deltas.is_empty())
+            .cloned();
         let task = chunk_task_pool.task_pool.spawn(async move {
-            generate_chunk(
+            if let Some(chunk_generation_result) =
+                chunk_mesh_cache.as_ref().and_then(|chunk_mesh_cache| {
+                    chunk_mesh_cache.load(
+                        lod_pos,
+                        tree_pos,
+                        stack_height,
+                        delta_revision,
+                    )
+                })
+            {
+                return chunk_generation_result;
+            }
+
+            let chunk_generation_result = generate_chunk(
                 lod_pos,
                 tree_pos,
                 stack_height,
                 &generation_options,
                 &country_data,
-            )
+                &chunk_deltas,
+                delta_revision,
+                chunk_mesh_mode,
+            );
+
+            if let Some(chunk_mesh_cache) = chunk_mesh_cache
+                && let Err(err) =
+                    chunk_mesh_cache.store(&chunk_generation_result)
+            {
+                warn!("Failed caching chunk: {}", err);
+            }
+
+            chunk_generation_result
         });
 
         commands
@@ -90,16 +134,37 @@ fn generate_chunk(
     stack_height: i32,
     generation_options: &GenerationOptions,
     country_data: &CountryData,
+    chunk_deltas: &[Arc<ChunkDelta>],
+    delta_revision: u64,
+    chunk_mesh_mode: ChunkMeshMode,
 ) -> ChunkGenerationResult {
     let absolute_chunk_pos = chunk_pos.get_absolute_chunk_pos(tree_pos);
-    let (data, min_height, more) = generate_voxels(
+    let (mut data, min_height, mut more) = generate_voxels(
         [absolute_chunk_pos.x, stack_height, absolute_chunk_pos.y],
         generation_options,
         chunk_pos.lod,
         country_data,
     );
 
-    let mesh_result = generate_mesh(&data, chunk_pos.lod);
+    if !chunk_deltas.is_empty() {
+        more |= apply_voxel_deltas(
+            &mut data,
+            chunk_deltas,
+            absolute_chunk_pos,
+            min_height,
+            chunk_pos.lod,
+        );
+    }
+
+    data.compact();
+
+    let mesh_result = chunk_mesh_mode.generate_mesh(&data, chunk_pos.lod);
+
+    let voxel_data = if chunk_pos.lod == ChunkLod::Full {
+        Some(Arc::new(data))
+    } else {
+        None
+    };
 
     ChunkGenerationResult {
         mesh_result,
@@ -108,5 +173,7 @@ fn generate_chunk(
         chunk_tree_position: tree_pos,
         chunk_stack_offset: stack_height,
         chunk_min_height: min_height,
+        voxel_data,
+        delta_revision,
     }
 }
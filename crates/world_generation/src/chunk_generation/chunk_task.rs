@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 use bevy::{
+    ecs::system::EntityCommands,
     pbr::ExtendedMaterial,
     prelude::*,
     tasks::{Task, TaskPool, TaskPoolBuilder},
@@ -9,9 +12,15 @@ use physics::physics_object::StaticPhysicsObject;
 use crate::{
     chunk_generation::{
         chunk::Chunk, chunk_generation_result::ChunkGenerationResult,
-        chunk_triangles::ChunkTriangles, terrain_mesh::TerrainMesh,
+        chunk_lod::ChunkLod, chunk_mesh_mode::ChunkMeshMode,
+        chunk_triangles::ChunkTriangles, mesh_generation::MeshResult,
+        terrain_mesh::TerrainMesh,
+    },
+    terrain_material::{BlockTextures, TerrainMaterial},
+    voxel_editing::{
+        chunk_remesh_task::ChunkRemeshTask, chunk_voxels::ChunkVoxels,
     },
-    terrain_material::TerrainMaterial,
+    world_save::voxel_delta_store::{VoxelDeltaStore, apply_voxel_deltas},
 };
 
 #[derive(Component)]
@@ -36,14 +45,18 @@ impl Default for ChunkTaskPool {
 pub fn set_generated_chunks(
     mut commands: Commands,
     mut chunks: Query<(Entity, &mut ChunkTask)>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<
-        Assets<ExtendedMaterial<StandardMaterial, TerrainMaterial>>,
+    mut meshes: Option<ResMut<Assets<Mesh>>>,
+    mut materials: Option<
+        ResMut<Assets<ExtendedMaterial<StandardMaterial, TerrainMaterial>>>,
     >,
     mut _chunk_triangles: ResMut<ChunkTriangles>,
+    voxel_delta_store: Res<VoxelDeltaStore>,
+    chunk_task_pool: Res<ChunkTaskPool>,
+    chunk_mesh_mode: Res<ChunkMeshMode>,
+    block_textures: Option<Res<BlockTextures>>,
 ) {
     for (entity, mut task) in &mut chunks {
-        let Some(chunk_generation_result) =
+        let Some(mut chunk_generation_result) =
             future::block_on(future::poll_once(&mut task.0))
         else {
             continue;
@@ -56,22 +69,53 @@ pub fn set_generated_chunks(
                 chunk_generation_result.chunk_tree_position,
             );
 
-        let chunk_position = chunk_pos.to_absolute(
-            chunk_generation_result.chunk_min_height,
-            chunk_generation_result.chunk_pos.lod,
-        );
+        let chunk_lod = chunk_generation_result.chunk_pos.lod;
+
+        let chunk_position = chunk_pos
+            .to_absolute(chunk_generation_result.chunk_min_height, chunk_lod);
+
+        let mut generate_above = chunk_generation_result.generate_above;
+
+        // Blocks edited while the chunk was generating are missing from it,
+        // so they get applied now and the chunk is meshed again.
+        if let Some(mut voxel_data) = chunk_generation_result.voxel_data {
+            if chunk_generation_result.delta_revision
+                != voxel_delta_store.get_revision()
+            {
+                generate_above |= apply_voxel_deltas(
+                    Arc::make_mut(&mut voxel_data),
+                    &voxel_delta_store
+                        .get_deltas_for_chunk(chunk_pos, chunk_lod),
+                    chunk_pos,
+                    chunk_generation_result.chunk_min_height,
+                    chunk_lod,
+                );
+
+                current_entity.insert(ChunkRemeshTask::spawn(
+                    &chunk_task_pool,
+                    voxel_data.clone(),
+                    chunk_lod,
+                    *chunk_mesh_mode,
+                ));
+            }
+
+            current_entity.insert(ChunkVoxels(voxel_data));
+        }
 
         current_entity.remove::<ChunkTask>().insert((
             Chunk {
                 tree_position: chunk_generation_result.chunk_tree_position,
                 chunk_height: chunk_generation_result.chunk_stack_offset,
-                generate_above: chunk_generation_result.generate_above,
+                generate_above,
                 lod_position: chunk_generation_result.chunk_pos,
+                min_height: chunk_generation_result.chunk_min_height,
             },
             Transform::from_translation(chunk_position),
         ));
 
-        if let Some(collider) = chunk_generation_result.mesh_result.collider {
+        if let Some(collider) =
+            chunk_generation_result.mesh_result.collider.take()
+        {
             current_entity.insert((collider, StaticPhysicsObject));
         }
 
@@ -79,49 +123,83 @@ pub fn set_generated_chunks(
         // let result_lod = chunk_generation_result.chunk_pos.lod.usize();
         // chunk_triangles.0[result_lod - 1] += triangle_count as u64;
 
-        let opaque_mesh = chunk_generation_result.mesh_result.opaque_mesh;
-        let transparent_mesh =
-            chunk_generation_result.mesh_result.transparent_mesh;
+        // Without a renderer there are no mesh or material assets.
+        let (Some(meshes), Some(materials)) = (&mut meshes, &mut materials)
+        else {
+            continue;
+        };
 
-        let chunk_shader_pos = chunk_position;
+        spawn_terrain_meshes(
+            &mut current_entity,
+            chunk_generation_result.mesh_result,
+            chunk_position,
+            chunk_lod,
+            meshes,
+            materials,
+            block_textures.as_deref(),
+        );
+    }
+}
 
-        let terrain_material = TerrainMaterial {
-            chunk_position: chunk_shader_pos,
-            lod_multiplier: chunk_generation_result
-                .chunk_pos
-                .lod
-                .multiplier_i32() as u32,
-        };
+pub fn spawn_terrain_meshes(
+    chunk_entity: &mut EntityCommands,
+    mesh_result: MeshResult,
+    chunk_position: Vec3,
+    chunk_lod: ChunkLod,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ExtendedMaterial<StandardMaterial, TerrainMaterial>>,
+    block_textures: Option<&BlockTextures>,
+) {
+    let terrain_material = TerrainMaterial {
+        chunk_position,
+        lod_multiplier: chunk_lod.multiplier_i32() as u32,
+        block_textures: block_textures
+            .map(|block_textures| block_textures.0.clone()),
+    };
+
+    chunk_entity.with_children(|child_spawner| {
+        if let Some(mesh) = mesh_result.opaque_mesh {
+            child_spawner.spawn((
+                TerrainMesh,
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(materials.add(ExtendedMaterial {
+                    base: StandardMaterial {
+                        perceptual_roughness: 1.,
+                        ..Default::default()
+                    },
+                    extension: terrain_material.clone(),
+                })),
+            ));
+        }
 
-        current_entity.with_children(|child_spawner| {
-            if let Some(mesh) = opaque_mesh {
-                child_spawner.spawn((
-                    TerrainMesh,
-                    Mesh3d(meshes.add(mesh)),
-                    MeshMaterial3d(materials.add(ExtendedMaterial {
-                        base: StandardMaterial {
-                            perceptual_roughness: 1.,
-                            ..Default::default()
-                        },
-                        extension: terrain_material.clone(),
-                    })),
-                ));
-            }
+        if let Some(mesh) = mesh_result.cutout_mesh {
+            child_spawner.spawn((
+                TerrainMesh,
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(materials.add(ExtendedMaterial {
+                    base: StandardMaterial {
+                        alpha_mode: AlphaMode::Mask(0.5),
+                        perceptual_roughness: 1.,
+                        ..Default::default()
+                    },
+                    extension: terrain_material.clone(),
+                })),
+            ));
+        }
 
-            if let Some(mesh) = transparent_mesh {
-                child_spawner.spawn((
-                    TerrainMesh,
-                    Mesh3d(meshes.add(mesh)),
-                    MeshMaterial3d(materials.add(ExtendedMaterial {
-                        base: StandardMaterial {
-                            alpha_mode: AlphaMode::AlphaToCoverage,
-                            perceptual_roughness: 1.,
-                            ..Default::default()
-                        },
-                        extension: terrain_material,
-                    })),
-                ));
-            }
-        });
-    }
+        if let Some(mesh) = mesh_result.transparent_mesh {
+            child_spawner.spawn((
+                TerrainMesh,
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(materials.add(ExtendedMaterial {
+                    base: StandardMaterial {
+                        alpha_mode: AlphaMode::Blend,
+                        perceptual_roughness: 1.,
+                        ..Default::default()
+                    },
+                    extension: terrain_material,
+                })),
+            ));
+        }
+    });
 }
@@ -5,8 +5,7 @@ use bevy::{
 use futures_lite::future;
 
 use crate::chunk_generation::country::{
-    country_cache::{CountryCache, GenerationState},
-    country_data::CountryData,
+    country_cache::CountryCache, country_data::CountryData,
 };
 
 #[derive(Component)]
@@ -37,10 +36,7 @@ pub fn set_generated_caches(
         if let Some(chunk_task_data_option) =
             future::block_on(future::poll_once(&mut task.0))
         {
-            country_cache.country_cache.insert(
-                chunk_task_data_option.country_pos,
-                GenerationState::Some(chunk_task_data_option),
-            );
+            country_cache.insert_generated(chunk_task_data_option);
             commands.entity(entity).despawn();
         }
     }
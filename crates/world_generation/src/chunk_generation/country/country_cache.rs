@@ -1,15 +1,20 @@
 use bevy::prelude::*;
+use itertools::Itertools;
 use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     chunk_generation::{
         VOXEL_SIZE,
+        cache_statistics::CacheStatistics,
         country::{
             cache_generation_task::{CacheGenerationTask, CacheTaskPool},
             country_cache_position::CountryPosition,
             country_data::CountryData,
+            country_disk_cache::CountryDiskCache,
             generation_cache::{GenerationCache, GenerationCacheItem},
             path_data::PathData,
+            river_data::RiverData,
+            settlement_data::SettlementData,
             structure_data::StructureData,
         },
     },
@@ -18,16 +23,28 @@ use crate::{
 
 pub const COUNTRY_SIZE: usize = (2usize.pow(14) as f32 / VOXEL_SIZE) as usize;
 
+/// How many generated countries are kept before the least recently used
+/// ones get dropped. Their data stays in the [`CacheStore`] until it gets
+/// evicted there as well.
+pub const MAX_CACHED_COUNTRIES: usize = 16;
+
 #[derive(Resource, Default)]
 pub struct CountryCache {
     pub country_cache: HashMap<CountryPosition, GenerationState<CountryData>>,
     pub cache_store: Arc<CacheStore>,
+    country_last_used: HashMap<CountryPosition, u64>,
+    use_counter: u64,
+    statistics: CacheStatistics,
 }
 
 #[derive(Default)]
 pub struct CacheStore {
     pub path_cache: GenerationCache<CountryPosition, PathData>,
     pub structure_cache: GenerationCache<CountryPosition, StructureData>,
+    pub river_cache: GenerationCache<CountryPosition, RiverData>,
+    pub settlement_cache: GenerationCache<CountryPosition, SettlementData>,
+    /// Only set while playing in a world, so tools and tests stay in memory.
+    pub country_disk_cache: Option<CountryDiskCache>,
 }
 
 pub enum GenerationState<T> {
@@ -36,6 +53,13 @@ pub enum GenerationState<T> {
 }
 
 impl CountryCache {
+    pub fn with_cache_store(cache_store: Arc<CacheStore>) -> Self {
+        Self {
+            cache_store,
+            ..Default::default()
+        }
+    }
+
     pub fn get_or_queue(
         &mut self,
         commands: &mut Commands,
@@ -43,7 +67,12 @@ impl CountryCache {
         cache_task_pool: &CacheTaskPool,
         generation_options: &GenerationOptions,
     ) -> Option<CountryData> {
+        self.use_counter += 1;
+        self.country_last_used.insert(country_pos, self.use_counter);
+
         let Some(country_data) = self.country_cache.get(&country_pos) else {
+            self.statistics.add_miss();
+
             let cache_store = self.cache_store.clone();
             let generation_options = generation_options.clone();
             commands.spawn(CacheGenerationTask(
@@ -64,7 +93,55 @@ impl CountryCache {
 
         match country_data {
             GenerationState::Generating => None,
-            GenerationState::Some(country_data) => Some(country_data.clone()),
+            GenerationState::Some(country_data) => {
+                self.statistics.add_hit();
+                Some(country_data.clone())
+            }
+        }
+    }
+
+    pub fn insert_generated(&mut self, country_data: CountryData) {
+        if let Some(GenerationState::Some(_)) = self.country_cache.insert(
+            country_data.country_pos,
+            GenerationState::Some(country_data),
+        ) {
+            self.statistics.remove_entry(size_of::<CountryData>());
+        }
+        self.statistics.add_entry(size_of::<CountryData>());
+
+        self.evict();
+    }
+
+    pub fn get_statistics(&self) -> &CacheStatistics {
+        &self.statistics
+    }
+
+    /// Drops the least recently used countries that are done generating.
+    /// Running chunk tasks keep their own clone of the country data, so
+    /// nothing they use gets freed.
+    fn evict(&mut self) {
+        if self.statistics.get_entries() <= MAX_CACHED_COUNTRIES {
+            return;
+        }
+
+        let evictable_countries = self
+            .country_cache
+            .iter()
+            .filter(|(_, state)| matches!(state, GenerationState::Some(_)))
+            .map(|(country_pos, _)| *country_pos)
+            .sorted_by_key(|country_pos| {
+                self.country_last_used.get(country_pos).copied()
+            })
+            .collect_vec();
+
+        for country_pos in evictable_countries {
+            if self.statistics.get_entries() <= MAX_CACHED_COUNTRIES {
+                break;
+            }
+
+            self.country_cache.remove(&country_pos);
+            self.country_last_used.remove(&country_pos);
+            self.statistics.remove_entry(size_of::<CountryData>());
         }
     }
 }
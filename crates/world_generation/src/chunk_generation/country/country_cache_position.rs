@@ -1,7 +1,8 @@
 use bevy::prelude::*;
+use rand::{RngExt, SeedableRng, rngs::StdRng};
 
 use crate::{
-    chunk_generation::{country::country_cache::COUNTRY_SIZE, CHUNK_SIZE},
+    chunk_generation::{CHUNK_SIZE, country::country_cache::COUNTRY_SIZE},
     chunk_loading::chunk_pos::AbsoluteChunkPos,
 };
 
@@ -21,4 +22,18 @@ impl CountryPosition {
                 .as_ivec2(),
         )
     }
+
+    /// A random generator that is different for every country of a seed.
+    pub fn get_seeded_rng(&self, seed: u64) -> StdRng {
+        let mut rng = StdRng::seed_from_u64(if self.x < 0 {
+            seed.wrapping_sub(self.x.abs() as u64)
+        } else {
+            seed.wrapping_add(self.x.abs() as u64)
+        });
+        StdRng::seed_from_u64(if self.x < 0 {
+            rng.random::<u64>().wrapping_sub(self.y.abs() as u64)
+        } else {
+            rng.random::<u64>().wrapping_add(self.y.abs() as u64)
+        })
+    }
 }
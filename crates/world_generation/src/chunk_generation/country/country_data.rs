@@ -6,6 +6,7 @@ use crate::{
     chunk_generation::country::{
         country_cache::CacheStore, country_cache_position::CountryPosition,
         generation_cache::GenerationCacheItem, path_data::PathData,
+        river_data::RiverData, settlement_data::SettlementData,
         structure_data::StructureData,
     },
     generation_options::GenerationOptions,
@@ -18,6 +19,8 @@ pub struct CountryData {
     pub this_path_cache: Arc<PathData>,
     pub bottom_path_cache: Arc<PathData>,
     pub left_path_cache: Arc<PathData>,
+    pub river_cache: Arc<RiverData>,
+    pub settlement_cache: Arc<SettlementData>,
 }
 
 impl GenerationCacheItem<CountryPosition> for CountryData {
@@ -47,6 +50,15 @@ impl GenerationCacheItem<CountryPosition> for CountryData {
                 generation_options,
                 cache_store.clone(),
             ),
+            river_cache: cache_store.clone().river_cache.get_cache_entry(
+                key,
+                generation_options,
+                cache_store.clone(),
+            ),
+            settlement_cache: cache_store
+                .clone()
+                .settlement_cache
+                .get_cache_entry(key, generation_options, cache_store.clone()),
         }
     }
 }
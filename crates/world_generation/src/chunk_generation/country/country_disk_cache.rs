@@ -0,0 +1,156 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use utils::file_utils::write_bytes_to_file;
+
+use crate::{
+    chunk_generation::{
+        country::{
+            country_cache::{CacheStore, CountryCache},
+            country_cache_position::CountryPosition,
+        },
+        disk_cache_settings::DiskCacheSettings,
+    },
+    generation_options::GenerationOptions,
+};
+
+/// The folder of the country cache inside the cache directory.
+pub const COUNTRY_CACHE_DIRECTORY: &str = "countries";
+
+/// Stored with every item, which gets rebuilt when it doesn't match. Needed
+/// whenever the city or path generation or the layout of cached country data
+/// changes.
+pub const COUNTRY_CACHE_VERSION: u32 = 1;
+
+/// Generated country data on disk, so the path finding between cities
+/// doesn't have to run again in later sessions.
+///
+/// Every world gets a folder named after its seed. Each file remembers the
+/// version and the generation options it was made with, and gets rebuilt
+/// when either of them changed.
+#[derive(Clone)]
+pub struct CountryDiskCache {
+    directory: Arc<PathBuf>,
+    generation_options_hash: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedCountryItem<T> {
+    version: u32,
+    generation_options_hash: u64,
+    item: T,
+}
+
+impl CountryDiskCache {
+    pub fn new(
+        cache_directory: &str,
+        generation_options: &GenerationOptions,
+    ) -> Result<Self, anyhow::Error> {
+        let directory = Path::new(cache_directory)
+            .join(COUNTRY_CACHE_DIRECTORY)
+            .join(generation_options.seed.to_string());
+        fs::create_dir_all(&directory)?;
+
+        Ok(Self {
+            directory: Arc::new(directory),
+            generation_options_hash: generation_options.get_stable_hash()?,
+        })
+    }
+
+    /// Returns [`None`] if the item wasn't cached yet or is stale.
+    pub fn load<T: DeserializeOwned>(
+        &self,
+        item_name: &str,
+        country_pos: CountryPosition,
+    ) -> Option<T> {
+        let path = self.get_item_path(item_name, country_pos);
+        let bytes = fs::read(&path).ok()?;
+
+        let cached_item = match bincode::serde::decode_from_slice::<
+            CachedCountryItem<T>,
+            _,
+        >(&bytes, bincode::config::standard())
+        {
+            Ok((cached_item, _)) => cached_item,
+            Err(err) => {
+                warn!("Removing broken cached country {:?}: {}", path, err);
+                let _ = fs::remove_file(&path);
+                return None;
+            }
+        };
+
+        if cached_item.version != COUNTRY_CACHE_VERSION
+            || cached_item.generation_options_hash
+                != self.generation_options_hash
+        {
+            return None;
+        }
+
+        Some(cached_item.item)
+    }
+
+    pub fn store<T: Serialize>(
+        &self,
+        item_name: &str,
+        country_pos: CountryPosition,
+        item: &T,
+    ) -> Result<(), anyhow::Error> {
+        let bytes = bincode::serde::encode_to_vec(
+            CachedCountryItem {
+                version: COUNTRY_CACHE_VERSION,
+                generation_options_hash: self.generation_options_hash,
+                item,
+            },
+            bincode::config::standard(),
+        )?;
+
+        write_bytes_to_file(self.get_item_path(item_name, country_pos), &bytes)
+    }
+
+    fn get_item_path(
+        &self,
+        item_name: &str,
+        country_pos: CountryPosition,
+    ) -> PathBuf {
+        self.directory.join(format!(
+            "{}_{}_{}.bin",
+            item_name, country_pos.x, country_pos.y
+        ))
+    }
+}
+
+pub fn setup_country_disk_cache(
+    added_generation_options: Query<
+        &GenerationOptions,
+        Added<GenerationOptions>,
+    >,
+    mut country_cache: ResMut<CountryCache>,
+    disk_cache_settings: Res<DiskCacheSettings>,
+) {
+    for generation_options in &added_generation_options {
+        let country_disk_cache = if disk_cache_settings.enabled {
+            match CountryDiskCache::new(
+                &disk_cache_settings.cache_directory,
+                generation_options,
+            ) {
+                Ok(country_disk_cache) => Some(country_disk_cache),
+                Err(err) => {
+                    error!("Failed setting up country cache: {}", err);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        *country_cache = CountryCache::with_cache_store(Arc::new(CacheStore {
+            country_disk_cache,
+            ..Default::default()
+        }));
+    }
+}
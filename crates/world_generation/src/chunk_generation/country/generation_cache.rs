@@ -2,56 +2,144 @@ use std::{
     collections::HashMap,
     hash::Hash,
     ops::Deref,
-    sync::{Arc, RwLock},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
 };
 
+use itertools::Itertools;
+
 use crate::{
-    chunk_generation::country::country_cache::CacheStore,
+    chunk_generation::{
+        cache_statistics::CacheStatistics, country::country_cache::CacheStore,
+    },
     generation_options::GenerationOptions,
 };
 
+/// How many bytes of generated items a cache keeps before it starts evicting
+/// the least recently used ones.
+pub const DEFAULT_GENERATION_CACHE_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
 pub trait GenerationCacheItem<K: Copy + Eq + Hash> {
     fn generate(
         key: K,
         generation_options: &GenerationOptions,
         cache_store: Arc<CacheStore>,
     ) -> Self;
+
+    /// Items that are expensive to generate can load themselves from the
+    /// country disk cache instead.
+    fn load(_key: K, _cache_store: &CacheStore) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    fn store(&self, _key: K, _cache_store: &CacheStore) {}
+
+    /// An estimate of the bytes the item takes up, including its heap
+    /// allocations.
+    fn get_memory_size(&self) -> usize
+    where
+        Self: Sized,
+    {
+        size_of::<Self>()
+    }
+}
+
+struct GenerationCacheEntry<T> {
+    item_lock: Arc<RwLock<Option<Arc<T>>>>,
+    last_used: AtomicU64,
+    memory_size: AtomicUsize,
+}
+
+impl<T> GenerationCacheEntry<T> {
+    /// Entries are pinned while they are still being generated or any
+    /// chunk task still holds on to their item.
+    fn is_pinned(&self) -> bool {
+        if Arc::strong_count(&self.item_lock) > 1 {
+            return true;
+        }
+
+        match self.item_lock.try_read() {
+            Ok(item) => match item.deref() {
+                None => true,
+                Some(item) => Arc::strong_count(item) > 1,
+            },
+            Err(_) => true,
+        }
+    }
 }
 
-#[derive(Default)]
 pub struct GenerationCache<K: Copy + Eq + Hash, T: GenerationCacheItem<K>> {
-    cache_lock: RwLock<HashMap<K, Arc<RwLock<Option<Arc<T>>>>>>,
+    cache_lock: RwLock<HashMap<K, GenerationCacheEntry<T>>>,
+    memory_limit: usize,
+    use_counter: AtomicU64,
+    statistics: CacheStatistics,
+}
+
+impl<K: Copy + Eq + Hash, T: GenerationCacheItem<K>> Default
+    for GenerationCache<K, T>
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<K: Copy + Eq + Hash, T: GenerationCacheItem<K>> GenerationCache<K, T> {
     pub fn new() -> Self {
+        Self::with_memory_limit(DEFAULT_GENERATION_CACHE_MEMORY_LIMIT)
+    }
+
+    pub fn with_memory_limit(memory_limit: usize) -> Self {
         Self {
             cache_lock: RwLock::new(HashMap::new()),
+            memory_limit,
+            use_counter: AtomicU64::new(0),
+            statistics: CacheStatistics::new(),
         }
     }
 
+    pub fn get_statistics(&self) -> &CacheStatistics {
+        &self.statistics
+    }
+
     pub fn get_cache_entry(
         &self,
         key: K,
         generation_options: &GenerationOptions,
         cache_store: Arc<CacheStore>,
     ) -> Arc<T> {
-        self.get_generated_cache_entry(
+        let (item, generated) = self.get_generated_cache_entry(
             self.get_hash_lock_entry(key),
             key,
             generation_options,
             cache_store,
-        )
+        );
+
+        if generated {
+            self.statistics.add_miss();
+            self.evict();
+        } else {
+            self.statistics.add_hit();
+        }
+
+        item
     }
 
     pub fn try_get_entry_no_lock(&self, key: K) -> Option<Arc<T>> {
         match self.cache_lock.try_read() {
             Ok(read) => {
                 let entry = read.get(&key)?;
-                match entry.try_read() {
+                match entry.item_lock.try_read() {
                     Ok(read) => match read.deref() {
                         None => None,
-                        Some(t) => Some(t.clone()),
+                        Some(t) => {
+                            self.mark_used(entry);
+                            Some(t.clone())
+                        }
                     },
                     Err(_) => None,
                 }
@@ -60,51 +148,112 @@ impl<K: Copy + Eq + Hash, T: GenerationCacheItem<K>> GenerationCache<K, T> {
         }
     }
 
+    fn mark_used(&self, entry: &GenerationCacheEntry<T>) {
+        entry.last_used.store(
+            self.use_counter.fetch_add(1, Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+    }
+
     fn get_hash_lock_entry(&self, key: K) -> Arc<RwLock<Option<Arc<T>>>> {
         let read = self.cache_lock.read().unwrap();
         match read.get(&key) {
             None => {
                 drop(read);
                 let mut write = self.cache_lock.write().unwrap();
-                let result = match write.get(&key) {
-                    None => {
-                        let lock = Arc::new(RwLock::new(None));
-                        write.insert(key, lock);
-                        write.get(&key).unwrap().clone()
-                    }
-                    Some(cache) => cache.clone(),
-                };
+                let entry =
+                    write.entry(key).or_insert_with(|| GenerationCacheEntry {
+                        item_lock: Arc::new(RwLock::new(None)),
+                        last_used: AtomicU64::new(0),
+                        memory_size: AtomicUsize::new(0),
+                    });
+                self.mark_used(entry);
+                let result = entry.item_lock.clone();
                 drop(write);
                 result
             }
-            Some(cache) => cache.clone(),
+            Some(entry) => {
+                self.mark_used(entry);
+                entry.item_lock.clone()
+            }
         }
     }
 
+    /// Also returns whether the item had to be generated.
     fn get_generated_cache_entry(
         &self,
         hash_lock_entry: Arc<RwLock<Option<Arc<T>>>>,
         key: K,
         generation_options: &GenerationOptions,
         cache_store: Arc<CacheStore>,
-    ) -> Arc<T> {
+    ) -> (Arc<T>, bool) {
         let read = hash_lock_entry.read().unwrap();
         match read.deref() {
             None => {
                 drop(read);
                 let mut write = hash_lock_entry.write().unwrap();
                 match write.deref() {
-                    None => write
-                        .insert(Arc::new(T::generate(
-                            key,
-                            generation_options,
-                            cache_store,
-                        )))
-                        .clone(),
-                    Some(country_cache) => country_cache.clone(),
+                    None => {
+                        let item =
+                            T::load(key, &cache_store).unwrap_or_else(|| {
+                                let item = T::generate(
+                                    key,
+                                    generation_options,
+                                    cache_store.clone(),
+                                );
+                                item.store(key, &cache_store);
+                                item
+                            });
+                        let memory_size = item.get_memory_size();
+                        let item = write.insert(Arc::new(item)).clone();
+                        drop(write);
+
+                        self.set_memory_size(key, memory_size);
+
+                        (item, true)
+                    }
+                    Some(country_cache) => (country_cache.clone(), false),
                 }
             }
-            Some(country_cache) => country_cache.clone(),
+            Some(country_cache) => (country_cache.clone(), false),
+        }
+    }
+
+    fn set_memory_size(&self, key: K, memory_size: usize) {
+        let read = self.cache_lock.read().unwrap();
+        if let Some(entry) = read.get(&key) {
+            entry.memory_size.store(memory_size, Ordering::Relaxed);
+            self.statistics.add_entry(memory_size);
+        }
+    }
+
+    /// Removes the least recently used entries that aren't pinned, until the
+    /// cache fits into its memory limit again. Runs after every generated
+    /// item, so it only needs to be called to free items that got unpinned
+    /// since.
+    pub fn evict(&self) {
+        if self.statistics.get_memory_size() <= self.memory_limit {
+            return;
+        }
+
+        let mut write = self.cache_lock.write().unwrap();
+
+        let evictable_keys = write
+            .iter()
+            .filter(|(_, entry)| !entry.is_pinned())
+            .sorted_by_key(|(_, entry)| entry.last_used.load(Ordering::Relaxed))
+            .map(|(key, _)| *key)
+            .collect_vec();
+
+        for key in evictable_keys {
+            if self.statistics.get_memory_size() <= self.memory_limit {
+                break;
+            }
+
+            if let Some(entry) = write.remove(&key) {
+                self.statistics
+                    .remove_entry(entry.memory_size.load(Ordering::Relaxed));
+            }
         }
     }
 }
@@ -3,6 +3,9 @@ pub mod cache_generation_task;
 pub mod country_cache;
 pub mod country_cache_position;
 pub mod country_data;
+pub mod country_disk_cache;
 pub mod generation_cache;
 pub mod path_data;
+pub mod river_data;
+pub mod settlement_data;
 pub mod structure_data;
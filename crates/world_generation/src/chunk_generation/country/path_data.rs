@@ -5,6 +5,7 @@ use std::{
 };
 
 use bevy::{math::DVec2, prelude::*};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     chunk_generation::{
@@ -23,11 +24,14 @@ use crate::{
     generation_options::GenerationOptions,
 };
 
-#[derive(Default)]
+const PATH_DATA_CACHE_NAME: &str = "paths";
+
+#[derive(Default, Serialize, Deserialize)]
 pub struct PathData {
     pub paths: Vec<Path>,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Path {
     pub lines: Vec<PathLine>,
     pub box_pos_start: IVec2,
@@ -45,6 +49,7 @@ impl Path {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct PathLine {
     pub start: IVec2,
     pub end: IVec2,
@@ -270,6 +275,40 @@ impl GenerationCacheItem<CountryPosition> for PathData {
             ],
         }
     }
+
+    fn get_memory_size(&self) -> usize {
+        size_of::<Self>()
+            + self.paths.capacity() * size_of::<Path>()
+            + self
+                .paths
+                .iter()
+                .flat_map(|path| {
+                    path.lines.iter().map(|line| {
+                        size_of::<PathLine>()
+                            + line.sample_points.capacity() * size_of::<IVec2>()
+                    })
+                })
+                .sum::<usize>()
+    }
+
+    fn load(key: CountryPosition, cache_store: &CacheStore) -> Option<Self> {
+        cache_store
+            .country_disk_cache
+            .as_ref()?
+            .load(PATH_DATA_CACHE_NAME, key)
+    }
+
+    fn store(&self, key: CountryPosition, cache_store: &CacheStore) {
+        let Some(country_disk_cache) = &cache_store.country_disk_cache else {
+            return;
+        };
+
+        if let Err(err) =
+            country_disk_cache.store(PATH_DATA_CACHE_NAME, key, self)
+        {
+            warn!("Failed caching path data: {}", err);
+        }
+    }
 }
 
 impl PathData {
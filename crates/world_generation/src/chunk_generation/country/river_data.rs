@@ -0,0 +1,286 @@
+use std::{f64::consts::TAU, sync::Arc};
+
+use bevy::{math::DVec2, prelude::*};
+use rand::RngExt;
+
+use crate::{
+    chunk_generation::{
+        country::{
+            country_cache::{COUNTRY_SIZE, CacheStore},
+            country_cache_position::CountryPosition,
+            generation_cache::GenerationCacheItem,
+        },
+        noise::{noise_function::NoiseFunction, noise_result::NoiseResult},
+        water::water_options::WaterOptions,
+    },
+    generation_options::GenerationOptions,
+};
+
+/// Keeps the springs of rivers apart from the city locations, which use the
+/// same country seed.
+const RIVER_SEED_SALT: u64 = 0x5249_5645_5253;
+
+/// How many points around a lake are checked for the lowest rim height.
+const LAKE_RIM_SAMPLES: usize = 32;
+
+/// The rivers and lakes of one country. Rivers never leave the country they
+/// start in, so a chunk only needs the ones of its own country.
+#[derive(Default)]
+pub struct RiverData {
+    pub rivers: Vec<River>,
+    pub lakes: Vec<Lake>,
+}
+
+/// A river traced downhill from its spring, until it reaches the sea or
+/// its last lake.
+pub struct River {
+    pub points: Vec<RiverPoint>,
+    pub box_pos_start: IVec2,
+    pub box_pos_end: IVec2,
+}
+
+#[derive(Clone, Copy)]
+pub struct RiverPoint {
+    pub position: IVec2,
+    /// Never rises from one point to the next.
+    pub water_height: f32,
+    pub half_width: f32,
+}
+
+pub struct Lake {
+    pub center: IVec2,
+    pub radius: i32,
+    pub water_height: f32,
+}
+
+impl River {
+    fn new(points: Vec<RiverPoint>) -> Self {
+        let box_pos_start = points
+            .iter()
+            .fold(IVec2::MAX, |min, point| min.min(point.position));
+        let box_pos_end = points
+            .iter()
+            .fold(IVec2::MIN, |max, point| max.max(point.position));
+
+        Self {
+            points,
+            box_pos_start,
+            box_pos_end,
+        }
+    }
+
+    pub fn is_in_box(&self, point: IVec2, margin: IVec2) -> bool {
+        let bb_start = self.box_pos_start - margin;
+        let bb_end = self.box_pos_end + margin;
+        !(point.x < bb_start.x
+            || point.x > bb_end.x
+            || point.y < bb_start.y
+            || point.y > bb_end.y)
+    }
+}
+
+impl GenerationCacheItem<CountryPosition> for RiverData {
+    fn generate(
+        key: CountryPosition,
+        generation_options: &GenerationOptions,
+        _cache_store: Arc<CacheStore>,
+    ) -> Self {
+        let water_options = &generation_options.water_options;
+        let terrain_noise = generation_options.get_terrain_noise();
+
+        let mut rng =
+            key.get_seeded_rng(generation_options.seed ^ RIVER_SEED_SALT);
+
+        // The longest river with its banks and lake has to fit into the
+        // country from every spring.
+        let margin = water_options.river_step
+            * water_options.max_river_steps as i32
+            + water_options.lake_radius
+            + (water_options.get_max_half_width() * 2.).ceil() as i32;
+
+        let mut river_data = Self::default();
+
+        if margin * 2 >= COUNTRY_SIZE as i32 {
+            return river_data;
+        }
+
+        for _ in 0..water_options.springs_per_country {
+            let spring = IVec2::new(
+                rng.random_range(margin..COUNTRY_SIZE as i32 - margin),
+                rng.random_range(margin..COUNTRY_SIZE as i32 - margin),
+            ) + *key * COUNTRY_SIZE as i32;
+
+            let Some((river, lakes)) = Self::trace_river(
+                spring.as_dvec2(),
+                &terrain_noise,
+                water_options,
+            ) else {
+                continue;
+            };
+
+            river_data.rivers.push(river);
+            river_data.lakes.extend(lakes);
+        }
+
+        river_data
+    }
+
+    fn get_memory_size(&self) -> usize {
+        size_of::<Self>()
+            + self.lakes.capacity() * size_of::<Lake>()
+            + self.rivers.capacity() * size_of::<River>()
+            + self
+                .rivers
+                .iter()
+                .map(|river| river.points.capacity() * size_of::<RiverPoint>())
+                .sum::<usize>()
+    }
+}
+
+impl RiverData {
+    /// Follows the terrain downhill from the spring. Sinks fill up to lakes,
+    /// which spill over at the lowest point of their rim. Returns `None` for
+    /// springs that are too low and for rivers that neither reach the sea
+    /// nor a lake.
+    fn trace_river(
+        spring: DVec2,
+        terrain_noise: &impl NoiseFunction<NoiseResult, [f64; 2]>,
+        water_options: &WaterOptions,
+    ) -> Option<(River, Vec<Lake>)> {
+        let mut position = spring;
+        let mut height = terrain_noise.get(position.to_array());
+
+        if height.value < water_options.min_spring_height as f64 {
+            return None;
+        }
+
+        let mut points = vec![];
+        let mut lakes: Vec<Lake> = vec![];
+        // How many points the river had when it reached its last lake
+        let mut points_to_last_lake = 0;
+        let mut water_height = f32::INFINITY;
+
+        for index in 0..water_options.max_river_steps {
+            water_height = water_height.min(height.value as f32 - 1.);
+            points.push(RiverPoint {
+                position: position.as_ivec2(),
+                water_height,
+                half_width: water_options.spring_width
+                    + water_options.width_per_step * index as f32,
+            });
+
+            if water_height <= water_options.sea_level as f32 {
+                return Some((River::new(points), lakes));
+            }
+
+            if let Some((next_position, next_height)) = Self::get_downhill_step(
+                position,
+                height,
+                water_options.river_step as f64,
+                terrain_noise,
+            ) {
+                position = next_position;
+                height = next_height;
+                continue;
+            }
+
+            // Flowing back into a lake of this river would go in circles
+            if lakes.iter().any(|lake| {
+                lake.center.as_dvec2().distance(position) < lake.radius as f64
+            }) {
+                break;
+            }
+
+            let (lake, outlet) = Self::get_lake(
+                position,
+                height.value,
+                terrain_noise,
+                water_options,
+            );
+
+            if let Some(lake) = lake {
+                water_height = water_height.min(lake.water_height);
+                lakes.push(lake);
+                points_to_last_lake = points.len();
+            }
+
+            let Some(outlet) = outlet else {
+                break;
+            };
+
+            position = outlet;
+            height = terrain_noise.get(position.to_array());
+        }
+
+        if lakes.is_empty() {
+            return None;
+        }
+
+        points.truncate(points_to_last_lake);
+
+        Some((River::new(points), lakes))
+    }
+
+    /// Steps against the gradient of the terrain. A step can overshoot a
+    /// narrow valley, so if that doesn't lead downhill the lowest of the
+    /// surrounding directions is taken. Returns `None` at a local minimum.
+    fn get_downhill_step(
+        position: DVec2,
+        height: NoiseResult,
+        step: f64,
+        terrain_noise: &impl NoiseFunction<NoiseResult, [f64; 2]>,
+    ) -> Option<(DVec2, NoiseResult)> {
+        let gradient = DVec2::from_array(height.derivative);
+        let along_gradient = position - gradient.normalize_or_zero() * step;
+        let along_gradient_height =
+            terrain_noise.get(along_gradient.to_array());
+
+        if along_gradient_height.value < height.value {
+            return Some((along_gradient, along_gradient_height));
+        }
+
+        (0..8)
+            .map(|index| {
+                let next = position
+                    + DVec2::from_angle(index as f64 * TAU / 8.) * step;
+                (next, terrain_noise.get(next.to_array()))
+            })
+            .filter(|(_, next_height)| next_height.value < height.value)
+            .min_by(|(_, a), (_, b)| a.value.total_cmp(&b.value))
+    }
+
+    /// Fills the sink at `center` up to the lowest point of its rim. Returns
+    /// the lake, if the sink is deep enough for one, and the point the water
+    /// spills over, if it doesn't hit the depth limit first.
+    fn get_lake(
+        center: DVec2,
+        height: f64,
+        terrain_noise: &impl NoiseFunction<NoiseResult, [f64; 2]>,
+        water_options: &WaterOptions,
+    ) -> (Option<Lake>, Option<DVec2>) {
+        let (outlet, rim_height) = (0..LAKE_RIM_SAMPLES)
+            .map(|index| {
+                let angle = index as f64 * TAU / LAKE_RIM_SAMPLES as f64;
+                let rim_point = center
+                    + DVec2::from_angle(angle)
+                        * water_options.lake_radius as f64;
+                (rim_point, terrain_noise.get(rim_point.to_array()).value)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap();
+
+        let max_water_height = height + water_options.max_lake_depth as f64;
+        let water_height = (rim_height - 1.).min(max_water_height);
+
+        let lake = (water_height > height + 1.).then(|| Lake {
+            center: center.as_ivec2(),
+            radius: water_options.lake_radius,
+            water_height: water_height as f32,
+        });
+
+        (
+            lake,
+            (rim_height - 1. <= max_water_height).then_some(outlet),
+        )
+    }
+}
@@ -0,0 +1,313 @@
+use std::{f32::consts::TAU, sync::Arc};
+
+use bevy::prelude::*;
+use itertools::Itertools;
+use rand::RngExt;
+
+use crate::{
+    chunk_generation::{
+        country::{
+            country_cache::{COUNTRY_SIZE, CacheStore},
+            country_cache_position::CountryPosition,
+            generation_cache::GenerationCacheItem,
+            path_data::{Path, PathData},
+        },
+        noise::noise_function::NoiseFunction,
+    },
+    generation_options::GenerationOptions,
+};
+
+/// Keeps the village layout apart from the city location and the rivers,
+/// which use the same country seed.
+const SETTLEMENT_SEED_SALT: u64 = 0x5345_5454_4c45;
+
+/// How close to the city a path has to start to count as one of its roads.
+const ROAD_SNAP_DISTANCE: f32 = 256.;
+
+/// The space kept free between two buildings.
+const BUILDING_GAP: i32 = 4;
+
+/// The village around the city of one country. Buildings never leave the
+/// country, so a chunk only needs the village of its own country.
+#[derive(Default)]
+pub struct SettlementData {
+    pub buildings: Vec<Building>,
+    pub box_pos_start: IVec2,
+    pub box_pos_end: IVec2,
+}
+
+/// One placed building template.
+#[derive(Clone)]
+pub struct Building {
+    pub template_index: usize,
+    /// The lower corner of the turned template. Its height is the floor.
+    pub position: IVec3,
+    /// Quarter turns of the template around the Y axis.
+    pub rotation: u8,
+    /// The size of the turned template.
+    pub size: IVec3,
+}
+
+impl Building {
+    pub fn contains_column(&self, position: IVec2) -> bool {
+        let local = position - self.position.xz();
+        local.cmpge(IVec2::ZERO).all() && local.cmplt(self.size.xz()).all()
+    }
+
+    /// How far a column is outside of the footprint, zero inside.
+    pub fn get_distance_outside(&self, position: IVec2) -> f32 {
+        let start = self.position.xz();
+        let end = start + self.size.xz() - IVec2::ONE;
+        let outside = (start - position).max(position - end).max(IVec2::ZERO);
+
+        outside.as_vec2().length()
+    }
+
+    fn overlaps(&self, other: &Building, gap: i32) -> bool {
+        let start = self.position.xz() - gap;
+        let end = self.position.xz() + self.size.xz() + gap;
+        let other_start = other.position.xz();
+        let other_end = other.position.xz() + other.size.xz();
+
+        start.cmplt(other_end).all() && other_start.cmplt(end).all()
+    }
+}
+
+impl GenerationCacheItem<CountryPosition> for SettlementData {
+    fn generate(
+        key: CountryPosition,
+        generation_options: &GenerationOptions,
+        cache_store: Arc<CacheStore>,
+    ) -> Self {
+        let settlement_options = &generation_options.settlement_options;
+        let building_templates = &generation_options.building_templates;
+
+        if building_templates.is_empty() {
+            return Self::default();
+        }
+
+        let city_location = cache_store
+            .structure_cache
+            .get_cache_entry(key, generation_options, cache_store.clone())
+            .city_location;
+
+        let mut rng =
+            key.get_seeded_rng(generation_options.seed ^ SETTLEMENT_SEED_SALT);
+
+        let mut roads = [
+            cache_store.path_cache.get_cache_entry(
+                key,
+                generation_options,
+                cache_store.clone(),
+            ),
+            cache_store.path_cache.get_cache_entry(
+                CountryPosition::new(*key + IVec2::NEG_X),
+                generation_options,
+                cache_store.clone(),
+            ),
+            cache_store.path_cache.get_cache_entry(
+                CountryPosition::new(*key + IVec2::NEG_Y),
+                generation_options,
+                cache_store.clone(),
+            ),
+        ]
+        .iter()
+        .flat_map(|path_data: &Arc<PathData>| {
+            path_data
+                .paths
+                .iter()
+                .filter_map(|path| Self::get_road(path, city_location))
+                .collect_vec()
+        })
+        .collect_vec();
+
+        if roads.is_empty() && settlement_options.fallback_roads > 0 {
+            let start_angle = rng.random_range(0. ..TAU);
+            roads = (0..settlement_options.fallback_roads)
+                .map(|index| {
+                    let angle = start_angle
+                        + index as f32 * TAU
+                            / settlement_options.fallback_roads as f32;
+                    vec![
+                        city_location,
+                        city_location
+                            + (Vec2::from_angle(angle)
+                                * settlement_options.fallback_road_length)
+                                .as_ivec2(),
+                    ]
+                })
+                .collect();
+        }
+
+        let terrain_noise = generation_options.get_terrain_noise();
+        let get_height = |position: IVec2| {
+            terrain_noise.get(position.as_dvec2().to_array()).value as f32
+        };
+
+        let country_start = *key * COUNTRY_SIZE as i32;
+        let country_end = country_start + COUNTRY_SIZE as i32;
+
+        let mut buildings: Vec<Building> = vec![];
+
+        for road in &roads {
+            for index in 0..settlement_options.buildings_per_road {
+                let distance = settlement_options.plaza_radius
+                    + index as f32 * settlement_options.building_spacing;
+                let Some((road_point, road_direction)) =
+                    Self::get_point_on_road(road, distance)
+                else {
+                    break;
+                };
+
+                for side in [road_direction.perp(), -road_direction.perp()] {
+                    let template_index =
+                        rng.random_range(0..building_templates.len());
+                    let template = &building_templates[template_index];
+
+                    // The entrance looks back at the road.
+                    let facing = Self::get_cardinal_direction(-side);
+                    let rotation = template.get_rotation_towards(facing);
+                    let size = template.get_turned_size(rotation);
+
+                    let depth = (size.xz() * facing.abs()).element_sum();
+                    let center = road_point
+                        + side
+                            * (settlement_options.road_clearance
+                                + depth as f32 / 2.);
+                    let corner = center.as_ivec2() - size.xz() / 2;
+
+                    let margin = settlement_options.flatten_margin;
+                    if (corner - margin).cmplt(country_start).any()
+                        || (corner + size.xz() + margin)
+                            .cmpgt(country_end)
+                            .any()
+                    {
+                        continue;
+                    }
+
+                    let floor = get_height(center.as_ivec2()).round();
+                    if floor < generation_options.water_options.sea_level as f32
+                    {
+                        continue;
+                    }
+
+                    let corners = [
+                        corner,
+                        corner + IVec2::new(size.x - 1, 0),
+                        corner + IVec2::new(0, size.z - 1),
+                        corner + size.xz() - IVec2::ONE,
+                    ];
+                    if corners.iter().any(|corner| {
+                        (get_height(*corner) - floor).abs()
+                            > settlement_options.max_ground_difference as f32
+                    }) {
+                        continue;
+                    }
+
+                    let building = Building {
+                        template_index,
+                        position: corner.extend(floor as i32).xzy(),
+                        rotation,
+                        size,
+                    };
+
+                    if buildings
+                        .iter()
+                        .any(|other| building.overlaps(other, BUILDING_GAP))
+                    {
+                        continue;
+                    }
+
+                    buildings.push(building);
+                }
+            }
+        }
+
+        let margin = IVec2::splat(settlement_options.flatten_margin);
+        let box_pos_start = buildings
+            .iter()
+            .fold(IVec2::MAX, |min, building| min.min(building.position.xz()))
+            - margin;
+        let box_pos_end = buildings.iter().fold(IVec2::MIN, |max, building| {
+            max.max(building.position.xz() + building.size.xz())
+        }) + margin;
+
+        Self {
+            buildings,
+            box_pos_start,
+            box_pos_end,
+        }
+    }
+
+    fn get_memory_size(&self) -> usize {
+        size_of::<Self>() + self.buildings.capacity() * size_of::<Building>()
+    }
+}
+
+impl SettlementData {
+    pub fn is_in_box(&self, point: IVec2) -> bool {
+        !(point.x < self.box_pos_start.x
+            || point.x > self.box_pos_end.x
+            || point.y < self.box_pos_start.y
+            || point.y > self.box_pos_end.y)
+    }
+
+    /// The points of a path, going out from the city, if it starts or ends
+    /// there.
+    fn get_road(path: &Path, city_location: IVec2) -> Option<Vec<IVec2>> {
+        let mut points = path
+            .lines
+            .iter()
+            .flat_map(|line| line.sample_points.iter().copied())
+            .dedup()
+            .collect_vec();
+
+        let is_close = |point: &IVec2| {
+            point.as_vec2().distance(city_location.as_vec2())
+                <= ROAD_SNAP_DISTANCE
+        };
+
+        if points.last().is_some_and(is_close) {
+            points.reverse();
+        } else if !points.first().is_some_and(is_close) {
+            return None;
+        }
+
+        points.insert(0, city_location);
+        Some(points)
+    }
+
+    /// The point at a distance along a road and the direction of the road
+    /// there.
+    fn get_point_on_road(
+        road: &[IVec2],
+        mut distance: f32,
+    ) -> Option<(Vec2, Vec2)> {
+        for (start, end) in road.iter().tuple_windows() {
+            let start = start.as_vec2();
+            let end = end.as_vec2();
+            let length = start.distance(end);
+
+            if length <= 0. {
+                continue;
+            }
+
+            if distance <= length {
+                let direction = (end - start) / length;
+                return Some((start + direction * distance, direction));
+            }
+
+            distance -= length;
+        }
+
+        None
+    }
+
+    fn get_cardinal_direction(direction: Vec2) -> IVec2 {
+        if direction.x.abs() > direction.y.abs() {
+            IVec2::new(direction.x.signum() as i32, 0)
+        } else {
+            IVec2::new(0, direction.y.signum() as i32)
+        }
+    }
+}
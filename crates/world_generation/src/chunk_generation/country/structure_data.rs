@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use bevy::prelude::*;
-use rand::{RngExt, SeedableRng, rngs::StdRng};
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     chunk_generation::country::{
@@ -12,7 +13,9 @@ use crate::{
     generation_options::GenerationOptions,
 };
 
-#[derive(Default)]
+const STRUCTURE_DATA_CACHE_NAME: &str = "structures";
+
+#[derive(Default, Serialize, Deserialize)]
 pub struct StructureData {
     pub city_location: IVec2,
 }
@@ -23,16 +26,7 @@ impl GenerationCacheItem<CountryPosition> for StructureData {
         generation_options: &GenerationOptions,
         _country_cache: Arc<CacheStore>,
     ) -> Self {
-        let mut rng = StdRng::seed_from_u64(if key.x < 0 {
-            generation_options.seed.wrapping_sub(key.x.abs() as u64)
-        } else {
-            generation_options.seed.wrapping_add(key.x.abs() as u64)
-        });
-        let mut rng = StdRng::seed_from_u64(if key.x < 0 {
-            rng.random::<u64>().wrapping_sub(key.y.abs() as u64)
-        } else {
-            rng.random::<u64>().wrapping_add(key.y.abs() as u64)
-        });
+        let mut rng = key.get_seeded_rng(generation_options.seed);
 
         let min_offset = 100i32;
 
@@ -46,4 +40,23 @@ impl GenerationCacheItem<CountryPosition> for StructureData {
                 + *key * COUNTRY_SIZE as i32,
         }
     }
+
+    fn load(key: CountryPosition, cache_store: &CacheStore) -> Option<Self> {
+        cache_store
+            .country_disk_cache
+            .as_ref()?
+            .load(STRUCTURE_DATA_CACHE_NAME, key)
+    }
+
+    fn store(&self, key: CountryPosition, cache_store: &CacheStore) {
+        let Some(country_disk_cache) = &cache_store.country_disk_cache else {
+            return;
+        };
+
+        if let Err(err) =
+            country_disk_cache.store(STRUCTURE_DATA_CACHE_NAME, key, self)
+        {
+            warn!("Failed caching structure data: {}", err);
+        }
+    }
 }
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+pub const DENSITY_OPTIONS_FILE_PATH: &str = "density_options.ron";
+
+/// How far the 3D density pass reaches around the height map. Distances
+/// are in voxels of the full LOD.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DensityOptions {
+    /// How deep below the height map caves are carved.
+    pub cave_depth: i32,
+    /// How far the overhang noise can move the ground up or down from the
+    /// height map. Zero turns overhangs off.
+    pub overhang_height: i32,
+}
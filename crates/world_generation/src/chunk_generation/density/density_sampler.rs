@@ -0,0 +1,73 @@
+use crate::{
+    chunk_generation::{
+        density::density_options::DensityOptions,
+        noise::{noise_function::NoiseFunction, noise_result::NoiseResult},
+    },
+    generation_options::GenerationOptions,
+};
+
+type BoxedNoise3d = Box<dyn NoiseFunction<NoiseResult, [f64; 3]> + Send + Sync>;
+
+/// Decides which voxels around the height map are solid, carving caves
+/// and adding overhangs. Positions and depths are in voxels of the full
+/// LOD, so every LOD samples the same shapes.
+pub struct DensitySampler<'a> {
+    density_options: &'a DensityOptions,
+    overhang_noise: BoxedNoise3d,
+    cave_noise: BoxedNoise3d,
+}
+
+impl<'a> DensitySampler<'a> {
+    pub fn new(generation_options: &'a GenerationOptions) -> Self {
+        let terrain_noise_group = &generation_options.terrain_noise_group;
+
+        Self {
+            density_options: &generation_options.density_options,
+            overhang_noise: terrain_noise_group
+                .overhang_density
+                .get_noise_fn_3d(&mut generation_options.get_seeded_rng()),
+            cave_noise: terrain_noise_group
+                .cave_density
+                .get_noise_fn_3d(&mut generation_options.get_seeded_rng()),
+        }
+    }
+
+    /// How far above the height map ground can be.
+    pub fn get_reach_above(&self) -> i32 {
+        self.density_options.overhang_height.max(0)
+    }
+
+    /// How far below the height map ground can be missing.
+    pub fn get_reach_below(&self) -> i32 {
+        self.density_options
+            .cave_depth
+            .max(self.density_options.overhang_height)
+            .max(0)
+    }
+
+    /// Whether the voxel at `position` is part of the ground. `depth` is
+    /// how far it is below the height map, negative above it.
+    pub fn is_solid(&self, position: [f64; 3], depth: i32) -> bool {
+        let overhang_height = self.density_options.overhang_height;
+
+        let solid = if depth.abs() < overhang_height {
+            depth as f64 / overhang_height as f64
+                + self.overhang_noise.get(position).value
+                >= 0.
+        } else {
+            depth >= 0
+        };
+
+        if solid && depth < self.density_options.cave_depth {
+            self.cave_noise.get(position).value <= 0.
+        } else {
+            solid
+        }
+    }
+
+    /// Whether air at this depth lets light onto the ground below it, so
+    /// the ground gets a surface block instead of a cave floor.
+    pub fn is_open_air(&self, depth: i32) -> bool {
+        depth < self.density_options.overhang_height.max(1)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod density_options;
+pub mod density_sampler;
@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+pub const CACHE_DIRECTORY: &str = "cache";
+
+/// Where the chunk mesh and country caches keep their folders.
+#[derive(Resource)]
+pub struct DiskCacheSettings {
+    pub cache_directory: String,
+    pub enabled: bool,
+}
+
+impl Default for DiskCacheSettings {
+    fn default() -> Self {
+        Self {
+            cache_directory: CACHE_DIRECTORY.into(),
+            enabled: true,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+};
+
+use crate::chunk_generation::{
+    cache_statistics::CacheStatistics, country::country_cache::CountryCache,
+    structures::structure_generator::STRUCTURE_MODEL_CACHE_STATISTICS,
+};
+
+/// The entries, memory and hit rate diagnostics of one cache.
+pub struct CacheDiagnosticPaths {
+    pub entries: DiagnosticPath,
+    pub memory: DiagnosticPath,
+    pub hit_rate: DiagnosticPath,
+}
+
+impl CacheDiagnosticPaths {
+    const fn new(
+        entries: &'static str,
+        memory: &'static str,
+        hit_rate: &'static str,
+    ) -> Self {
+        Self {
+            entries: DiagnosticPath::const_new(entries),
+            memory: DiagnosticPath::const_new(memory),
+            hit_rate: DiagnosticPath::const_new(hit_rate),
+        }
+    }
+
+    fn register(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(self.entries.clone()))
+            .register_diagnostic(
+                Diagnostic::new(self.memory.clone()).with_suffix(" MiB"),
+            )
+            .register_diagnostic(
+                Diagnostic::new(self.hit_rate.clone()).with_suffix("%"),
+            );
+    }
+
+    fn measure(
+        &self,
+        diagnostics: &mut Diagnostics,
+        statistics: &CacheStatistics,
+    ) {
+        diagnostics
+            .add_measurement(&self.entries, || statistics.get_entries() as f64);
+        diagnostics.add_measurement(&self.memory, || {
+            statistics.get_memory_size() as f64 / (1024. * 1024.)
+        });
+
+        if let Some(hit_rate) = statistics.take_hit_rate() {
+            diagnostics.add_measurement(&self.hit_rate, || hit_rate * 100.);
+        }
+    }
+}
+
+pub const COUNTRY_CACHE_DIAGNOSTICS: CacheDiagnosticPaths =
+    CacheDiagnosticPaths::new(
+        "generation_cache/countries/entries",
+        "generation_cache/countries/memory",
+        "generation_cache/countries/hit_rate",
+    );
+pub const PATH_CACHE_DIAGNOSTICS: CacheDiagnosticPaths =
+    CacheDiagnosticPaths::new(
+        "generation_cache/paths/entries",
+        "generation_cache/paths/memory",
+        "generation_cache/paths/hit_rate",
+    );
+pub const STRUCTURE_CACHE_DIAGNOSTICS: CacheDiagnosticPaths =
+    CacheDiagnosticPaths::new(
+        "generation_cache/structures/entries",
+        "generation_cache/structures/memory",
+        "generation_cache/structures/hit_rate",
+    );
+pub const RIVER_CACHE_DIAGNOSTICS: CacheDiagnosticPaths =
+    CacheDiagnosticPaths::new(
+        "generation_cache/rivers/entries",
+        "generation_cache/rivers/memory",
+        "generation_cache/rivers/hit_rate",
+    );
+pub const SETTLEMENT_CACHE_DIAGNOSTICS: CacheDiagnosticPaths =
+    CacheDiagnosticPaths::new(
+        "generation_cache/settlements/entries",
+        "generation_cache/settlements/memory",
+        "generation_cache/settlements/hit_rate",
+    );
+pub const STRUCTURE_MODEL_CACHE_DIAGNOSTICS: CacheDiagnosticPaths =
+    CacheDiagnosticPaths::new(
+        "generation_cache/structure_models/entries",
+        "generation_cache/structure_models/memory",
+        "generation_cache/structure_models/hit_rate",
+    );
+
+pub struct GenerationCacheDiagnosticsPlugin;
+
+impl Plugin for GenerationCacheDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        for cache_diagnostics in [
+            &COUNTRY_CACHE_DIAGNOSTICS,
+            &PATH_CACHE_DIAGNOSTICS,
+            &STRUCTURE_CACHE_DIAGNOSTICS,
+            &RIVER_CACHE_DIAGNOSTICS,
+            &SETTLEMENT_CACHE_DIAGNOSTICS,
+            &STRUCTURE_MODEL_CACHE_DIAGNOSTICS,
+        ] {
+            cache_diagnostics.register(app);
+        }
+
+        app.add_systems(Update, measure_generation_caches);
+    }
+}
+
+fn measure_generation_caches(
+    mut diagnostics: Diagnostics,
+    country_cache: Res<CountryCache>,
+) {
+    let cache_store = &country_cache.cache_store;
+
+    COUNTRY_CACHE_DIAGNOSTICS
+        .measure(&mut diagnostics, country_cache.get_statistics());
+    PATH_CACHE_DIAGNOSTICS
+        .measure(&mut diagnostics, cache_store.path_cache.get_statistics());
+    STRUCTURE_CACHE_DIAGNOSTICS.measure(
+        &mut diagnostics,
+        cache_store.structure_cache.get_statistics(),
+    );
+    RIVER_CACHE_DIAGNOSTICS
+        .measure(&mut diagnostics, cache_store.river_cache.get_statistics());
+    SETTLEMENT_CACHE_DIAGNOSTICS.measure(
+        &mut diagnostics,
+        cache_store.settlement_cache.get_statistics(),
+    );
+    STRUCTURE_MODEL_CACHE_DIAGNOSTICS
+        .measure(&mut diagnostics, &STRUCTURE_MODEL_CACHE_STATISTICS);
+}
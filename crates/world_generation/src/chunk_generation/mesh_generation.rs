@@ -4,17 +4,21 @@ use bevy::{
     prelude::*,
 };
 use physics::collider::Collider;
-use utils::cartesian_product::cube_cartesian_product;
 
 use crate::chunk_generation::{
-    CHUNK_SIZE, VOXEL_SIZE, ambient_occlusion::AmbiantOcclusion,
-    block_type::BlockFace, chunk_lod::ChunkLod, mesh_type::MeshType,
+    CHUNK_SIZE, VOXEL_SIZE,
+    ambient_occlusion::AmbiantOcclusion,
+    block_type::BlockFace,
+    chunk_lod::{ChunkLod, MAX_TEXTURED_LOD},
+    mesh_type::MeshType,
     voxel_data::VoxelData,
 };
 
+#[derive(Default)]
 pub struct MeshResult {
     pub opaque_mesh: Option<Mesh>,
     pub transparent_mesh: Option<Mesh>,
+    pub cutout_mesh: Option<Mesh>,
     pub collider: Option<Collider>,
 }
 
@@ -22,14 +26,25 @@ pub fn generate_mesh(
     voxel_data: &VoxelData,
     chunk_lod: ChunkLod,
 ) -> MeshResult {
+    if voxel_data.has_no_faces() {
+        return MeshResult::default();
+    }
+
     let opaque_mesh =
         get_mesh_for_mesh_type(MeshType::Opaque, voxel_data, chunk_lod, true);
 
-    let transparent_mesh = None;
-    // get_mesh_for_blocks(&[BlockType::Leaf], voxel_data, chunk_lod, true);
+    let transparent_mesh = get_mesh_for_mesh_type(
+        MeshType::Transparent,
+        voxel_data,
+        chunk_lod,
+        false,
+    );
+
+    let cutout_mesh =
+        get_mesh_for_mesh_type(MeshType::Cutout, voxel_data, chunk_lod, true);
 
     let collider = if chunk_lod == ChunkLod::Full {
-        get_compound_collider(voxel_data)
+        get_voxel_collider(voxel_data)
     } else {
         None
     };
@@ -37,6 +52,26 @@ pub fn generate_mesh(
     MeshResult {
         opaque_mesh,
         transparent_mesh,
+        cutout_mesh,
+        collider,
+    }
+}
+
+/// Builds only the collider of a chunk and skips the render meshes.
+pub fn generate_collider(
+    voxel_data: &VoxelData,
+    chunk_lod: ChunkLod,
+) -> MeshResult {
+    let collider = if chunk_lod == ChunkLod::Full {
+        get_voxel_collider(voxel_data)
+    } else {
+        None
+    };
+
+    MeshResult {
+        opaque_mesh: None,
+        transparent_mesh: None,
+        cutout_mesh: None,
         collider,
     }
 }
@@ -55,6 +90,8 @@ fn get_mesh_for_mesh_type(
     let mut triangles: Vec<[u32; 3]> = Vec::new();
     let mut uvs: Vec<[f32; 2]> = Vec::new();
     let mut colors: Vec<[f32; 4]> = Vec::new();
+    // The texture layer of every vertex, -1 for untextured blocks.
+    let mut texture_layers: Vec<[f32; 2]> = Vec::new();
 
     let mut generate_sides = |direction: IVec3, block_face: BlockFace| {
         let get_ambiant_occlusion = |pos: IVec3| {
@@ -163,6 +200,12 @@ fn get_mesh_for_mesh_type(
                         [uv_end.x, uv_start.y],
                     ]);
 
+                    let texture_layer = current_block
+                        .get_texture_layer(block_face)
+                        .map_or(-1., |layer| layer as f32);
+
+                    texture_layers.extend_from_slice(&[[texture_layer, 0.]; 4]);
+
                     let height = height as f32 - 1.;
                     let width = width as f32 - 1.;
 
@@ -285,6 +328,10 @@ fn get_mesh_for_mesh_type(
     mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
     mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
 
+    if chunk_lod <= MAX_TEXTURED_LOD {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, texture_layers);
+    }
+
     mesh.insert_indices(Indices::U32(mesh_triangles));
 
     Some(mesh)
@@ -302,101 +349,24 @@ pub fn rotate_into_direction<T: Vec3Swizzles>(
     }
 }
 
-fn get_compound_collider(voxel_data: &VoxelData) -> Option<Collider> {
-    let mut colliders: Vec<(Vec3, Vec3)> = Vec::new();
-    let mut done_blocks =
-        [[[false; CHUNK_SIZE + 2]; CHUNK_SIZE + 2]; CHUNK_SIZE + 2];
-
-    for (x, y, z) in cube_cartesian_product(1..CHUNK_SIZE + 1) {
-        let current_pos = IVec3::new(x as i32, y as i32, z as i32);
-        let current_block = voxel_data.get_block(current_pos);
-
-        if done_blocks[x][y][z]
-            || !current_block.is_colliding()
-            || !voxel_data.is_next_to_air(current_pos)
-        {
-            continue;
-        }
-
-        let mut x_length = 1;
-        let mut y_length = 1;
-        let mut z_length = 1;
-
-        while x + x_length <= CHUNK_SIZE
-            && !done_blocks[x + x_length][y][z]
-            && voxel_data
-                .get_block(current_pos + (IVec3::X * x_length as i32))
-                .is_colliding()
-            && voxel_data
-                .is_next_to_air(current_pos + (IVec3::X * x_length as i32))
-        {
-            x_length += 1;
-        }
-
-        while y + y_length <= CHUNK_SIZE
-            && (x..x_length + x).all(|x| {
-                !done_blocks[x][y + y_length][z]
-                    && voxel_data
-                        .get_block(
-                            current_pos.with_x(x as i32)
-                                + (IVec3::Y * y_length as i32),
-                        )
-                        .is_colliding()
-                    && voxel_data.is_next_to_air(
-                        current_pos.with_x(x as i32)
-                            + (IVec3::Y * y_length as i32),
-                    )
-            })
-        {
-            y_length += 1;
-        }
-
-        while z + z_length <= CHUNK_SIZE
-            && (x..x_length + x).all(|x| {
-                (y..y_length + y).all(|y| {
-                    !done_blocks[x][y][z + z_length]
-                        && voxel_data
-                            .get_block(
-                                current_pos.with_x(x as i32).with_y(y as i32)
-                                    + (IVec3::Z * z_length as i32),
-                            )
-                            .is_colliding()
-                        && voxel_data.is_next_to_air(
-                            current_pos.with_x(x as i32).with_y(y as i32)
-                                + (IVec3::Z * z_length as i32),
-                        )
-                })
-            })
-        {
-            z_length += 1;
-        }
-
-        for (x_inner, y_inner, z_inner) in
-            cube_cartesian_product(0..x_length.max(y_length).max(z_length))
-        {
-            if x_inner >= x_length || y_inner >= y_length || z_inner >= z_length
-            {
-                continue;
-            }
-
-            done_blocks[x + x_inner][y + y_inner][z + z_inner] = true;
-        }
-
-        let x_length = x_length as f32 * VOXEL_SIZE;
-        let y_length = y_length as f32 * VOXEL_SIZE;
-        let z_length = z_length as f32 * VOXEL_SIZE;
-
-        let collider_offset = Vec3::new(x_length, y_length, z_length) * 0.5;
-
-        colliders.push((
-            Vec3::new(x_length, y_length, z_length),
-            current_pos.as_vec3() * VOXEL_SIZE + collider_offset,
-        ));
-    }
-
-    if colliders.is_empty() {
+/// Only the inner voxels collide, the border belongs to the neighbouring
+/// chunks.
+fn get_voxel_collider(voxel_data: &VoxelData) -> Option<Collider> {
+    if voxel_data
+        .get_uniform_block()
+        .is_some_and(|block| !block.is_colliding())
+    {
         return None;
     }
 
-    Some(Collider::compund(colliders.as_slice()))
+    Collider::voxel_grid(
+        UVec3::splat(CHUNK_SIZE as u32),
+        VOXEL_SIZE,
+        Vec3::splat(VOXEL_SIZE),
+        |voxel| {
+            voxel_data
+                .get_block(voxel.as_ivec3() + IVec3::ONE)
+                .is_colliding()
+        },
+    )
 }
@@ -1,5 +1,11 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MeshType {
     Opaque,
     Transparent,
+    /// Opaque, but with holes cut out where the texture is transparent.
+    Cutout,
+    /// Never gets meshed, like air.
+    Invisible,
 }
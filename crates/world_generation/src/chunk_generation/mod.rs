@@ -1,20 +1,31 @@
 pub mod ambient_occlusion;
+pub mod biomes;
+pub mod block_registry;
 pub mod block_type;
+pub mod cache_statistics;
 pub mod chunk;
 pub mod chunk_generation_plugin;
 pub mod chunk_generation_result;
 pub mod chunk_lod;
+pub mod chunk_mesh_cache;
+pub mod chunk_mesh_mode;
 pub mod chunk_start;
 pub mod chunk_task;
 pub mod chunk_triangles;
 pub mod country;
+pub mod density;
+pub mod disk_cache_settings;
+pub mod generation_cache_diagnostics;
 pub mod mesh_generation;
 pub mod mesh_type;
 pub mod noise;
+pub mod palette_array;
+pub mod settlements;
 pub mod structures;
 pub mod terrain_mesh;
 pub mod voxel_data;
 pub mod voxel_generation;
+pub mod water;
 
 pub const CHUNK_SIZE: usize = 64;
 pub const VOXEL_SIZE: f32 = 0.5;
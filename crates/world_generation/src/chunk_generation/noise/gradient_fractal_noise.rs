@@ -1,7 +1,8 @@
 use std::f64::consts::E;
 
 use crate::chunk_generation::noise::{
-    noise_function::NoiseFunction, noise_result::NoiseResult,
+    noise_function::NoiseFunction, noise_point::NoisePoint,
+    noise_result::NoiseResult,
 };
 
 pub struct GFT<T> {
@@ -24,10 +25,7 @@ pub const DEFAULT_PERSISTENCE: f64 = 0.5;
 pub const DEFAULT_GRADIENT: f64 = 1.;
 pub const DEFAULT_AMPLITUDE: f64 = 1.;
 
-impl<T> GFT<T>
-where
-    T: NoiseFunction<NoiseResult, [f64; 2]>,
-{
+impl<T> GFT<T> {
     pub fn new_with_source(source: T) -> Self {
         Self {
             octaves: DEFAULT_OCTAVE_COUNT,
@@ -95,12 +93,13 @@ impl<T> GFT<T> {
     }
 }
 
-/// 2-dimensional Fbm noise
-impl<T> NoiseFunction<NoiseResult, [f64; 2]> for GFT<T>
+/// Fbm noise, in 2D or 3D
+impl<T, TInput> NoiseFunction<NoiseResult, TInput> for GFT<T>
 where
-    T: NoiseFunction<NoiseResult, [f64; 2]>,
+    T: NoiseFunction<NoiseResult, TInput>,
+    TInput: NoisePoint,
 {
-    fn get(&self, point: [f64; 2]) -> NoiseResult {
+    fn get(&self, point: TInput) -> NoiseResult {
         let mut result = NoiseResult::new_constant(0.);
         let mut total_flatness = 0.;
 
@@ -109,9 +108,7 @@ where
             let amplitude = self.amplitude * self.persistence.powi(x);
 
             // Get the signal.
-            let mut noise_value = self
-                .source
-                .get([point[0] * frequency, point[1] * frequency]);
+            let mut noise_value = self.source.get(point.scale(frequency));
 
             noise_value.derivative = [
                 noise_value.derivative[0] * frequency,
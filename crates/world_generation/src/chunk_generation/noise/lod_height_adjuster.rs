@@ -3,6 +3,14 @@ use crate::chunk_generation::{
     noise::{noise_function::NoiseFunction, noise_result::NoiseResult},
 };
 
+/// Converts a height of the full LOD into the voxels of the given LOD,
+/// the same way the terrain height gets adjusted.
+pub fn adjust_height_to_lod(height: f64, lod: ChunkLod) -> f64 {
+    height * (1. / lod.multiplier_i32() as f64)
+        + 1.
+        + (10. / lod.multiplier_i32() as f64)
+}
+
 pub struct LodHeightAdjuster<T> {
     noise: T,
     lod: ChunkLod,
@@ -41,12 +49,9 @@ where
 {
     fn get(&self, point: [f64; 2]) -> NoiseResult {
         let result = self.noise.get(point);
-        let value = result.value * (1. / self.lod.multiplier_i32() as f64)
-            + 1.
-            + (10. / self.lod.multiplier_i32() as f64);
 
         NoiseResult {
-            value,
+            value: adjust_height_to_lod(result.value, self.lod),
             derivative: result.derivative,
         }
     }
@@ -10,6 +10,7 @@ pub mod max;
 pub mod multiply;
 pub mod negate;
 pub mod noise_function;
+pub mod noise_point;
 pub mod noise_result;
 pub mod power;
 pub mod scale_point;
@@ -0,0 +1,32 @@
+/// A position the noise functions can be sampled at. 2D points are
+/// `[x, z]` on the ground, 3D points are `[x, y, z]`.
+pub trait NoisePoint: Copy + Send + Sync + 'static {
+    fn scale(self, scale: f64) -> Self;
+
+    /// Moves the point along the ground.
+    fn translate(self, x: f64, z: f64) -> Self;
+}
+
+impl NoisePoint for [f64; 2] {
+    #[inline]
+    fn scale(self, scale: f64) -> Self {
+        [self[0] * scale, self[1] * scale]
+    }
+
+    #[inline]
+    fn translate(self, x: f64, z: f64) -> Self {
+        [self[0] + x, self[1] + z]
+    }
+}
+
+impl NoisePoint for [f64; 3] {
+    #[inline]
+    fn scale(self, scale: f64) -> Self {
+        [self[0] * scale, self[1] * scale, self[2] * scale]
+    }
+
+    #[inline]
+    fn translate(self, x: f64, z: f64) -> Self {
+        [self[0] + x, self[1], self[2] + z]
+    }
+}
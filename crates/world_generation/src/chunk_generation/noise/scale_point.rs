@@ -1,5 +1,6 @@
 use crate::chunk_generation::noise::{
-    noise_function::NoiseFunction, noise_result::NoiseResult,
+    noise_function::NoiseFunction, noise_point::NoisePoint,
+    noise_result::NoiseResult,
 };
 
 pub struct ScalePoint<T> {
@@ -13,14 +14,13 @@ impl<T> ScalePoint<T> {
     }
 }
 
-impl<T> NoiseFunction<NoiseResult, [f64; 2]> for ScalePoint<T>
+impl<T, TInput> NoiseFunction<NoiseResult, TInput> for ScalePoint<T>
 where
-    T: NoiseFunction<NoiseResult, [f64; 2]>,
+    T: NoiseFunction<NoiseResult, TInput>,
+    TInput: NoisePoint,
 {
-    fn get(&self, input: [f64; 2]) -> NoiseResult {
-        let result = self
-            .source
-            .get([input[0] * self.scale, input[1] * self.scale]);
+    fn get(&self, input: TInput) -> NoiseResult {
+        let result = self.source.get(input.scale(self.scale));
 
         NoiseResult {
             value: result.value,
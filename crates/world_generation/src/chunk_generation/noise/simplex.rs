@@ -1,4 +1,7 @@
-use noise::{permutationtable::PermutationTable, simplex::simplex_2d};
+use noise::{
+    permutationtable::PermutationTable,
+    simplex::{simplex_2d, simplex_3d},
+};
 
 use crate::chunk_generation::noise::{
     noise_function::NoiseFunction, noise_result::NoiseResult,
@@ -32,3 +35,16 @@ impl NoiseFunction<NoiseResult, [f64; 2]> for Simplex {
         }
     }
 }
+
+/// The derivative only holds the slope along the ground, `[x, z]`, so the
+/// 3D noise can be combined with the 2D ones.
+impl NoiseFunction<NoiseResult, [f64; 3]> for Simplex {
+    fn get(&self, input: [f64; 3]) -> NoiseResult {
+        let (value, derivative) = simplex_3d(input.into(), &self.hasher);
+
+        NoiseResult {
+            value,
+            derivative: [derivative[0], derivative[2]],
+        }
+    }
+}
@@ -6,7 +6,7 @@ use crate::chunk_generation::noise::{
     terrain_noise_type::TerrainNoiseType,
 };
 
-pub const TERRAIN_NOISE_FILE_PATH: &'static str = "assets/terrain_noise.ron";
+pub const TERRAIN_NOISE_FILE_PATH: &'static str = "terrain_noise.ron";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TerrainNoise {
@@ -28,4 +28,12 @@ impl TerrainNoise {
     ) -> Box<dyn NoiseFunction<NoiseResult, [f64; 2]> + Send + Sync> {
         self.noise_types[self.start_index].to_noise_fn(&self.noise_types, rng)
     }
+
+    /// Samples the same node graph with `[x, y, z]` points.
+    pub fn get_noise_fn_3d(
+        &self,
+        rng: &mut impl Rng,
+    ) -> Box<dyn NoiseFunction<NoiseResult, [f64; 3]> + Send + Sync> {
+        self.noise_types[self.start_index].to_noise_fn(&self.noise_types, rng)
+    }
 }
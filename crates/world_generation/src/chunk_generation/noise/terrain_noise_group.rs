@@ -6,6 +6,12 @@ use crate::chunk_generation::noise::terrain_noise::TerrainNoise;
 pub struct TerrainNoiseGroup {
     pub terrain_height: TerrainNoise,
     pub grass_hue: TerrainNoise,
+    // Biomes
+    pub temperature: TerrainNoise,
+    pub humidity: TerrainNoise,
+    // Density, sampled with [x, y, z] points
+    pub overhang_density: TerrainNoise,
+    pub cave_density: TerrainNoise,
     // Oak
     pub oak_min_thickness: TerrainNoise,
     pub oak_max_length: TerrainNoise,
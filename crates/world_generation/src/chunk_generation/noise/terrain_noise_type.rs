@@ -7,8 +7,9 @@ use crate::chunk_generation::{
     noise::{
         abs::Abs, add::Add, constant::Constant, gradient_fractal_noise::GFT,
         map_range::MapRange, max::Max, multiply::Multiply, negate::Negate,
-        noise_function::NoiseFunction, noise_result::NoiseResult, power::Power,
-        scale_point::ScalePoint, simplex::Simplex, smooth_step::SmoothStep,
+        noise_function::NoiseFunction, noise_point::NoisePoint,
+        noise_result::NoiseResult, power::Power, scale_point::ScalePoint,
+        simplex::Simplex, smooth_step::SmoothStep,
         translate_point::TranslatePoint,
     },
 };
@@ -116,11 +117,15 @@ impl ConstantValue {
 }
 
 impl TerrainNoiseType {
-    pub fn to_noise_fn(
+    pub fn to_noise_fn<TInput>(
         &self,
         noise_types: &Vec<TerrainNoiseType>,
         rng: &mut impl Rng,
-    ) -> Box<dyn NoiseFunction<NoiseResult, [f64; 2]> + Send + Sync> {
+    ) -> Box<dyn NoiseFunction<NoiseResult, TInput> + Send + Sync>
+    where
+        TInput: NoisePoint,
+        Simplex: NoiseFunction<NoiseResult, TInput>,
+    {
         match self {
             TerrainNoiseType::Simplex { seed_index } => Box::new(Simplex::new(
                 noise_types[*seed_index].to_i64_value(noise_types, rng) as u32,
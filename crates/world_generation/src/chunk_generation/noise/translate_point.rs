@@ -1,5 +1,6 @@
 use crate::chunk_generation::noise::{
-    noise_function::NoiseFunction, noise_result::NoiseResult,
+    noise_function::NoiseFunction, noise_point::NoisePoint,
+    noise_result::NoiseResult,
 };
 
 pub struct TranslatePoint<T> {
@@ -18,12 +19,13 @@ impl<T> TranslatePoint<T> {
     }
 }
 
-impl<T> NoiseFunction<NoiseResult, [f64; 2]> for TranslatePoint<T>
+impl<T, TInput> NoiseFunction<NoiseResult, TInput> for TranslatePoint<T>
 where
-    T: NoiseFunction<NoiseResult, [f64; 2]>,
+    T: NoiseFunction<NoiseResult, TInput>,
+    TInput: NoisePoint,
 {
-    fn get(&self, input: [f64; 2]) -> NoiseResult {
+    fn get(&self, input: TInput) -> NoiseResult {
         self.source
-            .get([input[0] + self.x_translation, input[1] + self.y_translation])
+            .get(input.translate(self.x_translation, self.y_translation))
     }
 }
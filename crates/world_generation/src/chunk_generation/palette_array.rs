@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chunk_generation::block_type::BlockType;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed number of blocks, stored as indexes into a palette of the blocks
+/// that actually occur. The indexes use as few bits as the palette needs, so
+/// a chunk of only air or stone takes up no more than its palette.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PaletteArray {
+    len: usize,
+    palette: Vec<BlockType>,
+    /// Always a power of two, so an index never spans two words. Zero while
+    /// every block is the first palette entry.
+    bits_per_index: usize,
+    words: Vec<u64>,
+}
+
+impl PaletteArray {
+    pub fn new(len: usize, block: BlockType) -> Self {
+        Self {
+            len,
+            palette: vec![block],
+            bits_per_index: 0,
+            words: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The block every entry holds, if they are all the same.
+    pub fn get_uniform_block(&self) -> Option<BlockType> {
+        (self.bits_per_index == 0).then_some(self.palette[0])
+    }
+
+    pub fn get(&self, index: usize) -> BlockType {
+        self.palette[self.get_palette_index(index)]
+    }
+
+    pub fn set(&mut self, index: usize, block: BlockType) {
+        assert!(index < self.len, "Index {} out of bounds", index);
+
+        let palette_index =
+            match self.palette.iter().position(|entry| *entry == block) {
+                Some(palette_index) => palette_index,
+                None => {
+                    self.palette.push(block);
+                    if self.palette.len() > 1 << self.bits_per_index {
+                        self.repack(Self::get_bits_for(self.palette.len()));
+                    }
+                    self.palette.len() - 1
+                }
+            };
+
+        if self.bits_per_index == 0 {
+            return;
+        }
+
+        self.set_palette_index(index, palette_index);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = BlockType> + '_ {
+        (0..self.len).map(|index| self.get(index))
+    }
+
+    /// Drops palette entries that are no longer used and shrinks the indexes
+    /// to match, down to nothing for uniform arrays.
+    pub fn compact(&mut self) {
+        if self.bits_per_index == 0 {
+            return;
+        }
+
+        let mut used = vec![false; self.palette.len()];
+        for index in 0..self.len {
+            used[self.get_palette_index(index)] = true;
+        }
+
+        if used.iter().all(|used| *used) {
+            return;
+        }
+
+        let mut palette = Vec::new();
+        let remapped_indexes: Vec<usize> = used
+            .iter()
+            .zip(&self.palette)
+            .map(|(used, block)| {
+                if *used {
+                    palette.push(*block);
+                }
+                palette.len().saturating_sub(1)
+            })
+            .collect();
+
+        let old = std::mem::replace(self, Self::new(self.len, palette[0]));
+        if palette.len() == 1 {
+            return;
+        }
+
+        self.palette = palette;
+        self.repack(Self::get_bits_for(self.palette.len()));
+        for index in 0..self.len {
+            self.set_palette_index(
+                index,
+                remapped_indexes[old.get_palette_index(index)],
+            );
+        }
+    }
+
+    fn get_palette_index(&self, index: usize) -> usize {
+        if self.bits_per_index == 0 {
+            return 0;
+        }
+
+        let bit = index * self.bits_per_index;
+        let mask = (1 << self.bits_per_index) - 1;
+        ((self.words[bit / WORD_BITS] >> (bit % WORD_BITS)) & mask) as usize
+    }
+
+    fn set_palette_index(&mut self, index: usize, palette_index: usize) {
+        let bit = index * self.bits_per_index;
+        let mask = ((1 << self.bits_per_index) - 1) << (bit % WORD_BITS);
+        let word = &mut self.words[bit / WORD_BITS];
+        *word = (*word & !mask) | ((palette_index as u64) << (bit % WORD_BITS));
+    }
+
+    fn repack(&mut self, bits_per_index: usize) {
+        let old = std::mem::replace(
+            self,
+            Self {
+                len: self.len,
+                palette: self.palette.clone(),
+                bits_per_index,
+                words: vec![0; (self.len * bits_per_index).div_ceil(WORD_BITS)],
+            },
+        );
+
+        if old.bits_per_index == 0 {
+            return;
+        }
+
+        for index in 0..self.len {
+            self.set_palette_index(index, old.get_palette_index(index));
+        }
+    }
+
+    fn get_bits_for(palette_len: usize) -> usize {
+        let bits = (usize::BITS - (palette_len - 1).leading_zeros()) as usize;
+        bits.next_power_of_two()
+    }
+}
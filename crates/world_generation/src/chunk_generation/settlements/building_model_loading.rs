@@ -0,0 +1,147 @@
+use bevy::{asset::UntypedAssetId, prelude::*};
+
+use crate::{
+    chunk_generation::{
+        settlements::{
+            building_template::BuildingTemplate,
+            settlement_options::BuildingModel,
+        },
+        structures::{
+            structure_model::StructureModel, vox_structure::VoxStructure,
+        },
+    },
+    generation_options::GenerationOptions,
+};
+
+/// The models of the building sources that are still loading, in the order
+/// of the sources. Chunks wait for them, since villages are built from them.
+#[derive(Component)]
+pub struct LoadingBuildingModels(Vec<BuildingModelHandle>);
+
+enum BuildingModelHandle {
+    Vox(Handle<VoxStructure>),
+    StructureModel(Handle<StructureModel>),
+}
+
+impl BuildingModelHandle {
+    fn get_id(&self) -> UntypedAssetId {
+        match self {
+            BuildingModelHandle::Vox(handle) => handle.id().untyped(),
+            BuildingModelHandle::StructureModel(handle) => {
+                handle.id().untyped()
+            }
+        }
+    }
+}
+
+/// The templates aren't sent along with the generation options, so every
+/// peer loads them from the building sources it got.
+pub fn load_building_models(
+    mut commands: Commands,
+    changed_generation_options: Query<
+        (Entity, &GenerationOptions),
+        Changed<GenerationOptions>,
+    >,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, generation_options) in &changed_generation_options {
+        if !generation_options.building_templates.is_empty()
+            || generation_options.settlement_options.buildings.is_empty()
+        {
+            continue;
+        }
+
+        let handles = generation_options
+            .settlement_options
+            .buildings
+            .iter()
+            .map(|building_source| match &building_source.model {
+                BuildingModel::Vox { path } => {
+                    BuildingModelHandle::Vox(asset_server.load(path))
+                }
+                BuildingModel::StructureModel { path } => {
+                    BuildingModelHandle::StructureModel(asset_server.load(path))
+                }
+            })
+            .collect();
+
+        commands
+            .entity(entity)
+            .insert(LoadingBuildingModels(handles));
+    }
+}
+
+pub fn set_building_templates(
+    mut commands: Commands,
+    mut loading_generation_options: Query<(
+        Entity,
+        &mut GenerationOptions,
+        &LoadingBuildingModels,
+    )>,
+    asset_server: Res<AssetServer>,
+    vox_structures: Res<Assets<VoxStructure>>,
+    structure_models: Res<Assets<StructureModel>>,
+) {
+    for (entity, mut generation_options, loading_building_models) in
+        &mut loading_generation_options
+    {
+        let is_loading = loading_building_models.0.iter().any(|handle| {
+            let load_state = asset_server.load_state(handle.get_id());
+            !load_state.is_loaded() && !load_state.is_failed()
+        });
+        if is_loading {
+            continue;
+        }
+
+        let mut building_templates = vec![];
+        for (building_source, handle) in generation_options
+            .settlement_options
+            .buildings
+            .iter()
+            .zip(&loading_building_models.0)
+        {
+            let models = match handle {
+                BuildingModelHandle::Vox(handle) => {
+                    vox_structures.get(handle).map(|vox_structure| {
+                        vox_structure.get_structure_models(
+                            &generation_options.vox_palette,
+                        )
+                    })
+                }
+                BuildingModelHandle::StructureModel(handle) => structure_models
+                    .get(handle)
+                    .map(|structure_model| vec![structure_model.clone()]),
+            };
+
+            let Some(models) = models else {
+                error!(
+                    "Failed loading building model {:?}",
+                    building_source.model
+                );
+                continue;
+            };
+
+            match BuildingTemplate::from_structure_models(
+                building_source,
+                models,
+            ) {
+                Ok(templates) => building_templates.extend(templates),
+                Err(err) => error!("Failed loading building template: {}", err),
+            }
+        }
+
+        commands.entity(entity).remove::<LoadingBuildingModels>();
+
+        // Without any template the options stay unchanged, so they don't get
+        // loaded again.
+        if !building_templates.is_empty() {
+            generation_options.building_templates = building_templates;
+        }
+    }
+}
+
+pub fn building_models_loaded(
+    loading_building_models: Query<(), With<LoadingBuildingModels>>,
+) -> bool {
+    loading_building_models.is_empty()
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use utils::file_utils::{get_asset_path, read_ron_from_file};
+
+use crate::chunk_generation::{
+    block_type::BlockType,
+    settlements::settlement_options::{
+        BuildingModel, BuildingSource, EntranceSide,
+    },
+    structures::{
+        structure_model::StructureModel,
+        vox_structure::{VoxPalette, VoxStructure},
+    },
+};
+
+/// A building model villages are laid out from, indexed like the structure
+/// models as `[x][y][z]`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BuildingTemplate {
+    pub blocks: Arc<Vec<Vec<Vec<BlockType>>>>,
+    pub size: IVec3,
+    pub entrance: EntranceSide,
+}
+
+impl BuildingTemplate {
+    /// Reads all templates of a building source directly, for world
+    /// generation running without an asset server.
+    pub fn load(
+        building_source: &BuildingSource,
+        vox_palette: &VoxPalette,
+    ) -> Result<Vec<Self>, anyhow::Error> {
+        let structure_models = match &building_source.model {
+            BuildingModel::Vox { path } => {
+                VoxStructure::load(get_asset_path(path))?
+                    .get_structure_models(vox_palette)
+            }
+            BuildingModel::StructureModel { path } => {
+                vec![read_ron_from_file::<StructureModel>(get_asset_path(
+                    path,
+                ))?]
+            }
+        };
+
+        Self::from_structure_models(building_source, structure_models)
+    }
+
+    /// Makes a template of every model of a building source.
+    pub fn from_structure_models(
+        building_source: &BuildingSource,
+        structure_models: Vec<StructureModel>,
+    ) -> Result<Vec<Self>, anyhow::Error> {
+        if structure_models.is_empty() {
+            return Err(anyhow!("{:?} has no models", building_source.model));
+        }
+
+        Ok(structure_models
+            .into_iter()
+            .map(|structure_model| Self {
+                blocks: Arc::new(structure_model.blocks),
+                size: structure_model.model_size,
+                entrance: building_source.entrance,
+            })
+            .collect())
+    }
+
+    /// The quarter turns around the Y axis that make the entrance look
+    /// into `facing`.
+    pub fn get_rotation_towards(&self, facing: IVec2) -> u8 {
+        (0..4)
+            .find(|rotation| {
+                turn_direction(self.entrance.to_direction(), *rotation)
+                    == facing
+            })
+            .unwrap_or(0)
+    }
+
+    /// The size of the template after turning it.
+    pub fn get_turned_size(&self, rotation: u8) -> IVec3 {
+        if rotation.is_multiple_of(2) {
+            self.size
+        } else {
+            IVec3::new(self.size.z, self.size.y, self.size.x)
+        }
+    }
+
+    /// Looks up a block by its position inside the turned template.
+    pub fn get_turned_block(
+        &self,
+        turned_position: IVec3,
+        rotation: u8,
+    ) -> BlockType {
+        let IVec3 { x, y, z } = turned_position;
+        let size = self.size;
+        let [template_x, template_z] = match rotation % 4 {
+            0 => [x, z],
+            1 => [z, size.z - 1 - x],
+            2 => [size.x - 1 - x, size.z - 1 - z],
+            _ => [size.x - 1 - z, x],
+        };
+
+        self.blocks[template_x as usize][y as usize][template_z as usize]
+    }
+}
+
+/// Turns a direction by quarter turns around the Y axis, the same way
+/// [`BuildingTemplate::get_turned_block`] turns the template.
+pub fn turn_direction(direction: IVec2, rotation: u8) -> IVec2 {
+    (0..rotation % 4).fold(direction, |direction, _| {
+        IVec2::new(-direction.y, direction.x)
+    })
+}
@@ -0,0 +1,4 @@
+pub mod building_model_loading;
+pub mod building_template;
+pub mod settlement_options;
+pub mod settlement_sampler;
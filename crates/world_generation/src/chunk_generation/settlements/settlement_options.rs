@@ -0,0 +1,72 @@
+use bevy::math::IVec2;
+use serde::{Deserialize, Serialize};
+
+pub const SETTLEMENT_OPTIONS_FILE_PATH: &str = "settlement_options.ron";
+
+/// How villages get laid out around the city of every country. Distances
+/// and heights are in voxels of the full LOD.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SettlementOptions {
+    /// The buildings villages are built from.
+    pub buildings: Vec<BuildingSource>,
+    /// How many buildings are tried on each side of every road.
+    pub buildings_per_road: usize,
+    /// How far from the city location the first buildings are placed.
+    pub plaza_radius: f32,
+    /// The distance along a road between two buildings on the same side.
+    pub building_spacing: f32,
+    /// The distance between the middle of a road and the building fronts.
+    pub road_clearance: f32,
+    /// How many roads get laid out when no paths lead to the city.
+    pub fallback_roads: usize,
+    /// How long the roads of villages without paths are.
+    pub fallback_road_length: f32,
+    /// How far around a building the terrain is blended to its floor.
+    pub flatten_margin: i32,
+    /// Buildings where the terrain is further away from their floor than
+    /// this are left out, so they don't end up on cliffs.
+    pub max_ground_difference: i32,
+}
+
+/// Where a building template comes from.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum BuildingModel {
+    /// A MagicaVoxel file, with its colors turned into blocks by the vox
+    /// palette. Every model in the file becomes its own template. Paths are
+    /// relative to the asset root.
+    Vox { path: String },
+    /// A RON file of a `StructureModel`, ending in `.structure.ron`.
+    StructureModel { path: String },
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BuildingSource {
+    pub model: BuildingModel,
+    /// The side of the model the entrance is on, which gets turned towards
+    /// the road.
+    pub entrance: EntranceSide,
+}
+
+/// A side of a building model, looking down from above.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum EntranceSide {
+    /// Towards positive Z.
+    Front,
+    /// Towards negative Z.
+    Back,
+    /// Towards positive X.
+    Right,
+    /// Towards negative X.
+    Left,
+}
+
+impl EntranceSide {
+    pub fn to_direction(&self) -> IVec2 {
+        match self {
+            Self::Front => IVec2::Y,
+            Self::Back => IVec2::NEG_Y,
+            Self::Right => IVec2::X,
+            Self::Left => IVec2::NEG_X,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::{
+    chunk_generation::{
+        block_type::BlockType,
+        country::{
+            country_data::CountryData,
+            settlement_data::{Building, SettlementData},
+        },
+        settlements::{
+            building_template::BuildingTemplate,
+            settlement_options::SettlementOptions,
+        },
+    },
+    generation_options::GenerationOptions,
+};
+
+/// How the terrain of a column gets moved to the floor of a building.
+pub struct GroundFlattening {
+    /// The floor height in voxels of the full LOD.
+    pub height: f32,
+    /// How much of the flattening applies, from 0 at the outer edge of the
+    /// margin to 1 under the building.
+    pub strength: f32,
+}
+
+/// The part of a building standing on one terrain column.
+pub struct BuildingColumn<'a> {
+    pub building: &'a Building,
+    template: &'a BuildingTemplate,
+    local_position: IVec2,
+}
+
+impl BuildingColumn<'_> {
+    pub fn get_height(&self) -> i32 {
+        self.building.size.y
+    }
+
+    /// The block at a height above the floor of the building.
+    pub fn get_block(&self, height: i32) -> BlockType {
+        self.template.get_turned_block(
+            IVec3::new(self.local_position.x, height, self.local_position.y),
+            self.building.rotation,
+        )
+    }
+}
+
+/// Looks up the village of a country for terrain columns.
+pub struct SettlementSampler<'a> {
+    settlement_options: &'a SettlementOptions,
+    building_templates: &'a [BuildingTemplate],
+    settlement_data: &'a SettlementData,
+}
+
+impl<'a> SettlementSampler<'a> {
+    pub fn new(
+        generation_options: &'a GenerationOptions,
+        country_data: &'a CountryData,
+    ) -> Self {
+        Self {
+            settlement_options: &generation_options.settlement_options,
+            building_templates: &generation_options.building_templates,
+            settlement_data: &country_data.settlement_cache,
+        }
+    }
+
+    /// How far below the height map the ground under a building can be,
+    /// for the columns from `start` to `end`. Zero outside of the village,
+    /// so chunks there keep their height.
+    pub fn get_max_flatten_depth(&self, start: IVec2, end: IVec2) -> i32 {
+        let settlement_data = self.settlement_data;
+        if settlement_data.buildings.is_empty()
+            || start.cmpgt(settlement_data.box_pos_end).any()
+            || end.cmplt(settlement_data.box_pos_start).any()
+        {
+            return 0;
+        }
+
+        self.settlement_options.max_ground_difference.max(0) + 1
+    }
+
+    pub fn get_flattening(&self, position: IVec2) -> Option<GroundFlattening> {
+        if !self.settlement_data.is_in_box(position) {
+            return None;
+        }
+
+        let margin = self.settlement_options.flatten_margin.max(1) as f32;
+
+        self.settlement_data
+            .buildings
+            .iter()
+            .map(|building| GroundFlattening {
+                height: building.position.y as f32,
+                strength: 1. - building.get_distance_outside(position) / margin,
+            })
+            .filter(|flattening| flattening.strength > 0.)
+            .max_by(|a, b| a.strength.total_cmp(&b.strength))
+    }
+
+    pub fn get_building_column(
+        &self,
+        position: IVec2,
+    ) -> Option<BuildingColumn<'a>> {
+        if !self.settlement_data.is_in_box(position) {
+            return None;
+        }
+
+        let building = self
+            .settlement_data
+            .buildings
+            .iter()
+            .find(|building| building.contains_column(position))?;
+
+        Some(BuildingColumn {
+            building,
+            template: &self.building_templates[building.template_index],
+            local_position: position - building.position.xz(),
+        })
+    }
+}
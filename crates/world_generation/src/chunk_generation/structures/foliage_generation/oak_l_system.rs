@@ -52,8 +52,8 @@ impl LSystem<OakEntryType, OakOptions> for OakLSystem {
 
     fn get_block_from_entry(entry: &LSystemEntry<OakEntryType>) -> BlockType {
         match entry.entry_type {
-            OakEntryType::Leaf => BlockType::Leaf,
-            _ => BlockType::Log,
+            OakEntryType::Leaf => BlockType::LEAF,
+            _ => BlockType::LOG,
         }
     }
 
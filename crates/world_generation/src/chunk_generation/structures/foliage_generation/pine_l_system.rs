@@ -103,8 +103,8 @@ impl LSystem<PineEntryType, PineOptions> for PineLSystem {
 
     fn get_block_from_entry(entry: &LSystemEntry<PineEntryType>) -> BlockType {
         match entry.entry_type {
-            PineEntryType::Needle => BlockType::PineNeedle,
-            _ => BlockType::PineLog,
+            PineEntryType::Needle => BlockType::PINE_NEEDLE,
+            _ => BlockType::PINE_LOG,
         }
     }
 
@@ -43,7 +43,7 @@ pub trait LSystem<EntryEnum: Clone + Copy, GrowOptions> {
             for y in 0..size.y {
                 voxel_grid[x].push(vec![]);
                 for _ in 0..size.x {
-                    voxel_grid[x][y].push(BlockType::Air);
+                    voxel_grid[x][y].push(BlockType::AIR);
                 }
             }
         }
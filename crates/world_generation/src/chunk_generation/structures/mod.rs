@@ -6,3 +6,4 @@ pub mod structure_generator;
 pub mod structure_generators;
 pub mod structure_model;
 pub mod tree_structure_generator;
+pub mod vox_structure;
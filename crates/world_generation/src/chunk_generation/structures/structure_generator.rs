@@ -1,13 +1,24 @@
-use bevy::math::IVec2;
+use bevy::math::{IVec2, IVec3};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::Arc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::Arc,
+};
+use utils::stable_hash::get_stable_hash;
 
 use crate::chunk_generation::{
     block_type::BlockType,
+    cache_statistics::CacheStatistics,
     chunk_lod::ChunkLod,
     noise::terrain_noise::TerrainNoise,
     structures::{
-        noise_wrapper::NoiseWrapper, structure_generators::StructureGenerators,
+        noise_wrapper::NoiseWrapper,
+        structure_generators::{StructureGenerators, StructureKind},
+        structure_model::StructureModel,
+        vox_structure::{VoxPalette, VoxStructure},
     },
 };
 
@@ -66,9 +77,71 @@ pub trait StructureGenerator {
     ) -> Rc<Vec<Vec<Vec<BlockType>>>>;
 }
 
+/// Places models that were made by hand, picking one of them for every
+/// structure position.
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct FixedStructureGenerator {
     pub fixed_structure_metadata: VoxelStructureMetadata,
-    pub fixed_structure_model: Arc<Vec<Vec<Vec<BlockType>>>>,
+    pub fixed_structure_models: Vec<Arc<Vec<Vec<Vec<BlockType>>>>>,
+}
+
+impl FixedStructureGenerator {
+    /// Takes over the size of the largest model. Smaller ones get centered
+    /// on it and stand on its bottom, so all models fill the same space.
+    pub fn new(
+        mut metadata: VoxelStructureMetadata,
+        structure_models: Vec<StructureModel>,
+    ) -> Self {
+        let model_size = structure_models
+            .iter()
+            .fold(IVec3::ZERO, |size, model| size.max(model.model_size));
+        metadata.model_size = model_size.to_array();
+
+        Self {
+            fixed_structure_metadata: metadata,
+            fixed_structure_models: structure_models
+                .into_iter()
+                .map(|model| Arc::new(Self::pad_model(model, model_size)))
+                .collect(),
+        }
+    }
+
+    pub fn from_vox_structure(
+        metadata: VoxelStructureMetadata,
+        vox_structure: &VoxStructure,
+        vox_palette: &VoxPalette,
+    ) -> Self {
+        Self::new(metadata, vox_structure.get_structure_models(vox_palette))
+    }
+
+    fn pad_model(
+        model: StructureModel,
+        model_size: IVec3,
+    ) -> Vec<Vec<Vec<BlockType>>> {
+        if model.model_size == model_size {
+            return model.blocks;
+        }
+
+        let offset = (model_size - model.model_size) / 2;
+        let mut blocks = vec![
+            vec![
+                vec![BlockType::AIR; model_size.z as usize];
+                model_size.y as usize
+            ];
+            model_size.x as usize
+        ];
+
+        for (x, plane) in model.blocks.into_iter().enumerate() {
+            for (y, column) in plane.into_iter().enumerate() {
+                for (z, block) in column.into_iter().enumerate() {
+                    blocks[x + offset.x as usize][y][z + offset.z as usize] =
+                        block;
+                }
+            }
+        }
+
+        blocks
+    }
 }
 
 impl StructureGenerator for FixedStructureGenerator {
@@ -78,15 +151,41 @@ impl StructureGenerator for FixedStructureGenerator {
 
     fn get_structure_model(
         &self,
-        _: IVec2,
+        structure_position: IVec2,
         _: ChunkLod,
     ) -> Rc<Vec<Vec<Vec<BlockType>>>> {
-        Rc::new(self.fixed_structure_model.to_vec())
+        if self.fixed_structure_models.is_empty() {
+            return Rc::new(vec![]);
+        }
+
+        let model_index = get_stable_hash([
+            &self.fixed_structure_metadata.noise.seed.to_le_bytes()[..],
+            &structure_position.x.to_le_bytes(),
+            &structure_position.y.to_le_bytes(),
+        ]) as usize
+            % self.fixed_structure_models.len();
+
+        Rc::new(self.fixed_structure_models[model_index].to_vec())
     }
 }
 
+/// How many structure models a chunk task keeps before the least recently
+/// used ones get dropped.
+pub const STRUCTURE_MODEL_CACHE_CAPACITY: usize = 64;
+
+/// Shared by the structure generator caches of all chunk tasks.
+pub static STRUCTURE_MODEL_CACHE_STATISTICS: CacheStatistics =
+    CacheStatistics::new();
+
+struct StructureModelEntry {
+    model: Rc<Vec<Vec<Vec<BlockType>>>>,
+    last_used: u64,
+    memory_size: usize,
+}
+
 pub struct StructureGeneratorCache {
-    cache: RefCell<HashMap<IVec2, Rc<Vec<Vec<Vec<BlockType>>>>>>,
+    cache: RefCell<HashMap<IVec2, StructureModelEntry>>,
+    use_counter: Cell<u64>,
     structure_generator: Arc<Box<StructureGenerators>>,
 }
 
@@ -95,6 +194,62 @@ impl StructureGeneratorCache {
         Self {
             structure_generator: structure_generator.clone(),
             cache: RefCell::new(HashMap::new()),
+            use_counter: Cell::new(0),
+        }
+    }
+
+    pub fn get_kind(&self) -> StructureKind {
+        self.structure_generator.get_kind()
+    }
+
+    fn next_use(&self) -> u64 {
+        let use_counter = self.use_counter.get() + 1;
+        self.use_counter.set(use_counter);
+        use_counter
+    }
+
+    fn get_model_memory_size(model: &[Vec<Vec<BlockType>>]) -> usize {
+        model
+            .iter()
+            .flatten()
+            .map(|column| {
+                size_of::<Vec<BlockType>>()
+                    + column.capacity() * size_of::<BlockType>()
+            })
+            .sum()
+    }
+
+    /// Drops the least recently used models that no caller holds on to
+    /// anymore, until the cache fits into its capacity again.
+    fn evict(&self, cache: &mut HashMap<IVec2, StructureModelEntry>) {
+        if cache.len() <= STRUCTURE_MODEL_CACHE_CAPACITY {
+            return;
+        }
+
+        let evictable_positions = cache
+            .iter()
+            .filter(|(_, entry)| Rc::strong_count(&entry.model) == 1)
+            .sorted_by_key(|(_, entry)| entry.last_used)
+            .map(|(structure_position, _)| *structure_position)
+            .collect_vec();
+
+        for structure_position in evictable_positions {
+            if cache.len() <= STRUCTURE_MODEL_CACHE_CAPACITY {
+                break;
+            }
+
+            if let Some(entry) = cache.remove(&structure_position) {
+                STRUCTURE_MODEL_CACHE_STATISTICS
+                    .remove_entry(entry.memory_size);
+            }
+        }
+    }
+}
+
+impl Drop for StructureGeneratorCache {
+    fn drop(&mut self) {
+        for entry in self.cache.get_mut().values() {
+            STRUCTURE_MODEL_CACHE_STATISTICS.remove_entry(entry.memory_size);
         }
     }
 }
@@ -115,16 +270,32 @@ impl StructureGenerator for StructureGeneratorCache {
             structure_position
         };
 
+        let last_used = self.next_use();
         let mut cache = self.cache.borrow_mut();
-        if let Some(model) = cache.get(&structure_position) {
-            return model.clone();
+        if let Some(entry) = cache.get_mut(&structure_position) {
+            STRUCTURE_MODEL_CACHE_STATISTICS.add_hit();
+            entry.last_used = last_used;
+            return entry.model.clone();
         }
 
+        STRUCTURE_MODEL_CACHE_STATISTICS.add_miss();
+
         let model = self
             .structure_generator
             .get_structure_model(structure_position, lod);
 
-        cache.insert(structure_position, model.clone());
+        let memory_size = Self::get_model_memory_size(&model);
+        STRUCTURE_MODEL_CACHE_STATISTICS.add_entry(memory_size);
+        cache.insert(
+            structure_position,
+            StructureModelEntry {
+                model: model.clone(),
+                last_used,
+                memory_size,
+            },
+        );
+
+        self.evict(&mut cache);
 
         model
     }
@@ -8,7 +8,9 @@ use crate::chunk_generation::{
     structures::{
         oak_structure_generator::OakStructureGenerator,
         pine_structure_generator::PineStructureGenerator,
-        structure_generator::{StructureGenerator, VoxelStructureMetadata},
+        structure_generator::{
+            FixedStructureGenerator, StructureGenerator, VoxelStructureMetadata,
+        },
     },
 };
 
@@ -16,6 +18,25 @@ use crate::chunk_generation::{
 pub enum StructureGenerators {
     Oak(OakStructureGenerator),
     Pine(PineStructureGenerator),
+    Fixed(FixedStructureGenerator),
+}
+
+/// Which kind of structure a generator places, so biomes can pick them.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum StructureKind {
+    Oak,
+    Pine,
+    Fixed,
+}
+
+impl StructureGenerators {
+    pub fn get_kind(&self) -> StructureKind {
+        match self {
+            Self::Oak(_) => StructureKind::Oak,
+            Self::Pine(_) => StructureKind::Pine,
+            Self::Fixed(_) => StructureKind::Fixed,
+        }
+    }
 }
 
 impl StructureGenerator for StructureGenerators {
@@ -23,6 +44,7 @@ impl StructureGenerator for StructureGenerators {
         match self {
             Self::Oak(sg) => sg.get_structure_metadata(),
             Self::Pine(sg) => sg.get_structure_metadata(),
+            Self::Fixed(sg) => sg.get_structure_metadata(),
         }
     }
 
@@ -34,6 +56,7 @@ impl StructureGenerator for StructureGenerators {
         match self {
             Self::Oak(sg) => sg.get_structure_model(structure_position, lod),
             Self::Pine(sg) => sg.get_structure_model(structure_position, lod),
+            Self::Fixed(sg) => sg.get_structure_model(structure_position, lod),
         }
     }
 }
@@ -1,10 +1,39 @@
-use bevy::math::IVec3;
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::chunk_generation::block_type::BlockType;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Asset, TypePath, Clone, Serialize, Deserialize, Debug)]
 pub struct StructureModel {
     pub blocks: Vec<Vec<Vec<BlockType>>>,
     pub model_size: IVec3,
 }
+
+/// Loads `.structure.ron` files as [`StructureModel`] assets.
+#[derive(Default, TypePath)]
+pub struct StructureModelLoader;
+
+impl AssetLoader for StructureModelLoader {
+    type Asset = StructureModel;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["structure.ron"]
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use utils::file_utils::{get_asset_path, read_ron_from_file};
+use vox_format::{VoxData, types::Model};
+
+use crate::chunk_generation::{
+    block_type::BlockType, structures::structure_model::StructureModel,
+};
+
+pub const VOX_PALETTE_FILE_PATH: &str = "vox_palette.ron";
+
+/// Turns the colors of MagicaVoxel files into blocks. Every color becomes
+/// the block of the closest entry, so files don't have to use the exact
+/// colors listed here.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct VoxPalette {
+    pub entries: Vec<VoxPaletteEntry>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct VoxPaletteEntry {
+    /// The RGB color of the entry.
+    pub color: [u8; 3],
+    pub block: BlockType,
+}
+
+impl VoxPalette {
+    pub fn load() -> Result<Self, anyhow::Error> {
+        read_ron_from_file(get_asset_path(VOX_PALETTE_FILE_PATH))
+    }
+
+    pub fn get_block(&self, color: [u8; 3]) -> BlockType {
+        self.entries
+            .iter()
+            .min_by_key(|entry| {
+                entry
+                    .color
+                    .iter()
+                    .zip(color)
+                    .map(|(a, b)| (*a as i32 - b as i32).pow(2))
+                    .sum::<i32>()
+            })
+            .map_or(BlockType::STONE, |entry| entry.block)
+    }
+}
+
+/// All models of a MagicaVoxel file. The colors only become blocks through
+/// a [`VoxPalette`], so the same file can be used with any palette.
+#[derive(Asset, TypePath, Debug)]
+pub struct VoxStructure {
+    pub models: Vec<VoxModel>,
+}
+
+/// A model of a MagicaVoxel file, already turned to be Y-up.
+#[derive(Clone, Debug)]
+pub struct VoxModel {
+    pub model_size: IVec3,
+    /// The position and RGB color of every voxel that isn't empty.
+    pub voxels: Vec<(IVec3, [u8; 3])>,
+}
+
+impl VoxStructure {
+    pub fn from_vox_data(vox_data: &VoxData) -> Self {
+        Self {
+            models: vox_data
+                .models
+                .iter()
+                .map(|model| VoxModel::from_vox_model(vox_data, model))
+                .collect(),
+        }
+    }
+
+    /// Reads a file directly, for world generation running without an
+    /// asset server.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let vox_data = vox_format::from_file(path)?;
+        Ok(Self::from_vox_data(&vox_data))
+    }
+
+    pub fn get_structure_models(
+        &self,
+        vox_palette: &VoxPalette,
+    ) -> Vec<StructureModel> {
+        self.models
+            .iter()
+            .map(|model| model.get_structure_model(vox_palette))
+            .collect()
+    }
+}
+
+impl VoxModel {
+    /// MagicaVoxel models are Z-up while the engine is Y-up, so they get
+    /// turned around the X axis: their Z becomes the height and their Y
+    /// points towards negative Z. Turning instead of swapping keeps them from
+    /// getting mirrored.
+    fn from_vox_model(vox_data: &VoxData, model: &Model) -> Self {
+        let model_size = IVec3::new(
+            model.size.x as i32,
+            model.size.z as i32,
+            model.size.y as i32,
+        );
+
+        // The coordinates are stored as bytes, but read as signed ones.
+        let voxels = model
+            .voxels
+            .iter()
+            .map(|voxel| {
+                let position = IVec3::new(
+                    voxel.point.x as u8 as i32,
+                    voxel.point.z as u8 as i32,
+                    model_size.z - 1 - voxel.point.y as u8 as i32,
+                );
+                let color = vox_data.palette.get(voxel.color_index);
+
+                (position, [color.r, color.g, color.b])
+            })
+            .collect();
+
+        Self { model_size, voxels }
+    }
+
+    pub fn get_structure_model(
+        &self,
+        vox_palette: &VoxPalette,
+    ) -> StructureModel {
+        let mut blocks =
+            vec![
+                vec![
+                    vec![BlockType::AIR; self.model_size.z as usize];
+                    self.model_size.y as usize
+                ];
+                self.model_size.x as usize
+            ];
+
+        for (position, color) in &self.voxels {
+            blocks[position.x as usize][position.y as usize]
+                [position.z as usize] = vox_palette.get_block(*color);
+        }
+
+        StructureModel {
+            blocks,
+            model_size: self.model_size,
+        }
+    }
+}
+
+/// Loads `.vox` files as [`VoxStructure`] assets.
+#[derive(Default, TypePath)]
+pub struct VoxStructureLoader;
+
+impl AssetLoader for VoxStructureLoader {
+    type Asset = VoxStructure;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let vox_data = vox_format::from_slice(&bytes)?;
+
+        Ok(VoxStructure::from_vox_data(&vox_data))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["vox"]
+    }
+}
@@ -1,24 +1,27 @@
 use bevy::math::IVec3;
+use serde::{Deserialize, Serialize};
 
 use crate::chunk_generation::{
     ambient_occlusion::AmbiantOcclusion, block_type::BlockType,
-    mesh_generation::rotate_into_direction,
+    mesh_generation::rotate_into_direction, mesh_type::MeshType,
+    palette_array::PaletteArray,
 };
 
 use super::CHUNK_SIZE;
 
-pub type VoxelArray =
-    [BlockType; (CHUNK_SIZE + 2) * (CHUNK_SIZE + 2) * (CHUNK_SIZE + 2)];
+/// The blocks of a chunk plus a border of one block on every side.
+pub const VOXEL_COUNT: usize =
+    (CHUNK_SIZE + 2) * (CHUNK_SIZE + 2) * (CHUNK_SIZE + 2);
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct VoxelData {
-    pub array: VoxelArray,
+    blocks: PaletteArray,
 }
 
 impl Default for VoxelData {
     fn default() -> Self {
         Self {
-            array: [BlockType::Air;
-                (CHUNK_SIZE + 2) * (CHUNK_SIZE + 2) * (CHUNK_SIZE + 2)],
+            blocks: PaletteArray::new(VOXEL_COUNT, BlockType::AIR),
         }
     }
 }
@@ -26,7 +29,33 @@ impl Default for VoxelData {
 impl VoxelData {
     pub fn get_block<T: Into<IVec3>>(&self, position: T) -> BlockType {
         let index = Self::position_to_indexes(position);
-        self.array[index]
+        self.blocks.get(index)
+    }
+
+    /// The block filling the whole chunk, border included, if there is only
+    /// one.
+    pub fn get_uniform_block(&self) -> Option<BlockType> {
+        self.blocks.get_uniform_block()
+    }
+
+    /// Uniform chunks have nothing to mesh, unless their block shows faces
+    /// next to itself.
+    pub fn has_no_faces(&self) -> bool {
+        self.get_uniform_block().is_some_and(|block| {
+            block.get_mesh_type() == MeshType::Invisible
+                || block.is_covering_for(&block)
+        })
+    }
+
+    /// Every block in storage order, x first, then y, then z.
+    pub fn iter_blocks(&self) -> impl Iterator<Item = BlockType> + '_ {
+        self.blocks.iter()
+    }
+
+    /// Frees palette entries of blocks that got overwritten. Worth it once a
+    /// chunk is done changing, as uniform chunks skip most of the work.
+    pub fn compact(&mut self) {
+        self.blocks.compact();
     }
 
     pub fn get_block_mesh<T: Into<IVec3>>(&self, position: T) -> BlockType {
@@ -42,34 +71,12 @@ impl VoxelData {
             position
         };
         let index = Self::position_to_indexes(position);
-        self.array[index]
+        self.blocks.get(index)
     }
 
     pub fn set_block<T: Into<IVec3>>(&mut self, position: T, block: BlockType) {
         let index = Self::position_to_indexes(position);
-        self.array[index] = block;
-    }
-
-    pub fn is_next_to_air<T: Into<IVec3>>(&self, position: T) -> bool {
-        let position: IVec3 = position.into();
-
-        if self.array[Self::position_to_indexes(position + IVec3::X)]
-            == BlockType::Air
-            || self.array[Self::position_to_indexes(position - IVec3::X)]
-                == BlockType::Air
-            || self.array[Self::position_to_indexes(position + IVec3::Y)]
-                == BlockType::Air
-            || self.array[Self::position_to_indexes(position - IVec3::Y)]
-                == BlockType::Air
-            || self.array[Self::position_to_indexes(position + IVec3::Z)]
-                == BlockType::Air
-            || self.array[Self::position_to_indexes(position - IVec3::Z)]
-                == BlockType::Air
-        {
-            return true;
-        }
-
-        false
+        self.blocks.set(index, block);
     }
 
     fn position_to_indexes<T: Into<IVec3>>(position: T) -> usize {
@@ -90,10 +97,10 @@ impl VoxelData {
         let front = rotate_into_direction(IVec3::Z, direction);
 
         let get_corner_value = |right: IVec3, front: IVec3| -> u8 {
-            let side_1 = self.get_block(position + right) != BlockType::Air;
-            let side_2 = self.get_block(position + front) != BlockType::Air;
+            let side_1 = self.get_block(position + right).is_occluding();
+            let side_2 = self.get_block(position + front).is_occluding();
             let corner =
-                self.get_block(position + right + front) != BlockType::Air;
+                self.get_block(position + right + front).is_occluding();
 
             if side_1 && side_2 {
                 return 0;
@@ -6,20 +6,26 @@ use utils::div_floor::div_floor;
 use crate::{
     chunk_generation::{
         CHUNK_SIZE, VOXEL_SIZE,
+        biomes::biome_sampler::BiomeSampler,
         block_type::BlockType,
         chunk_lod::ChunkLod,
         country::{
             country_data::CountryData,
             path_data::{Path, PathLine},
         },
+        density::density_sampler::DensitySampler,
         noise::{
-            full_cache::FullCache, lod_height_adjuster::LodHeightAdjuster,
-            noise_function::NoiseFunction, noise_result::NoiseResult,
+            full_cache::FullCache,
+            lod_height_adjuster::{LodHeightAdjuster, adjust_height_to_lod},
+            noise_function::NoiseFunction,
+            noise_result::NoiseResult,
         },
+        settlements::settlement_sampler::SettlementSampler,
         structures::structure_generator::{
             StructureGenerator, StructureGeneratorCache,
         },
         voxel_data::VoxelData,
+        water::water_sampler::WaterSampler,
     },
     generation_options::GenerationOptions,
 };
@@ -36,20 +42,28 @@ pub fn generate_voxels(
         generation_options.get_terrain_noise(),
         chunk_lod,
     ));
-    let grass_hue_noise = generation_options
-        .terrain_noise_group
-        .grass_hue
-        .get_noise_fn(&mut generation_options.get_seeded_rng());
+    let biome_sampler = BiomeSampler::new(generation_options);
+    let density_sampler = DensitySampler::new(generation_options);
+    let water_sampler = WaterSampler::new(generation_options, country_data);
+    let settlement_sampler =
+        SettlementSampler::new(generation_options, country_data);
 
     let chunk_noise_offset =
         DVec2::new(position[0] as f64, position[2] as f64) * CHUNK_SIZE as f64;
+    let chunk_start = IVec2::new(position[0], position[2]) * CHUNK_SIZE as i32;
+    let chunk_end =
+        chunk_start + (CHUNK_SIZE as i32 + 2) * chunk_lod.multiplier_i32();
 
     let min_height =
         (get_min_in_noise_map(&terrain_noise, chunk_noise_offset, chunk_lod)
             as i32)
             - 2
             + position[1] * CHUNK_SIZE as i32
-            - 10 / chunk_lod.multiplier_i32();
+            - 10 / chunk_lod.multiplier_i32()
+            - density_sampler.get_reach_below() / chunk_lod.multiplier_i32()
+            - water_sampler.get_max_carve_depth() / chunk_lod.multiplier_i32()
+            - settlement_sampler.get_max_flatten_depth(chunk_start, chunk_end)
+                / chunk_lod.multiplier_i32();
 
     let mut generate_more: bool = false;
 
@@ -86,13 +100,13 @@ pub fn generate_voxels(
 
             let mut noise_height = noise_result.value as f32;
 
-            let is_snow =
-                noise_height * chunk_lod.multiplier_f32() > 3500. / VOXEL_SIZE;
-            let is_grass_steep = if is_snow {
-                steepness < 1.2
-            } else {
-                steepness < 1.
-            };
+            let biome_surface = biome_sampler.get_surface(
+                noise_position,
+                noise_height * chunk_lod.multiplier_f32(),
+                steepness,
+            );
+            let subsurface_depth =
+                biome_surface.subsurface_depth / chunk_lod.multiplier_i32();
 
             let (mut path_distance, closest_point_on_path, _, line) =
                 get_min_distance_to_path(
@@ -138,35 +152,136 @@ pub fn generate_voxels(
                 .max(noise_height - 10.);
             }
 
-            for y in min_height
-                ..noise_height.min((CHUNK_SIZE as i32 + 2 + min_height) as f32)
-                    as i32
-            {
+            let flattening =
+                settlement_sampler.get_flattening(IVec2::new(total_x, total_z));
+
+            if let Some(flattening) = &flattening {
+                let floor_height =
+                    adjust_height_to_lod(flattening.height as f64, chunk_lod)
+                        as f32;
+                noise_height =
+                    lerp(noise_height, floor_height, flattening.strength);
+            }
+
+            let water_column =
+                water_sampler.get_water_column(IVec2::new(total_x, total_z));
+
+            if let Some(carving) = &water_column.carving {
+                let carved_height =
+                    adjust_height_to_lod(carving.height as f64, chunk_lod)
+                        as f32;
+                noise_height = noise_height.min(lerp(
+                    noise_height,
+                    carved_height,
+                    carving.strength,
+                ));
+            }
+
+            let water_top = adjust_height_to_lod(
+                water_column.water_height as f64,
+                chunk_lod,
+            )
+            .floor() as i32;
+
+            let top_terrain = noise_height.floor() as i32;
+            let column_top = top_terrain
+                + density_sampler.get_reach_above()
+                    / chunk_lod.multiplier_i32();
+            let density_bottom = top_terrain
+                - density_sampler.get_reach_below()
+                    / chunk_lod.multiplier_i32();
+
+            // Water above the chunk doesn't need to be walked through
+            let column_top = column_top
+                .max(water_top.min(CHUNK_SIZE as i32 + 2 + min_height));
+
+            // Solid voxels between this one and the open air above
+            let mut covering_depth = 0;
+
+            for y in (min_height..column_top).rev() {
+                let depth = (top_terrain - 1 - y) * chunk_lod.multiplier_i32();
+
+                // Overhangs would fill river beds, banks and the ground
+                // around buildings back up
+                let is_solid = if is_path
+                    || water_column.carving.is_some()
+                    || flattening.is_some()
+                    || y < density_bottom
+                {
+                    depth >= 0
+                } else {
+                    density_sampler.is_solid(
+                        [
+                            total_x as f64,
+                            (y * chunk_lod.multiplier_i32()) as f64,
+                            total_z as f64,
+                        ],
+                        depth,
+                    )
+                };
+
+                let block = if is_solid {
+                    let block_depth = covering_depth;
+                    covering_depth += 1;
+
+                    match block_depth {
+                        _ if is_path => BlockType::DIRT,
+                        // Ground under water gets no surface block
+                        0 if y >= water_top => biome_surface.surface_block,
+                        depth if depth <= subsurface_depth => {
+                            biome_surface.subsurface_block
+                        }
+                        _ => BlockType::STONE,
+                    }
+                } else if density_sampler.is_open_air(depth) {
+                    covering_depth = 0;
+
+                    // Caves stay dry, only open air gets flooded
+                    if y >= water_top {
+                        continue;
+                    }
+                    BlockType::WATER
+                } else {
+                    continue;
+                };
+
+                if y >= CHUNK_SIZE as i32 + 2 + min_height {
+                    continue;
+                }
                 if y == CHUNK_SIZE as i32 + 1 + min_height {
                     generate_more = true;
                 }
-                blocks.set_block(
-                    [x as i32, y as i32 - min_height, z as i32],
-                    // BlockType::Gray((biome_noise.get([total_x as f64, total_z as f64]) * 255.) as u8)
-                    if is_path {
-                        BlockType::Dirt
-                    } else {
-                        if is_grass_steep
-                            && y + 1 == noise_height.floor() as i32
-                        {
-                            if is_snow {
-                                BlockType::Snow
-                            } else {
-                                BlockType::Grass(
-                                    grass_hue_noise.get(noise_position).value
-                                        as u8,
-                                )
-                            }
-                        } else {
-                            BlockType::Stone
-                        }
-                    },
-                );
+                blocks.set_block([x as i32, y - min_height, z as i32], block);
+            }
+
+            if let Some(building_column) = settlement_sampler
+                .get_building_column(IVec2::new(total_x, total_z))
+            {
+                let floor = building_column.building.position.y;
+
+                for height in 0..building_column.get_height() {
+                    let block_height = floor + height;
+                    if block_height.rem_euclid(chunk_lod.multiplier_i32()) != 0
+                    {
+                        continue;
+                    }
+
+                    let y = block_height.div_euclid(chunk_lod.multiplier_i32())
+                        - min_height;
+                    if y < 0 {
+                        continue;
+                    }
+                    if y >= CHUNK_SIZE as i32 + 2 {
+                        generate_more = true;
+                        break;
+                    }
+
+                    let block = building_column.get_block(height);
+                    if block == BlockType::AIR {
+                        continue;
+                    }
+                    blocks.set_block([x as i32, y, z as i32], block);
+                }
             }
 
             for structure_generator in &structure_generators {
@@ -194,7 +309,7 @@ pub fn generate_voxels(
                         - 1;
                     blocks.set_block(
                         [x as i32, top_terrain as i32, z as i32],
-                        BlockType::Stone,
+                        BlockType::STONE,
                     );
                 }
                 let mut rand = StdRng::seed_from_u64(
@@ -270,11 +385,49 @@ pub fn generate_voxels(
                         continue;
                     }
 
+                    if settlement_sampler
+                        .get_flattening(structure_center)
+                        .is_some()
+                    {
+                        continue;
+                    }
+
                     let noise_height = terrain_noise.get([
                         structure_noise_height_x as f64,
                         structure_noise_height_z as f64,
                     ]);
 
+                    if !biome_sampler.allows_structure(
+                        structure_center.as_dvec2().to_array(),
+                        (noise_height.value * chunk_lod.multiplier_i32() as f64)
+                            as f32,
+                        structure_generator.get_kind(),
+                    ) {
+                        continue;
+                    }
+
+                    if !water_sampler.is_dry(
+                        structure_center,
+                        (noise_height.value * chunk_lod.multiplier_i32() as f64)
+                            as f32,
+                    ) {
+                        continue;
+                    }
+
+                    // Caves and overhangs can take away the ground below
+                    if !density_sampler.is_solid(
+                        [
+                            structure_noise_height_x as f64,
+                            ((noise_height.value as i32 - 1)
+                                * chunk_lod.multiplier_i32())
+                                as f64,
+                            structure_noise_height_z as f64,
+                        ],
+                        0,
+                    ) {
+                        continue;
+                    }
+
                     for (index, sub_structure) in structure_generator
                         .get_structure_model(
                             IVec2 {
@@ -305,7 +458,7 @@ pub fn generate_voxels(
                         }
                         let structure_block =
                             sub_structure[structure_z as usize];
-                        if structure_block == BlockType::Air {
+                        if structure_block == BlockType::AIR {
                             continue;
                         }
                         if noise_height.value as i32 + chunk_index as i32
@@ -0,0 +1,3 @@
+pub mod water_options;
+pub mod water_overlap;
+pub mod water_sampler;
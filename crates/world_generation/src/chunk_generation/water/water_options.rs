@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+pub const WATER_OPTIONS_FILE_PATH: &str = "water_options.ron";
+
+/// Where the sea is and how rivers get traced. Heights and distances are
+/// in voxels of the full LOD.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct WaterOptions {
+    /// The height of the sea surface. Everything below it is flooded.
+    pub sea_level: i32,
+    /// How many springs every country tries to start a river from.
+    pub springs_per_country: usize,
+    /// Springs lower than this don't start a river.
+    pub min_spring_height: i32,
+    /// The distance between two traced points of a river.
+    pub river_step: i32,
+    /// Rivers that neither reach the sea nor a lake after this many steps
+    /// are dropped.
+    pub max_river_steps: usize,
+    /// The half width of a river at its spring.
+    pub spring_width: f32,
+    /// How much the half width grows with every step.
+    pub width_per_step: f32,
+    /// How deep the bed in the middle of a river is carved.
+    pub river_depth: i32,
+    /// How far around its lowest point a lake checks for a rim.
+    pub lake_radius: i32,
+    /// The deepest a lake can fill up above its lowest point.
+    pub max_lake_depth: i32,
+}
+
+impl WaterOptions {
+    /// The half width of a river at its last possible step.
+    pub fn get_max_half_width(&self) -> f32 {
+        self.spring_width + self.width_per_step * self.max_river_steps as f32
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use physics::{
+    collider::Collider, physics_object::DynamicPhysicsObject,
+    physics_position::PhysicsPosition,
+};
+
+use crate::{
+    chunk_generation::block_type::BlockType,
+    voxel_editing::voxel_ray_cast::VoxelRayCast,
+};
+
+/// Whether a collider at the given position overlaps a water block.
+pub fn is_in_water(
+    collider: &Collider,
+    position: Vec3,
+    voxel_ray_cast: &VoxelRayCast,
+) -> bool {
+    let (min, max) = collider.get_bounds(position);
+    voxel_ray_cast.is_block_in_box(min, max, BlockType::WATER)
+}
+
+/// Lets every dynamic physics object know whether it's in water.
+pub fn update_in_water(
+    physics_objects: Query<(
+        &mut DynamicPhysicsObject,
+        &PhysicsPosition,
+        &Collider,
+    )>,
+    voxel_ray_cast: VoxelRayCast,
+) {
+    for (mut physics_object, physics_position, collider) in physics_objects {
+        physics_object.in_water =
+            is_in_water(collider, **physics_position, &voxel_ray_cast);
+    }
+}
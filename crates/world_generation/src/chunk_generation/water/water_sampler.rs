@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+
+use crate::{
+    chunk_generation::{
+        country::{
+            country_data::CountryData,
+            river_data::{Lake, River, RiverData},
+        },
+        water::water_options::WaterOptions,
+    },
+    generation_options::GenerationOptions,
+};
+
+/// The water above one column of terrain. Heights are in voxels of the
+/// full LOD.
+pub struct WaterColumn {
+    /// The height of the water surface. Terrain above it stays dry.
+    pub water_height: f32,
+    /// How a river lowers the terrain of this column.
+    pub carving: Option<Carving>,
+}
+
+pub struct Carving {
+    /// The height the terrain gets lowered to.
+    pub height: f32,
+    /// How much of the lowering applies, from 0 at the outer edge of the
+    /// banks to 1 in the river bed.
+    pub strength: f32,
+}
+
+/// Looks up the sea, rivers and lakes of a country for terrain columns.
+pub struct WaterSampler<'a> {
+    water_options: &'a WaterOptions,
+    river_data: &'a RiverData,
+}
+
+impl<'a> WaterSampler<'a> {
+    pub fn new(
+        generation_options: &'a GenerationOptions,
+        country_data: &'a CountryData,
+    ) -> Self {
+        Self {
+            water_options: &generation_options.water_options,
+            river_data: &country_data.river_cache,
+        }
+    }
+
+    /// How far below the height map a river bed can be carved.
+    pub fn get_max_carve_depth(&self) -> i32 {
+        self.water_options.river_depth.max(0) + 1
+    }
+
+    pub fn get_water_column(&self, position: IVec2) -> WaterColumn {
+        let mut water_height = self.water_options.sea_level as f32;
+
+        for lake in &self.river_data.lakes {
+            if Self::is_in_lake(lake, position) {
+                water_height = water_height.max(lake.water_height);
+            }
+        }
+
+        let carving = self.get_closest_river_point(position).map(
+            |(river_water_height, distance)| {
+                if distance < 1. {
+                    water_height = water_height.max(river_water_height);
+
+                    // The bed gets deeper towards the middle.
+                    let depth = (self.water_options.river_depth - 1).max(0)
+                        as f32
+                        * (1. - distance * distance);
+
+                    Carving {
+                        height: river_water_height - 1. - depth,
+                        strength: 1.,
+                    }
+                } else {
+                    // The banks slope from the terrain down to just
+                    // above the water.
+                    let bank = 2. - distance;
+
+                    Carving {
+                        height: river_water_height + 1.,
+                        strength: bank * bank * (3. - 2. * bank),
+                    }
+                }
+            },
+        );
+
+        WaterColumn {
+            water_height,
+            carving,
+        }
+    }
+
+    /// Whether the terrain of the column is above all water and untouched
+    /// by rivers, so structures can stand on it.
+    pub fn is_dry(&self, position: IVec2, terrain_height: f32) -> bool {
+        let water_column = self.get_water_column(position);
+        water_column.carving.is_none()
+            && water_column.water_height < terrain_height
+    }
+
+    fn is_in_lake(lake: &Lake, position: IVec2) -> bool {
+        lake.center.distance_squared(position) < lake.radius * lake.radius
+    }
+
+    /// Returns the water height of the closest river and the distance to it,
+    /// relative to its half width. Rivers further away than their banks are
+    /// ignored.
+    fn get_closest_river_point(&self, position: IVec2) -> Option<(f32, f32)> {
+        let margin = IVec2::splat(
+            (self.water_options.get_max_half_width() * 2.).ceil() as i32,
+        );
+
+        self.river_data
+            .rivers
+            .iter()
+            .filter(|river| river.is_in_box(position, margin))
+            .filter_map(|river| {
+                Self::get_closest_point_on_river(river, position)
+            })
+            .filter(|(_, distance)| *distance < 2.)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    fn get_closest_point_on_river(
+        river: &River,
+        position: IVec2,
+    ) -> Option<(f32, f32)> {
+        let position = position.as_vec2();
+
+        river
+            .points
+            .windows(2)
+            .filter_map(|points| {
+                let [start, end] = points else {
+                    return None;
+                };
+
+                let line_start = start.position.as_vec2();
+                let line = end.position.as_vec2() - line_start;
+                let margin = start.half_width.max(end.half_width) * 2.;
+
+                if position
+                    .cmplt(line_start.min(line_start + line) - margin)
+                    .any()
+                    || position
+                        .cmpgt(line_start.max(line_start + line) + margin)
+                        .any()
+                {
+                    return None;
+                }
+
+                let t = ((position - line_start).dot(line)
+                    / line.length_squared().max(f32::EPSILON))
+                .clamp(0., 1.);
+                let distance = position.distance(line_start + line * t);
+                let half_width = start.half_width.lerp(end.half_width, t);
+
+                Some((
+                    start.water_height.lerp(end.water_height, t),
+                    distance / half_width,
+                ))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+}
@@ -61,6 +61,18 @@ impl ChunkLoader {
         let closest = lod_pos.get_closest_chunk_pos(chunk_pos, tree_pos);
         self.get_min_lod_for_chunk(closest, loader_pos)
     }
+
+    /// Whether the loader keeps the chunk tree from being unloaded.
+    pub fn keeps_loaded(
+        &self,
+        tree_pos: ChunkTreePos,
+        loader_pos: Vec3,
+    ) -> bool {
+        let loader_tree_pos = ChunkTreePos::from_global_pos(loader_pos.xz());
+
+        (tree_pos.x - loader_tree_pos.x).abs() < self.unload_range
+            && (tree_pos.y - loader_tree_pos.y).abs() < self.unload_range
+    }
 }
 
 pub fn load_chunks(
@@ -99,25 +111,11 @@ pub fn unload_chunks(
     mut chunk_load_cache: ResMut<ChunkLoadCache>,
 ) {
     for (entity, chunk_parent) in chunk_trees {
-        let mut should_unload = true;
-
         let chunk_position = chunk_parent.position;
 
-        for (chunk_loader, chunk_loader_transform) in &chunk_loaders {
-            let loader_chunk_pos = ChunkTreePos::from_global_pos(
-                chunk_loader_transform.translation.xz(),
-            );
-            if (chunk_position.x - loader_chunk_pos.x).abs()
-                < chunk_loader.unload_range
-                && (chunk_position.y - loader_chunk_pos.y).abs()
-                    < chunk_loader.unload_range
-            {
-                should_unload = false;
-                break;
-            }
-        }
-
-        if !should_unload {
+        if chunk_loaders.iter().any(|(chunk_loader, transform)| {
+            chunk_loader.keeps_loaded(chunk_position, transform.translation)
+        }) {
             continue;
         }
 
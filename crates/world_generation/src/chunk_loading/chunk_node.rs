@@ -373,24 +373,39 @@ pub fn stack_chunks(
             .find(|node| node.1 == *chunk_node_parent)
             .expect("Parent not found!");
 
-        let chunk_child_entity = commands.spawn_empty().id();
-        commands.entity(chunk_child_entity).insert((
-            ChunkStart {
-                chunk_lod_pos: chunk.lod_position,
-                chunk_tree_pos: chunk.tree_position,
-                chunk_stack_offset: chunk.chunk_height + 1,
-            },
-            Visibility::Visible,
-        ));
-
-        commands
-            .entity(*chunk_node_parent)
-            .add_child(chunk_child_entity);
-
-        parent_node.chunk_children.push(chunk_child_entity);
+        spawn_stacked_chunk(
+            chunk,
+            *chunk_node_parent,
+            &mut parent_node,
+            &mut commands,
+        );
     }
 }
 
+/// Spawns the next chunk on top of the given one in the same chunk node.
+pub fn spawn_stacked_chunk(
+    chunk: &Chunk,
+    chunk_node_entity: Entity,
+    chunk_node: &mut ChunkNode,
+    commands: &mut Commands,
+) {
+    let chunk_child_entity = commands.spawn_empty().id();
+    commands.entity(chunk_child_entity).insert((
+        ChunkStart {
+            chunk_lod_pos: chunk.lod_position,
+            chunk_tree_pos: chunk.tree_position,
+            chunk_stack_offset: chunk.chunk_height + 1,
+        },
+        Visibility::Visible,
+    ));
+
+    commands
+        .entity(chunk_node_entity)
+        .add_child(chunk_child_entity);
+
+    chunk_node.chunk_children.push(chunk_child_entity);
+}
+
 pub fn update_added_chunks(
     mut commands: Commands,
     added_chunks: Query<(&Chunk, &ChildOf), Added<Chunk>>,
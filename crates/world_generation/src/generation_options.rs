@@ -1,5 +1,7 @@
 use crate::chunk_generation::{
+    biomes::biome_map::{BIOME_MAP_FILE_PATH, BiomeMap},
     block_type::BlockType,
+    density::density_options::{DENSITY_OPTIONS_FILE_PATH, DensityOptions},
     noise::{
         noise_function::NoiseFunction,
         noise_result::NoiseResult,
@@ -7,19 +9,28 @@ use crate::chunk_generation::{
         terrain_noise_group::TerrainNoiseGroup,
         terrain_noise_type::TerrainNoiseType,
     },
+    settlements::{
+        building_template::BuildingTemplate,
+        settlement_options::{SETTLEMENT_OPTIONS_FILE_PATH, SettlementOptions},
+    },
     structures::{
         pine_structure_generator::PineStructureGenerator,
         structure_generator::VoxelStructureMetadata,
         structure_generators::StructureGenerators,
         tree_structure_generator::TreeStructureGenerator,
+        vox_structure::VoxPalette,
     },
+    water::water_options::{WATER_OPTIONS_FILE_PATH, WaterOptions},
 };
 use bevy::prelude::*;
 use rand::{Rng, SeedableRng};
 use rand::{RngExt, prelude::StdRng};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use utils::file_utils::read_ron_from_file;
+use utils::{
+    file_utils::{get_asset_path, read_ron_from_file},
+    stable_hash::get_stable_hash,
+};
 
 fn get_seeded_white_noise() -> TerrainNoise {
     TerrainNoise::new(
@@ -38,22 +49,36 @@ pub struct GenerationOptions {
     pub structure_assets: Vec<StructureAsset>,
     pub generate_paths: bool,
     pub terrain_noise_group: TerrainNoiseGroup,
+    pub biome_map: BiomeMap,
+    pub density_options: DensityOptions,
+    pub water_options: WaterOptions,
+    pub settlement_options: SettlementOptions,
+    pub vox_palette: VoxPalette,
+    /// Loaded from the building sources of the settlement options on every
+    /// peer, so only the sources get sent to other peers.
+    #[serde(skip)]
+    pub building_templates: Vec<BuildingTemplate>,
 }
 
 impl GenerationOptions {
     pub fn from_seed(seed: u64) -> Self {
-        // let tree_house = vox_data_to_structure_data(
-        //     &from_file("assets/tree_house.vox").unwrap(),
-        // );
-        // let box_structure =
-        //     vox_data_to_structure_data(&from_file("assets/box.vox").unwrap());
         let terrain_noise_group: TerrainNoiseGroup =
-            read_ron_from_file(TERRAIN_NOISE_FILE_PATH)
+            read_ron_from_file(get_asset_path(TERRAIN_NOISE_FILE_PATH))
                 .expect("Failed loading terrain noise config.");
-
-        // let tree_model: StructureModel =
-        //     read_ron_from_file("assets/tree_test.ron")
-        //         .expect("Failed to load tree model.");
+        let biome_map: BiomeMap =
+            read_ron_from_file(get_asset_path(BIOME_MAP_FILE_PATH))
+                .expect("Failed loading biome map.");
+        let density_options: DensityOptions =
+            read_ron_from_file(get_asset_path(DENSITY_OPTIONS_FILE_PATH))
+                .expect("Failed loading density options.");
+        let water_options: WaterOptions =
+            read_ron_from_file(get_asset_path(WATER_OPTIONS_FILE_PATH))
+                .expect("Failed loading water options.");
+        let settlement_options: SettlementOptions =
+            read_ron_from_file(get_asset_path(SETTLEMENT_OPTIONS_FILE_PATH))
+                .expect("Failed loading settlement options.");
+        let vox_palette =
+            VoxPalette::load().expect("Failed loading vox palette.");
 
         let mut rng = StdRng::seed_from_u64(seed);
 
@@ -139,17 +164,6 @@ impl GenerationOptions {
                         seed,
                     ),
                 ))),
-                // Arc::new(Box::new(FixedStructureGenerator {
-                //     fixed_structure_model: Arc::new(tree_model.blocks),
-                //     fixed_structure_metadata: VoxelStructureMetadata {
-                //         model_size: tree_model.model_size.to_array(),
-                //         generation_size: [10, 10],
-                //         grid_offset: [7, 4],
-                //         generate_debug_blocks: false,
-                //         debug_rgb_multiplier: [1., 1., 1.],
-                //         noise: get_seeded_white_noise(rng.random()),
-                //     },
-                // })),
             ],
             structure_assets: vec![
                 // StructureAsset {
@@ -157,9 +171,30 @@ impl GenerationOptions {
                 // }
             ],
             terrain_noise_group,
+            biome_map,
+            density_options,
+            water_options,
+            settlement_options,
+            vox_palette,
+            building_templates: vec![],
         }
     }
 
+    /// Reads the templates of all building sources of the settlement options
+    /// directly, for world generation running without an asset server.
+    pub fn load_building_templates(&mut self) -> Result<(), anyhow::Error> {
+        let mut building_templates = vec![];
+        for building_source in &self.settlement_options.buildings {
+            building_templates.extend(BuildingTemplate::load(
+                building_source,
+                &self.vox_palette,
+            )?);
+        }
+
+        self.building_templates = building_templates;
+        Ok(())
+    }
+
     pub fn get_seeded_rng(&self) -> impl Rng {
         StdRng::seed_from_u64(self.seed)
     }
@@ -171,54 +206,21 @@ impl GenerationOptions {
             .terrain_height
             .get_noise_fn(&mut self.get_seeded_rng())
     }
+
+    /// Changes whenever anything the world is generated from changes, so
+    /// caches on disk can tell when they are stale.
+    pub fn get_stable_hash(&self) -> Result<u64, anyhow::Error> {
+        // The templates are skipped when the options get serialized, but
+        // still change the world.
+        let bytes = bincode::serde::encode_to_vec(
+            (self, &self.building_templates),
+            bincode::config::standard(),
+        )?;
+        Ok(get_stable_hash([&bytes[..]]))
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StructureAsset {
     pub _blocks: Vec<Vec<Vec<BlockType>>>,
 }
-
-// fn vox_data_to_blocks(vox_data: &VoxData) -> Vec<Vec<Vec<BlockType>>> {
-//     let model = vox_data.models.first().unwrap();
-//     let mut result: Vec<Vec<Vec<BlockType>>> =
-//         Vec::with_capacity(model.size.x as usize);
-//     for x in 0..model.size.x {
-//         result.push(Vec::with_capacity(model.size.z as usize));
-//         for y in 0..model.size.z {
-//             result[x as usize].push(Vec::with_capacity(model.size.y as usize));
-//             for _ in 0..model.size.y {
-//                 result[x as usize][y as usize].push(BlockType::Air);
-//             }
-//         }
-//     }
-
-//     for voxel in model.voxels.iter() {
-//         //let color = vox_data.palette.colors[voxel.color_index.0 as usize];
-//         let [x, y, z] = [
-//             voxel.point.x as usize,
-//             voxel.point.y as usize,
-//             voxel.point.z as usize,
-//         ];
-//         result[x][z][y] = BlockType::Stone;
-//     }
-
-//     result
-// }
-
-// fn vox_data_model_size(vox_data: &VoxData) -> [i32; 3] {
-//     let model_size = vox_data.models.first().unwrap().size;
-//     [
-//         model_size.x as i32,
-//         model_size.z as i32,
-//         model_size.y as i32,
-//     ]
-// }
-
-// fn vox_data_to_structure_data(
-//     vox_data: &VoxData,
-// ) -> (Arc<Vec<Vec<Vec<BlockType>>>>, [i32; 3]) {
-//     (
-//         Arc::new(vox_data_to_blocks(vox_data)),
-//         vox_data_model_size(vox_data),
-//     )
-// }
@@ -4,6 +4,8 @@ pub mod generation_options;
 mod initial_chunk_loader;
 pub mod start_world_gen;
 pub mod terrain_material;
+pub mod voxel_editing;
 pub mod world_generation_plugin;
 pub mod world_generation_state;
 pub mod world_ready;
+pub mod world_save;
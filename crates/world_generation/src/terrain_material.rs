@@ -1,14 +1,27 @@
 use bevy::{
-    pbr::MaterialExtension, prelude::*, render::render_resource::AsBindGroup,
+    image::{
+        ImageAddressMode, ImageArrayLayout, ImageFilterMode,
+        ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor,
+    },
+    pbr::MaterialExtension,
+    prelude::*,
+    render::render_resource::AsBindGroup,
     shader::ShaderRef,
 };
 
+pub const BLOCK_TEXTURES_PATH: &str = "textures/blocks.png";
+/// The block textures are stacked vertically, one square layer per texture.
+pub const BLOCK_TEXTURE_SIZE: u32 = 16;
+
 #[derive(Asset, AsBindGroup, Debug, Clone, TypePath)]
 pub struct TerrainMaterial {
     #[uniform(100)]
     pub chunk_position: Vec3,
     #[uniform(100)]
     pub lod_multiplier: u32,
+    #[texture(101, dimension = "2d_array")]
+    #[sampler(102)]
+    pub block_textures: Option<Handle<Image>>,
 }
 
 impl MaterialExtension for TerrainMaterial {
@@ -16,3 +29,39 @@ impl MaterialExtension for TerrainMaterial {
         "shaders/terrain_material.wgsl".into()
     }
 }
+
+/// The texture array of every block face, see [`BLOCK_TEXTURES_PATH`].
+#[derive(Resource, Clone)]
+pub struct BlockTextures(pub Handle<Image>);
+
+pub fn load_block_textures(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    images: Option<Res<Assets<Image>>>,
+) {
+    // Without a renderer there is nothing to draw the textures with.
+    if images.is_none() {
+        return;
+    }
+
+    let block_textures = asset_server.load_with_settings(
+        BLOCK_TEXTURES_PATH,
+        |settings: &mut ImageLoaderSettings| {
+            settings.array_layout = Some(ImageArrayLayout::RowHeight {
+                pixels: BLOCK_TEXTURE_SIZE,
+            });
+            // The textures only add detail on top of the block colours.
+            settings.is_srgb = false;
+            settings.sampler =
+                ImageSampler::Descriptor(ImageSamplerDescriptor {
+                    address_mode_u: ImageAddressMode::Repeat,
+                    address_mode_v: ImageAddressMode::Repeat,
+                    mag_filter: ImageFilterMode::Nearest,
+                    min_filter: ImageFilterMode::Nearest,
+                    ..default()
+                });
+        },
+    );
+
+    commands.insert_resource(BlockTextures(block_textures));
+}
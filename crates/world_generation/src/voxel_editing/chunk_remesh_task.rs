@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use bevy::{pbr::ExtendedMaterial, prelude::*, tasks::Task};
+use futures_lite::future;
+use physics::{collider::Collider, physics_object::StaticPhysicsObject};
+
+use crate::{
+    chunk_generation::{
+        chunk::Chunk,
+        chunk_lod::ChunkLod,
+        chunk_mesh_mode::ChunkMeshMode,
+        chunk_task::{ChunkTaskPool, spawn_terrain_meshes},
+        mesh_generation::MeshResult,
+        terrain_mesh::TerrainMesh,
+        voxel_data::VoxelData,
+    },
+    terrain_material::{BlockTextures, TerrainMaterial},
+};
+
+/// Rebuilds the mesh and collider of an already generated chunk.
+/// Inserting a new task replaces a running one, so only the latest edit is meshed.
+#[derive(Component)]
+pub struct ChunkRemeshTask(pub Task<MeshResult>);
+
+impl ChunkRemeshTask {
+    pub fn spawn(
+        chunk_task_pool: &ChunkTaskPool,
+        voxel_data: Arc<VoxelData>,
+        chunk_lod: ChunkLod,
+        chunk_mesh_mode: ChunkMeshMode,
+    ) -> Self {
+        Self(chunk_task_pool.task_pool.spawn(async move {
+            chunk_mesh_mode.generate_mesh(&voxel_data, chunk_lod)
+        }))
+    }
+}
+
+pub fn set_remeshed_chunks(
+    mut commands: Commands,
+    mut chunks: Query<(
+        Entity,
+        &mut ChunkRemeshTask,
+        &Chunk,
+        &Transform,
+        Option<&Children>,
+    )>,
+    terrain_meshes: Query<(), With<TerrainMesh>>,
+    mut meshes: Option<ResMut<Assets<Mesh>>>,
+    mut materials: Option<
+        ResMut<Assets<ExtendedMaterial<StandardMaterial, TerrainMaterial>>>,
+    >,
+    block_textures: Option<Res<BlockTextures>>,
+) {
+    for (entity, mut task, chunk, transform, children) in &mut chunks {
+        let Some(mut mesh_result) =
+            future::block_on(future::poll_once(&mut task.0))
+        else {
+            continue;
+        };
+
+        for child in children.into_iter().flatten() {
+            if terrain_meshes.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
+
+        let mut current_entity = commands.entity(entity);
+        current_entity.remove::<ChunkRemeshTask>();
+
+        match mesh_result.collider.take() {
+            Some(collider) => {
+                current_entity.insert((collider, StaticPhysicsObject));
+            }
+            None => {
+                current_entity.remove::<(Collider, StaticPhysicsObject)>();
+            }
+        }
+
+        let (Some(meshes), Some(materials)) = (&mut meshes, &mut materials)
+        else {
+            continue;
+        };
+
+        spawn_terrain_meshes(
+            &mut current_entity,
+            mesh_result,
+            transform.translation,
+            chunk.lod_position.lod,
+            meshes,
+            materials,
+            block_textures.as_deref(),
+        );
+    }
+}
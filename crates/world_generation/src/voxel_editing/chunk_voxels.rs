@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::chunk_generation::voxel_data::VoxelData;
+
+/// The voxel data a full detail chunk was meshed from.
+/// Shared with running remesh tasks and the
+/// [`VoxelWorld`](super::voxel_world::VoxelWorld), edits clone it on write.
+#[derive(Component, Deref, DerefMut)]
+pub struct ChunkVoxels(pub Arc<VoxelData>);
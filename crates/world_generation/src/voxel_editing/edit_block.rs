@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+use crate::chunk_generation::block_type::BlockType;
+
+/// A block change the local player wants to make.
+/// Unlike [`SetBlock`](super::set_block::SetBlock) it is only a request,
+/// the host decides if it actually gets applied.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct EditBlock {
+    pub voxel_pos: IVec3,
+    pub block: BlockType,
+}
@@ -0,0 +1,7 @@
+pub mod chunk_remesh_task;
+pub mod chunk_voxels;
+pub mod edit_block;
+pub mod set_block;
+pub mod voxel_editing_plugin;
+pub mod voxel_ray_cast;
+pub mod voxel_world;
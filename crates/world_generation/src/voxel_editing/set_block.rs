@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::{
+    chunk_generation::{
+        CHUNK_SIZE, block_type::BlockType, chunk::Chunk,
+        chunk_mesh_mode::ChunkMeshMode, chunk_task::ChunkTaskPool,
+    },
+    chunk_loading::chunk_node::{ChunkNode, spawn_stacked_chunk},
+    voxel_editing::{
+        chunk_remesh_task::ChunkRemeshTask, chunk_voxels::ChunkVoxels,
+    },
+    world_save::voxel_delta_store::VoxelDeltaStore,
+};
+
+/// Changes a single block at a world voxel position.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SetBlock {
+    pub voxel_pos: IVec3,
+    pub block: BlockType,
+}
+
+pub fn set_block(
+    set_block: On<SetBlock>,
+    mut commands: Commands,
+    mut voxel_delta_store: ResMut<VoxelDeltaStore>,
+    mut chunks: Query<(Entity, &mut Chunk, &mut ChunkVoxels, &ChildOf)>,
+    mut chunk_nodes: Query<&mut ChunkNode>,
+    chunk_task_pool: Res<ChunkTaskPool>,
+    chunk_mesh_mode: Res<ChunkMeshMode>,
+) {
+    let voxel_pos = set_block.voxel_pos;
+    voxel_delta_store.set_block(voxel_pos, set_block.block);
+
+    let owner_column = (voxel_pos.xz() - IVec2::ONE)
+        .div_euclid(IVec2::splat(CHUNK_SIZE as i32));
+    let mut is_inside_chunk = false;
+    let mut top_chunk: Option<(Entity, i32)> = None;
+
+    for (entity, chunk, mut chunk_voxels, _) in &mut chunks {
+        let chunk_pos = chunk
+            .lod_position
+            .get_absolute_chunk_pos(chunk.tree_position);
+        let local_pos = voxel_pos
+            - IVec3::new(
+                chunk_pos.x * CHUNK_SIZE as i32,
+                chunk.min_height,
+                chunk_pos.y * CHUNK_SIZE as i32,
+            );
+
+        if *chunk_pos == owner_column {
+            is_inside_chunk |= (1..=CHUNK_SIZE as i32).contains(&local_pos.y);
+
+            if top_chunk.is_none_or(|(_, height)| height < chunk.chunk_height) {
+                top_chunk = Some((entity, chunk.chunk_height));
+            }
+        }
+
+        // Chunks that only have the voxel in their border still need
+        // a new mesh, since the faces next to it might change.
+        if local_pos.cmplt(IVec3::ZERO).any()
+            || local_pos.cmpgt(IVec3::splat(CHUNK_SIZE as i32 + 1)).any()
+        {
+            continue;
+        }
+
+        Arc::make_mut(&mut chunk_voxels).set_block(local_pos, set_block.block);
+
+        commands.entity(entity).insert(ChunkRemeshTask::spawn(
+            &chunk_task_pool,
+            chunk_voxels.0.clone(),
+            chunk.lod_position.lod,
+            *chunk_mesh_mode,
+        ));
+    }
+
+    if is_inside_chunk || set_block.block == BlockType::AIR {
+        return;
+    }
+
+    // The block was placed above the highest chunk of its column,
+    // so the chunk stack has to grow to contain it.
+    let Some((top_entity, _)) = top_chunk else {
+        return;
+    };
+
+    let Ok((_, mut chunk, _, ChildOf(chunk_node_entity))) =
+        chunks.get_mut(top_entity)
+    else {
+        return;
+    };
+
+    if chunk.generate_above
+        || voxel_pos.y <= chunk.min_height + CHUNK_SIZE as i32
+    {
+        return;
+    }
+
+    chunk.generate_above = true;
+
+    let Ok(mut chunk_node) = chunk_nodes.get_mut(*chunk_node_entity) else {
+        return;
+    };
+
+    spawn_stacked_chunk(
+        &chunk,
+        *chunk_node_entity,
+        &mut chunk_node,
+        &mut commands,
+    );
+}
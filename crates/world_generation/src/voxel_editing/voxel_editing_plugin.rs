@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+use crate::voxel_editing::{
+    chunk_remesh_task::set_remeshed_chunks,
+    set_block::set_block,
+    voxel_world::{VoxelWorld, add_chunk_voxels, remove_chunk_voxels},
+};
+
+pub struct VoxelEditingPlugin;
+
+impl Plugin for VoxelEditingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VoxelWorld>()
+            .add_systems(Update, set_remeshed_chunks)
+            .add_systems(PostUpdate, add_chunk_voxels)
+            .add_observer(set_block)
+            .add_observer(remove_chunk_voxels);
+    }
+}
@@ -0,0 +1,130 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    chunk_generation::{
+        VOXEL_SIZE,
+        block_type::{BlockFace, BlockType},
+    },
+    voxel_editing::voxel_world::VoxelWorld,
+};
+
+pub struct VoxelRayHit {
+    pub voxel_pos: IVec3,
+    pub block: BlockType,
+    pub face: BlockFace,
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+/// Returns the world voxel position containing the given point.
+pub fn get_voxel_pos(point: Vec3) -> IVec3 {
+    (point / VOXEL_SIZE).floor().as_ivec3()
+}
+
+/// Returns the position of the lower corner of a world voxel.
+pub fn get_voxel_point(voxel_pos: IVec3) -> Vec3 {
+    voxel_pos.as_vec3() * VOXEL_SIZE
+}
+
+/// Casts rays against the voxel data of the loaded full detail chunks.
+/// Without a [`VoxelWorld`] nothing counts as loaded.
+#[derive(SystemParam)]
+pub struct VoxelRayCast<'w> {
+    voxel_world: Option<Res<'w, VoxelWorld>>,
+}
+
+impl VoxelRayCast<'_> {
+    pub fn get_block(&self, voxel_pos: IVec3) -> Option<BlockType> {
+        self.voxel_world.as_ref()?.get_block(voxel_pos)
+    }
+
+    /// Whether any loaded voxel overlapping the box between the two points
+    /// is the given block.
+    pub fn is_block_in_box(
+        &self,
+        min: Vec3,
+        max: Vec3,
+        block: BlockType,
+    ) -> bool {
+        let min = get_voxel_pos(min);
+        let max = get_voxel_pos(max);
+
+        (min.x..=max.x).any(|x| {
+            (min.y..=max.y).any(|y| {
+                (min.z..=max.z)
+                    .any(|z| self.get_block(IVec3::new(x, y, z)) == Some(block))
+            })
+        })
+    }
+
+    /// Walks the voxel grid along the ray and returns the first solid block.
+    /// Voxels of chunks that are not loaded are treated as air.
+    pub fn cast_ray(
+        &self,
+        ray: Ray3d,
+        max_distance: f32,
+    ) -> Option<VoxelRayHit> {
+        let origin = ray.origin / VOXEL_SIZE;
+        let direction = *ray.direction;
+        let max_t = max_distance / VOXEL_SIZE;
+
+        let mut voxel_pos = origin.floor().as_ivec3();
+        let mut step = IVec3::ZERO;
+        let mut t_max = Vec3::INFINITY;
+        let mut t_delta = Vec3::INFINITY;
+
+        for axis in 0..3 {
+            if direction[axis] > 0. {
+                step[axis] = 1;
+                t_max[axis] = (origin[axis].floor() + 1. - origin[axis])
+                    / direction[axis];
+            } else if direction[axis] < 0. {
+                step[axis] = -1;
+                t_max[axis] =
+                    (origin[axis] - origin[axis].floor()) / -direction[axis];
+            } else {
+                continue;
+            }
+            t_delta[axis] = 1. / direction[axis].abs();
+        }
+
+        loop {
+            let axis = if t_max.x < t_max.y && t_max.x < t_max.z {
+                0
+            } else if t_max.y < t_max.z {
+                1
+            } else {
+                2
+            };
+
+            let t = t_max[axis];
+            if t > max_t {
+                return None;
+            }
+
+            voxel_pos[axis] += step[axis];
+            t_max[axis] += t_delta[axis];
+
+            let Some(block) = self.get_block(voxel_pos) else {
+                continue;
+            };
+
+            if !block.is_colliding() {
+                continue;
+            }
+
+            let mut normal = IVec3::ZERO;
+            normal[axis] = -step[axis];
+
+            return Some(VoxelRayHit {
+                voxel_pos,
+                block,
+                face: BlockFace::from_direction(normal)?,
+                point: ray.origin + direction * t * VOXEL_SIZE,
+                normal: normal.as_vec3(),
+                distance: t * VOXEL_SIZE,
+            });
+        }
+    }
+}
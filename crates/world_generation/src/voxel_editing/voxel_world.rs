@@ -0,0 +1,127 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bevy::prelude::*;
+
+use crate::{
+    chunk_generation::{
+        CHUNK_SIZE, block_type::BlockType, chunk::Chunk, voxel_data::VoxelData,
+    },
+    chunk_loading::chunk_pos::AbsoluteChunkPos,
+    voxel_editing::chunk_voxels::ChunkVoxels,
+};
+
+/// The voxel data of every loaded full detail chunk, grouped by chunk column,
+/// so blocks can be looked up by their world voxel position.
+#[derive(Resource, Default)]
+pub struct VoxelWorld {
+    columns: HashMap<AbsoluteChunkPos, Vec<LoadedChunkVoxels>>,
+    chunk_columns: HashMap<Entity, AbsoluteChunkPos>,
+}
+
+/// A chunk of a column, ordered by height within it.
+struct LoadedChunkVoxels {
+    entity: Entity,
+    min_height: i32,
+    voxel_data: Arc<VoxelData>,
+}
+
+impl VoxelWorld {
+    /// Returns [`None`] if no loaded chunk contains the voxel.
+    pub fn get_block(&self, voxel_pos: IVec3) -> Option<BlockType> {
+        let (column, local_xz) = get_column_position(voxel_pos.xz());
+
+        self.columns.get(&column)?.iter().find_map(|chunk| {
+            let local_y = voxel_pos.y - chunk.min_height;
+            (1..=CHUNK_SIZE as i32).contains(&local_y).then(|| {
+                chunk
+                    .voxel_data
+                    .get_block(IVec3::new(local_xz.x, local_y, local_xz.y))
+            })
+        })
+    }
+
+    /// The height of the highest colliding block at the world voxel column,
+    /// searching only the loaded chunks of its chunk stack.
+    pub fn get_surface_height(&self, voxel_xz: IVec2) -> Option<i32> {
+        let (column, local_xz) = get_column_position(voxel_xz);
+
+        self.columns.get(&column)?.iter().rev().find_map(|chunk| {
+            (1..=CHUNK_SIZE as i32).rev().find_map(|local_y| {
+                chunk
+                    .voxel_data
+                    .get_block(IVec3::new(local_xz.x, local_y, local_xz.y))
+                    .is_colliding()
+                    .then_some(chunk.min_height + local_y)
+            })
+        })
+    }
+
+    /// Adds the voxels of a chunk, or replaces them if it was added before.
+    pub fn insert(
+        &mut self,
+        entity: Entity,
+        chunk: &Chunk,
+        voxel_data: Arc<VoxelData>,
+    ) {
+        self.remove(entity);
+
+        let column = chunk
+            .lod_position
+            .get_absolute_chunk_pos(chunk.tree_position);
+        let chunks = self.columns.entry(column).or_default();
+        let index = chunks.partition_point(|loaded_chunk| {
+            loaded_chunk.min_height < chunk.min_height
+        });
+
+        chunks.insert(
+            index,
+            LoadedChunkVoxels {
+                entity,
+                min_height: chunk.min_height,
+                voxel_data,
+            },
+        );
+        self.chunk_columns.insert(entity, column);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        let Some(column) = self.chunk_columns.remove(&entity) else {
+            return;
+        };
+        let Some(chunks) = self.columns.get_mut(&column) else {
+            return;
+        };
+
+        chunks.retain(|loaded_chunk| loaded_chunk.entity != entity);
+        if chunks.is_empty() {
+            self.columns.remove(&column);
+        }
+    }
+}
+
+/// Splits a world voxel column into its chunk column and the position inside
+/// that chunk. A chunk only meshes its inner voxels, the border belongs to its
+/// neighbours.
+fn get_column_position(voxel_xz: IVec2) -> (AbsoluteChunkPos, IVec2) {
+    let column =
+        (voxel_xz - IVec2::ONE).div_euclid(IVec2::splat(CHUNK_SIZE as i32));
+    let local_xz = voxel_xz - column * CHUNK_SIZE as i32;
+
+    (AbsoluteChunkPos::new(column), local_xz)
+}
+
+pub fn add_chunk_voxels(
+    mut voxel_world: ResMut<VoxelWorld>,
+    chunks: Query<(Entity, &Chunk, &ChunkVoxels), Changed<ChunkVoxels>>,
+) {
+    for (entity, chunk, chunk_voxels) in &chunks {
+        voxel_world.insert(entity, chunk, chunk_voxels.0.clone());
+    }
+}
+
+pub fn remove_chunk_voxels(
+    remove: On<Remove, ChunkVoxels>,
+    mut voxel_world: ResMut<VoxelWorld>,
+) {
+    voxel_world.remove(remove.entity);
+}
@@ -1,20 +1,31 @@
 use bevy::prelude::*;
 
 use crate::{
-    chunk_generation::chunk_generation_plugin::ChunkGenerationPlugin,
+    chunk_generation::{
+        block_registry::load_block_registry,
+        chunk_generation_plugin::ChunkGenerationPlugin,
+    },
     initial_chunk_loader::{
         remove_initial_chunk_loader, spawn_initial_chunk_loader,
     },
+    voxel_editing::voxel_editing_plugin::VoxelEditingPlugin,
     world_generation_state::{
         WorldGenerationState, check_world_done_initializing,
         check_world_gen_started,
     },
+    world_save::world_save_plugin::WorldSavePlugin,
 };
 
 pub struct WorldGenerationPlugin;
 
 impl Plugin for WorldGenerationPlugin {
     fn build(&self, app: &mut App) {
+        // Every block lookup needs it, so a broken block asset stops the
+        // game before anything gets generated.
+        if let Err(err) = load_block_registry() {
+            panic!("{:?}", err);
+        }
+
         app.init_state::<WorldGenerationState>()
             .add_systems(
                 Update,
@@ -30,7 +41,11 @@ impl Plugin for WorldGenerationPlugin {
                 OnExit(WorldGenerationState::InitialGeneration),
                 remove_initial_chunk_loader,
             )
-            .add_plugins(ChunkGenerationPlugin)
+            .add_plugins((
+                ChunkGenerationPlugin,
+                WorldSavePlugin,
+                VoxelEditingPlugin,
+            ))
             .add_observer(spawn_initial_chunk_loader);
     }
 }
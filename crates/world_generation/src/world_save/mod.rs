@@ -0,0 +1,4 @@
+pub mod save_world;
+pub mod voxel_delta_store;
+pub mod world_save_file;
+pub mod world_save_plugin;
@@ -0,0 +1,164 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bevy::prelude::*;
+
+use crate::{
+    chunk_generation::{
+        CHUNK_SIZE, block_type::BlockType, chunk_lod::ChunkLod,
+        voxel_data::VoxelData,
+    },
+    chunk_loading::chunk_pos::AbsoluteChunkPos,
+};
+
+/// All player modifications inside one chunk column,
+/// keyed by their world voxel position.
+#[derive(Clone, Default)]
+pub struct ChunkDelta {
+    pub blocks: HashMap<IVec3, BlockType>,
+}
+
+/// Keeps track of every block a player changed, so chunks that get
+/// regenerated can be patched with them on top of the generated terrain.
+#[derive(Resource, Default)]
+pub struct VoxelDeltaStore {
+    seed: Option<u64>,
+    chunk_deltas: HashMap<IVec2, Arc<ChunkDelta>>,
+    dirty: bool,
+    revision: u64,
+}
+
+impl VoxelDeltaStore {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..Default::default()
+        }
+    }
+
+    pub fn get_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Increases with every edit, so chunks generated with an older
+    /// set of deltas can be detected.
+    pub fn get_revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Returns the chunk column a world voxel position belongs to.
+    pub fn get_chunk_column(voxel_pos: IVec3) -> IVec2 {
+        IVec2::new(
+            voxel_pos.x.div_euclid(CHUNK_SIZE as i32),
+            voxel_pos.z.div_euclid(CHUNK_SIZE as i32),
+        )
+    }
+
+    pub fn set_block(&mut self, voxel_pos: IVec3, block: BlockType) {
+        let chunk_delta = self
+            .chunk_deltas
+            .entry(Self::get_chunk_column(voxel_pos))
+            .or_default();
+        Arc::make_mut(chunk_delta).blocks.insert(voxel_pos, block);
+        self.dirty = true;
+        self.revision += 1;
+    }
+
+    pub fn get_block(&self, voxel_pos: IVec3) -> Option<BlockType> {
+        self.chunk_deltas
+            .get(&Self::get_chunk_column(voxel_pos))?
+            .blocks
+            .get(&voxel_pos)
+            .copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&IVec2, &Arc<ChunkDelta>)> {
+        self.chunk_deltas.iter()
+    }
+
+    /// Collects the deltas of every chunk column the voxel array of the given
+    /// chunk overlaps, including its one voxel border.
+    pub fn get_deltas_for_chunk(
+        &self,
+        chunk_pos: AbsoluteChunkPos,
+        chunk_lod: ChunkLod,
+    ) -> Vec<Arc<ChunkDelta>> {
+        let min_column = *chunk_pos;
+        let max_column = (*chunk_pos * CHUNK_SIZE as i32
+            + IVec2::ONE
+                * (CHUNK_SIZE as i32 + 1)
+                * chunk_lod.multiplier_i32())
+        .div_euclid(IVec2::ONE * CHUNK_SIZE as i32);
+
+        self.chunk_deltas
+            .iter()
+            .filter(|(column, _)| {
+                column.cmpge(min_column).all() && column.cmple(max_column).all()
+            })
+            .map(|(_, chunk_delta)| chunk_delta.clone())
+            .collect()
+    }
+}
+
+/// Writes the deltas into the generated voxel data of a chunk.
+/// Lower LODs only pick up edits that land exactly on one of their sample points.
+///
+/// Returns true if there are edited blocks above this chunk,
+/// so the chunk stack has to continue upwards.
+pub fn apply_voxel_deltas(
+    voxel_data: &mut VoxelData,
+    chunk_deltas: &[Arc<ChunkDelta>],
+    chunk_pos: AbsoluteChunkPos,
+    min_height: i32,
+    chunk_lod: ChunkLod,
+) -> bool {
+    let multiplier = chunk_lod.multiplier_i32();
+    let chunk_start = *chunk_pos * CHUNK_SIZE as i32;
+    let mut blocks_above = false;
+
+    for chunk_delta in chunk_deltas {
+        for (voxel_pos, block) in &chunk_delta.blocks {
+            let offset = voxel_pos.xz() - chunk_start;
+
+            if offset.x.rem_euclid(multiplier) != 0
+                || offset.y.rem_euclid(multiplier) != 0
+                || voxel_pos.y.rem_euclid(multiplier) != 0
+            {
+                continue;
+            }
+
+            let local_pos = IVec3::new(
+                offset.x / multiplier,
+                voxel_pos.y.div_euclid(multiplier) - min_height,
+                offset.y / multiplier,
+            );
+
+            if local_pos.x < 0
+                || local_pos.y < 0
+                || local_pos.z < 0
+                || local_pos.x > CHUNK_SIZE as i32 + 1
+                || local_pos.z > CHUNK_SIZE as i32 + 1
+            {
+                continue;
+            }
+
+            if local_pos.y > CHUNK_SIZE as i32 + 1 {
+                if *block != BlockType::AIR {
+                    blocks_above = true;
+                }
+                continue;
+            }
+
+            voxel_data.set_block(local_pos, *block);
+        }
+    }
+
+    blocks_above
+}
@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use utils::file_utils::{read_ron_from_file, write_ron_to_file};
+
+use crate::{
+    chunk_generation::block_type::BlockType,
+    world_save::voxel_delta_store::VoxelDeltaStore,
+};
+
+/// Has to be increased every time the layout of the save file changes.
+pub const WORLD_SAVE_VERSION: u32 = 2;
+pub const WORLD_SAVE_FILE_NAME: &'static str = "world.ron";
+
+#[derive(Serialize, Deserialize)]
+pub struct WorldSaveFile {
+    pub version: u32,
+    pub seed: u64,
+    pub chunk_deltas: Vec<SavedChunkDelta>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedChunkDelta {
+    pub chunk_column: IVec2,
+    pub blocks: Vec<(IVec3, BlockType)>,
+}
+
+impl WorldSaveFile {
+    /// Every world gets its own folder named after its seed.
+    pub fn get_path(save_directory: &str, seed: u64) -> String {
+        format!("{}/{}/{}", save_directory, seed, WORLD_SAVE_FILE_NAME)
+    }
+
+    pub fn from_store(voxel_delta_store: &VoxelDeltaStore, seed: u64) -> Self {
+        Self {
+            version: WORLD_SAVE_VERSION,
+            seed,
+            chunk_deltas: voxel_delta_store
+                .iter()
+                .sorted_by_key(|(chunk_column, _)| chunk_column.to_array())
+                .map(|(chunk_column, chunk_delta)| SavedChunkDelta {
+                    chunk_column: *chunk_column,
+                    blocks: chunk_delta
+                        .blocks
+                        .iter()
+                        .map(|(voxel_pos, block)| (*voxel_pos, *block))
+                        .sorted_by_key(|(voxel_pos, _)| voxel_pos.to_array())
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn into_store(self) -> VoxelDeltaStore {
+        let mut voxel_delta_store = VoxelDeltaStore::new(self.seed);
+
+        for (voxel_pos, block) in self
+            .chunk_deltas
+            .into_iter()
+            .flat_map(|chunk_delta| chunk_delta.blocks)
+        {
+            voxel_delta_store.set_block(voxel_pos, block);
+        }
+
+        voxel_delta_store.mark_saved();
+        voxel_delta_store
+    }
+
+    pub fn save(&self, save_directory: &str) -> Result<(), anyhow::Error> {
+        write_ron_to_file(&Self::get_path(save_directory, self.seed), self)
+    }
+
+    /// Loads the save of the given seed.
+    /// Returns None if the world was never saved before.
+    pub fn load(
+        save_directory: &str,
+        seed: u64,
+    ) -> Result<Option<Self>, anyhow::Error> {
+        let path = Self::get_path(save_directory, seed);
+
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+
+        let world_save: Self = read_ron_from_file(&path)?;
+
+        if world_save.version != WORLD_SAVE_VERSION {
+            return Err(anyhow::anyhow!(
+                "Unsupported world save version {}, expected {}",
+                world_save.version,
+                WORLD_SAVE_VERSION
+            ));
+        }
+
+        if world_save.seed != seed {
+            return Err(anyhow::anyhow!(
+                "World save seed {} does not match {}",
+                world_save.seed,
+                seed
+            ));
+        }
+
+        Ok(Some(world_save))
+    }
+}
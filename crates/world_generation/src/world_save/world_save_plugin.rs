@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::{
+    generation_options::GenerationOptions,
+    world_save::{
+        save_world::SaveWorld, voxel_delta_store::VoxelDeltaStore,
+        world_save_file::WorldSaveFile,
+    },
+};
+
+pub const WORLD_SAVE_DIRECTORY: &'static str = "saves";
+
+#[derive(Resource)]
+pub struct WorldSaveSettings {
+    pub save_directory: String,
+    pub enabled: bool,
+}
+
+impl Default for WorldSaveSettings {
+    fn default() -> Self {
+        Self {
+            save_directory: WORLD_SAVE_DIRECTORY.into(),
+            enabled: true,
+        }
+    }
+}
+
+pub struct WorldSavePlugin;
+
+impl Plugin for WorldSavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VoxelDeltaStore>()
+            .init_resource::<WorldSaveSettings>()
+            .add_systems(Update, load_world_save)
+            .add_systems(Last, save_world_on_exit)
+            .add_observer(save_world);
+    }
+}
+
+fn load_world_save(
+    added_generation_options: Query<
+        &GenerationOptions,
+        Added<GenerationOptions>,
+    >,
+    mut voxel_delta_store: ResMut<VoxelDeltaStore>,
+    world_save_settings: Res<WorldSaveSettings>,
+) {
+    for generation_options in &added_generation_options {
+        let seed = generation_options.seed;
+
+        if !world_save_settings.enabled {
+            *voxel_delta_store = VoxelDeltaStore::new(seed);
+            continue;
+        }
+
+        *voxel_delta_store = match WorldSaveFile::load(
+            &world_save_settings.save_directory,
+            seed,
+        ) {
+            Ok(Some(world_save)) => {
+                info!("Loaded world save for seed {}", seed);
+                world_save.into_store()
+            }
+            Ok(None) => VoxelDeltaStore::new(seed),
+            Err(err) => {
+                error!("Failed loading world save: {}", err);
+                VoxelDeltaStore::new(seed)
+            }
+        };
+    }
+}
+
+fn save_world(
+    _: On<SaveWorld>,
+    mut voxel_delta_store: ResMut<VoxelDeltaStore>,
+    world_save_settings: Res<WorldSaveSettings>,
+) {
+    if !world_save_settings.enabled || !voxel_delta_store.is_dirty() {
+        return;
+    }
+
+    let Some(seed) = voxel_delta_store.get_seed() else {
+        return;
+    };
+
+    match WorldSaveFile::from_store(&voxel_delta_store, seed)
+        .save(&world_save_settings.save_directory)
+    {
+        Ok(_) => {
+            info!("Saved world for seed {}", seed);
+            voxel_delta_store.mark_saved();
+        }
+        Err(err) => {
+            error!("Failed saving world: {}", err);
+        }
+    }
+}
+
+fn save_world_on_exit(
+    mut app_exit: MessageReader<AppExit>,
+    mut commands: Commands,
+) {
+    if app_exit.read().next().is_some() {
+        commands.trigger(SaveWorld);
+    }
+}
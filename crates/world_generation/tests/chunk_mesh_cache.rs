@@ -0,0 +1,57 @@
+use std::{env, fs, path::Path, process};
+
+use utils::file_utils::set_asset_root;
+use world_generation::{
+    chunk_generation::{
+        block_registry::load_block_registry,
+        chunk_mesh_cache::{CHUNK_MESH_CACHE_DIRECTORY, ChunkMeshCache},
+        chunk_mesh_mode::ChunkMeshMode,
+    },
+    generation_options::GenerationOptions,
+};
+
+const SEED: u64 = 1337;
+const ASSET_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+
+fn count_entries(directory: &Path) -> usize {
+    fs::read_dir(directory).map_or(0, |entries| entries.count())
+}
+
+#[test]
+fn mesh_modes_keep_their_own_caches() {
+    set_asset_root(ASSET_ROOT).unwrap();
+    load_block_registry().unwrap();
+
+    let cache_directory = env::temp_dir()
+        .join(format!("chunk_mesh_cache_test_{}", process::id()));
+    let world_directory = cache_directory
+        .join(CHUNK_MESH_CACHE_DIRECTORY)
+        .join(SEED.to_string());
+    let generation_options = GenerationOptions::from_seed(SEED);
+
+    // A client and a dedicated server sharing the cache directory.
+    for chunk_mesh_mode in [
+        ChunkMeshMode::Render,
+        ChunkMeshMode::ColliderOnly,
+        ChunkMeshMode::Render,
+    ] {
+        ChunkMeshCache::new(
+            cache_directory.to_str().unwrap(),
+            &generation_options,
+            chunk_mesh_mode,
+        )
+        .unwrap();
+    }
+
+    for chunk_mesh_mode in [ChunkMeshMode::Render, ChunkMeshMode::ColliderOnly]
+    {
+        assert_eq!(
+            count_entries(&world_directory.join(chunk_mesh_mode.get_name())),
+            1,
+            "Cache of {:?}",
+            chunk_mesh_mode
+        );
+    }
+
+    fs::remove_dir_all(&cache_directory).unwrap();
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use bevy::{ecs::world::CommandQueue, prelude::*};
+use utils::file_utils::set_asset_root;
+use world_generation::{
+    chunk_generation::{
+        block_registry::load_block_registry,
+        country::{
+            cache_generation_task::CacheTaskPool,
+            country_cache::{CacheStore, CountryCache, MAX_CACHED_COUNTRIES},
+            country_cache_position::CountryPosition,
+            country_data::CountryData,
+            generation_cache::{GenerationCache, GenerationCacheItem},
+        },
+    },
+    generation_options::GenerationOptions,
+};
+
+const ASSET_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+const SEED: u64 = 1337;
+const ITEM_SIZE: usize = 100;
+
+struct TestItem;
+
+impl GenerationCacheItem<i32> for TestItem {
+    fn generate(
+        _key: i32,
+        _generation_options: &GenerationOptions,
+        _cache_store: Arc<CacheStore>,
+    ) -> Self {
+        Self
+    }
+
+    fn get_memory_size(&self) -> usize {
+        ITEM_SIZE
+    }
+}
+
+struct TestCache {
+    cache: GenerationCache<i32, TestItem>,
+    generation_options: GenerationOptions,
+    cache_store: Arc<CacheStore>,
+}
+
+impl TestCache {
+    fn new(max_items: usize) -> Self {
+        set_asset_root(ASSET_ROOT).unwrap();
+        load_block_registry().unwrap();
+
+        Self {
+            cache: GenerationCache::with_memory_limit(max_items * ITEM_SIZE),
+            generation_options: GenerationOptions::from_seed(SEED),
+            cache_store: Arc::new(CacheStore::default()),
+        }
+    }
+
+    fn get(&self, key: i32) -> Arc<TestItem> {
+        self.cache.get_cache_entry(
+            key,
+            &self.generation_options,
+            self.cache_store.clone(),
+        )
+    }
+
+    fn contains(&self, key: i32) -> bool {
+        self.cache.try_get_entry_no_lock(key).is_some()
+    }
+}
+
+#[test]
+fn least_recently_used_items_get_evicted() {
+    let test_cache = TestCache::new(2);
+
+    test_cache.get(1);
+    test_cache.get(2);
+    test_cache.get(1);
+    test_cache.get(3);
+
+    assert!(test_cache.contains(1));
+    assert!(!test_cache.contains(2));
+    assert!(test_cache.contains(3));
+    assert_eq!(test_cache.cache.get_statistics().get_entries(), 2);
+    assert_eq!(
+        test_cache.cache.get_statistics().get_memory_size(),
+        2 * ITEM_SIZE
+    );
+}
+
+#[test]
+fn held_items_stay_pinned() {
+    let test_cache = TestCache::new(0);
+
+    let held_item = test_cache.get(1);
+    test_cache.get(2);
+    test_cache.get(3);
+
+    assert!(test_cache.contains(1));
+    assert!(!test_cache.contains(2));
+
+    drop(held_item);
+    test_cache.get(4);
+
+    assert!(!test_cache.contains(1));
+}
+
+#[test]
+fn statistics_return_to_zero() {
+    let test_cache = TestCache::new(0);
+
+    for key in 0..8 {
+        test_cache.get(key);
+    }
+    test_cache.get(7);
+    assert_eq!(test_cache.cache.get_statistics().get_entries(), 1);
+
+    // The last item was still held while it got generated.
+    test_cache.cache.evict();
+
+    let statistics = test_cache.cache.get_statistics();
+    assert_eq!(statistics.get_entries(), 0);
+    assert_eq!(statistics.get_memory_size(), 0);
+    assert_eq!(statistics.take_hit_rate(), Some(1. / 9.));
+    assert_eq!(statistics.take_hit_rate(), None);
+}
+
+fn get_country_data(x: i32) -> CountryData {
+    CountryData {
+        country_pos: CountryPosition::new(IVec2::new(x, 0)),
+        structure_cache: default(),
+        this_path_cache: default(),
+        bottom_path_cache: default(),
+        left_path_cache: default(),
+        river_cache: default(),
+        settlement_cache: default(),
+    }
+}
+
+#[test]
+fn country_cache_keeps_the_recently_used_countries() {
+    set_asset_root(ASSET_ROOT).unwrap();
+    load_block_registry().unwrap();
+
+    let world = World::new();
+    let mut command_queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut command_queue, &world);
+    let cache_task_pool = CacheTaskPool::default();
+    let generation_options = GenerationOptions::from_seed(SEED);
+    let mut country_cache = CountryCache::default();
+
+    for x in 0..MAX_CACHED_COUNTRIES as i32 {
+        country_cache.insert_generated(get_country_data(x));
+    }
+
+    // Generated countries are hits, so nothing gets queued.
+    let used_country = CountryPosition::new(IVec2::ZERO);
+    assert!(
+        country_cache
+            .get_or_queue(
+                &mut commands,
+                used_country,
+                &cache_task_pool,
+                &generation_options,
+            )
+            .is_some()
+    );
+
+    country_cache.insert_generated(get_country_data(-1));
+
+    assert_eq!(
+        country_cache.get_statistics().get_entries(),
+        MAX_CACHED_COUNTRIES
+    );
+    assert_eq!(country_cache.country_cache.len(), MAX_CACHED_COUNTRIES);
+    assert!(country_cache.country_cache.contains_key(&used_country));
+}
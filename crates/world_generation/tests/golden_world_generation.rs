@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use bevy::{
+    math::IVec2,
+    mesh::{Mesh, MeshVertexAttribute},
+};
+use serde::{Deserialize, Serialize};
+use utils::{
+    file_utils::{read_ron_from_file, set_asset_root, write_ron_to_file},
+    stable_hash::get_stable_hash,
+};
+use world_generation::{
+    chunk_generation::{
+        block_registry::load_block_registry,
+        chunk_lod::ChunkLod,
+        country::{
+            country_cache::CacheStore, country_cache_position::CountryPosition,
+            country_data::CountryData, generation_cache::GenerationCacheItem,
+        },
+        mesh_generation::generate_mesh,
+        voxel_data::{VOXEL_COUNT, VoxelData},
+        voxel_generation::generate_voxels,
+    },
+    chunk_loading::{chunk_tree::ChunkTreePos, lod_position::LodPosition},
+    generation_options::GenerationOptions,
+};
+
+const SEED: u64 = 1337;
+const ASSET_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+const GOLDEN_FILE_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/golden/world_generation.ron"
+);
+/// Set to rewrite the golden file after an intended terrain change.
+const UPDATE_GOLDEN_VAR: &str = "UPDATE_GOLDEN";
+/// Same as the chunk task pool, the voxel arrays live on the stack.
+const STACK_SIZE: usize = 4_000_000;
+
+const LOD_POSITIONS: [(ChunkLod, i32, i32); 6] = [
+    (ChunkLod::Full, 0, 0),
+    (ChunkLod::Full, 3, -2),
+    (ChunkLod::Half, 1, 1),
+    (ChunkLod::Eighth, 0, 0),
+    (ChunkLod::Sixtyfourth, 0, 0),
+    (ChunkLod::TwoFiftySix, 0, 0),
+];
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct GoldenChunk {
+    lod: u8,
+    position: (i32, i32),
+    min_height: i32,
+    generate_above: bool,
+    voxel_hash: u64,
+    opaque_mesh_hash: u64,
+    transparent_mesh_hash: u64,
+    cutout_mesh_hash: u64,
+}
+
+fn hash_voxel_data(voxel_data: &VoxelData) -> u64 {
+    // Hashed like the flat block array it replaced, to keep the golden hashes.
+    let blocks = voxel_data
+        .iter_blocks()
+        .flat_map(|block| [block.id(), block.tint()])
+        .collect::<Vec<_>>();
+
+    get_stable_hash([&VOXEL_COUNT.to_ne_bytes()[..], &blocks])
+}
+
+fn hash_mesh(mesh: Option<&Mesh>) -> u64 {
+    let Some(mesh) = mesh else {
+        return get_stable_hash([]);
+    };
+
+    let attributes: [MeshVertexAttribute; 5] = [
+        Mesh::ATTRIBUTE_POSITION,
+        Mesh::ATTRIBUTE_NORMAL,
+        Mesh::ATTRIBUTE_UV_0,
+        Mesh::ATTRIBUTE_UV_1,
+        Mesh::ATTRIBUTE_COLOR,
+    ];
+    let indices = mesh
+        .indices()
+        .into_iter()
+        .flat_map(|indices| indices.iter())
+        .flat_map(|index| (index as u32).to_ne_bytes())
+        .collect::<Vec<_>>();
+
+    get_stable_hash(
+        attributes
+            .into_iter()
+            .filter_map(|attribute| mesh.attribute(attribute))
+            .map(|values| values.get_bytes())
+            .chain([&indices[..]]),
+    )
+}
+
+fn generate_golden_chunk(
+    (lod, x, y): (ChunkLod, i32, i32),
+    generation_options: &GenerationOptions,
+    cache_store: &Arc<CacheStore>,
+) -> GoldenChunk {
+    let lod_position = LodPosition::new(lod, x, y);
+    let chunk_pos =
+        lod_position.get_absolute_chunk_pos(ChunkTreePos::new(IVec2::ZERO));
+    let country_data = CountryData::generate(
+        CountryPosition::from_chunk_pos(chunk_pos),
+        generation_options,
+        cache_store.clone(),
+    );
+
+    let (voxel_data, min_height, generate_above) = generate_voxels(
+        [chunk_pos.x, 0, chunk_pos.y],
+        generation_options,
+        lod,
+        &country_data,
+    );
+    let mesh_result = generate_mesh(&voxel_data, lod);
+
+    GoldenChunk {
+        lod: lod as u8,
+        position: (x, y),
+        min_height,
+        generate_above,
+        voxel_hash: hash_voxel_data(&voxel_data),
+        opaque_mesh_hash: hash_mesh(mesh_result.opaque_mesh.as_ref()),
+        transparent_mesh_hash: hash_mesh(mesh_result.transparent_mesh.as_ref()),
+        cutout_mesh_hash: hash_mesh(mesh_result.cutout_mesh.as_ref()),
+    }
+}
+
+/// Generates a few chunks of a fixed seed and compares them with the
+/// checked-in hashes. After an intended terrain change, rerun with
+/// `UPDATE_GOLDEN=1` and commit the new golden file.
+#[test]
+fn terrain_matches_golden_hashes() {
+    set_asset_root(ASSET_ROOT).unwrap();
+    load_block_registry().unwrap();
+
+    let chunks = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(|| {
+            let mut generation_options = GenerationOptions::from_seed(SEED);
+            generation_options.load_building_templates().unwrap();
+            let cache_store = Arc::new(CacheStore::default());
+
+            LOD_POSITIONS
+                .into_iter()
+                .map(|lod_position| {
+                    generate_golden_chunk(
+                        lod_position,
+                        &generation_options,
+                        &cache_store,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    if std::env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+        write_ron_to_file(GOLDEN_FILE_PATH, &chunks).unwrap();
+        return;
+    }
+
+    let golden_chunks: Vec<GoldenChunk> = read_ron_from_file(GOLDEN_FILE_PATH)
+        .expect("Missing golden file, run with UPDATE_GOLDEN=1 to create it");
+
+    let mismatches = chunks
+        .iter()
+        .zip(&golden_chunks)
+        .filter(|(chunk, golden_chunk)| chunk != golden_chunk)
+        .collect::<Vec<_>>();
+
+    assert_eq!(chunks.len(), golden_chunks.len());
+    assert!(
+        mismatches.is_empty(),
+        "Generated terrain changed for seed {}:\n{:#?}",
+        SEED,
+        mismatches
+    );
+}
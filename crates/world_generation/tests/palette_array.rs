@@ -0,0 +1,108 @@
+use utils::file_utils::set_asset_root;
+use world_generation::chunk_generation::{
+    block_registry::load_block_registry, block_type::BlockType,
+    palette_array::PaletteArray,
+};
+
+const ASSET_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+const LEN: usize = 100;
+
+/// Distinct blocks that don't need more of the block registry than grass.
+fn get_block(index: usize) -> BlockType {
+    BlockType::GRASS.with_tint(index as u8)
+}
+
+fn assert_blocks(palette_array: &PaletteArray, expected: &[BlockType]) {
+    assert_eq!(palette_array.len(), expected.len());
+    for (index, block) in expected.iter().enumerate() {
+        assert_eq!(palette_array.get(index), *block, "Block {}", index);
+    }
+}
+
+/// Fills the entries with `block_count` different blocks in turn.
+fn fill_with_blocks(
+    palette_array: &mut PaletteArray,
+    expected: &mut [BlockType],
+    block_count: usize,
+) {
+    for (index, expected_block) in expected.iter_mut().enumerate() {
+        let block = get_block(index % block_count + 1);
+        palette_array.set(index, block);
+        *expected_block = block;
+    }
+}
+
+#[test]
+fn uniform_arrays_take_no_indexes() {
+    let mut palette_array = PaletteArray::new(LEN, BlockType::AIR);
+
+    assert_eq!(palette_array.get_uniform_block(), Some(BlockType::AIR));
+    palette_array.set(7, BlockType::AIR);
+    assert_eq!(palette_array.get_uniform_block(), Some(BlockType::AIR));
+    assert_blocks(&palette_array, &[BlockType::AIR; LEN]);
+}
+
+#[test]
+fn set_keeps_blocks_while_repacking() {
+    let mut palette_array = PaletteArray::new(LEN, BlockType::AIR);
+    let mut expected = [BlockType::AIR; LEN];
+
+    // Along with air the palette grows to 2, 3, 5 and 17 blocks, so the
+    // indexes grow from 1 over 2 and 4 to 8 bits.
+    for block_count in [1, 2, 4, 16] {
+        fill_with_blocks(&mut palette_array, &mut expected, block_count);
+        assert_blocks(&palette_array, &expected);
+        assert_eq!(palette_array.get_uniform_block(), None);
+    }
+}
+
+#[test]
+fn compact_remaps_the_used_blocks() {
+    let mut palette_array = PaletteArray::new(LEN, BlockType::AIR);
+    let mut expected = [BlockType::AIR; LEN];
+    fill_with_blocks(&mut palette_array, &mut expected, 16);
+
+    // Only every fourth block stays in use, with a palette of 4 blocks.
+    for (index, expected_block) in expected.iter_mut().enumerate() {
+        let block = get_block(index % 16 / 4 * 4 + 1);
+        palette_array.set(index, block);
+        *expected_block = block;
+    }
+    palette_array.compact();
+    assert_blocks(&palette_array, &expected);
+
+    // Blocks added after compacting must not reuse the dropped entries.
+    palette_array.set(0, BlockType::STONE);
+    expected[0] = BlockType::STONE;
+    assert_blocks(&palette_array, &expected);
+}
+
+#[test]
+fn compact_turns_arrays_uniform() {
+    let mut palette_array = PaletteArray::new(LEN, BlockType::AIR);
+    let mut expected = [BlockType::AIR; LEN];
+    fill_with_blocks(&mut palette_array, &mut expected, 16);
+
+    for index in 0..LEN {
+        palette_array.set(index, BlockType::STONE);
+    }
+    palette_array.compact();
+
+    assert_eq!(palette_array.get_uniform_block(), Some(BlockType::STONE));
+    assert_blocks(&palette_array, &[BlockType::STONE; LEN]);
+}
+
+#[test]
+fn serde_round_trip_keeps_blocks() {
+    set_asset_root(ASSET_ROOT).unwrap();
+    load_block_registry().unwrap();
+
+    let mut palette_array = PaletteArray::new(LEN, BlockType::AIR);
+    let mut expected = [BlockType::AIR; LEN];
+    fill_with_blocks(&mut palette_array, &mut expected, 16);
+
+    let serialized = ron::to_string(&palette_array).unwrap();
+    let deserialized: PaletteArray = ron::from_str(&serialized).unwrap();
+
+    assert_blocks(&deserialized, &expected);
+}
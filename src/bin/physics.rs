@@ -7,13 +7,22 @@ use debug_tools::{
 };
 use physics::{
     collider::Collider,
+    physics_friction::PhysicsFriction,
+    physics_gravity::PhysicsGravity,
+    physics_impulse::Explosion,
+    physics_mass::PhysicsMass,
     physics_object::{DynamicPhysicsObject, StaticPhysicsObject},
     physics_plugin::PhysicsPlugin,
     physics_position::PhysicsPosition,
+    physics_restitution::PhysicsRestitution,
     physics_systems::PhysicsSystems,
     physics_velocity::PhysicsVelocity,
 };
 
+/// The box moved with the keyboard.
+#[derive(Component)]
+struct DemoPlayer;
+
 fn main() {
     App::new()
         .add_plugins((
@@ -34,7 +43,10 @@ fn main() {
             SpellhavenDebugPlugin,
         ))
         .add_systems(Startup, setup)
-        .add_systems(Update, move_player.after(PhysicsSystems))
+        .add_systems(
+            Update,
+            (move_player.after(PhysicsSystems), explode_at_player),
+        )
         .init_resource::<PhysicsDebugResource>()
         .run();
 }
@@ -50,19 +62,39 @@ fn setup(mut commands: Commands) {
     ));
 
     commands.spawn((
+        DemoPlayer,
         DynamicPhysicsObject {
             step_height: 0.6,
             ..Default::default()
         },
         PhysicsPosition(Vec3::NEG_Z * 2.),
+        PhysicsMass(5.),
         Collider::aabb(Vec3::ONE, Vec3::ZERO),
     ));
 
+    // Stacks of boxes to push over, each a bit higher than the last.
+    for stack in 0..3 {
+        for height in 0..=stack + 1 {
+            commands.spawn((
+                DynamicPhysicsObject::default(),
+                PhysicsPosition(Vec3::new(
+                    stack as f32 * 1.5 - 1.5,
+                    1. + height as f32 * 1.1,
+                    1.5,
+                )),
+                PhysicsGravity::default(),
+                PhysicsFriction(0.5),
+                PhysicsRestitution(0.2),
+                Collider::aabb(Vec3::splat(0.8), Vec3::ZERO),
+            ));
+        }
+    }
+
     commands.spawn(PanOrbitCamera::default());
 }
 
 fn move_player(
-    mut player_velocity: Single<&mut PhysicsVelocity>,
+    mut player_velocity: Single<&mut PhysicsVelocity, With<DemoPlayer>>,
     input: Res<ButtonInput<KeyCode>>,
 ) -> Result {
     let mut velocity = Vec3::ZERO;
@@ -92,3 +124,18 @@ fn move_player(
 
     Ok(())
 }
+
+/// Blows the boxes away from the player with space.
+fn explode_at_player(
+    player_position: Single<&PhysicsPosition, With<DemoPlayer>>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+) {
+    if input.just_pressed(KeyCode::Space) {
+        commands.trigger(Explosion {
+            position: ***player_position,
+            radius: 5.,
+            impulse: 15.,
+        });
+    }
+}
@@ -0,0 +1,31 @@
+//! Dedicated server without a window or rendering.
+//! Build it with `--no-default-features` to leave out Steam entirely.
+
+use std::{env, process};
+
+use bevy::prelude::*;
+use plugins::{server_config::ServerConfig, server_plugins::ServerPlugins};
+
+#[tokio::main]
+async fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let server_config = match ServerConfig::from_args(&args) {
+        Ok(server_config) => server_config,
+        Err(err) => {
+            eprintln!("Invalid server config: {:#}", err);
+            eprintln!(
+                "Usage: server [--config <file>] [--seed <u64>] [--address <ip>] [--port <u16>] [--max-players <count>]"
+            );
+            process::exit(1);
+        }
+    };
+
+    let app_exit = App::new()
+        .add_plugins(ServerPlugins { server_config })
+        .run();
+
+    if let AppExit::Error(code) = app_exit {
+        process::exit(code.get().into());
+    }
+}
@@ -27,21 +27,19 @@ use ron::ser::PrettyConfig;
 use utils::cartesian_product::cube_cartesian_product;
 use world_generation::{
     chunk_generation::{
-        block_type::BlockType, chunk_lod::ChunkLod,
+        block_registry::{get_block_registry, load_block_registry},
+        block_type::BlockType,
+        chunk_lod::ChunkLod,
         mesh_generation::generate_mesh,
-        structures::structure_model::StructureModel, voxel_data::VoxelData,
+        structures::structure_model::StructureModel,
+        voxel_data::VoxelData,
     },
     terrain_material::TerrainMaterial,
 };
 
-const PLACEABLE_BLOCKS: [BlockType; 4] = [
-    BlockType::Stone,
-    BlockType::Grass,
-    BlockType::Log,
-    BlockType::Snow,
-];
-
 fn main() {
+    load_block_registry().expect("Could not load blocks.");
+
     let args = env::args().collect_vec();
     let open_structure = args.get(1);
     let mut voxel_data = VoxelData::default();
@@ -77,7 +75,7 @@ fn main() {
 
         save_data.file_name = open_structure.clone();
     } else {
-        voxel_data.set_block([1, 1, 1], BlockType::Stone);
+        voxel_data.set_block([1, 1, 1], BlockType::STONE);
     }
 
     App::new()
@@ -118,7 +116,7 @@ fn main() {
         })
         .insert_resource(VoxelDataResource {
             voxel_data,
-            selected_block: BlockType::Stone,
+            selected_block: BlockType::STONE,
         })
         .insert_resource(save_data)
         .run();
@@ -171,6 +169,7 @@ fn setup(
             extension: TerrainMaterial {
                 chunk_position: Vec3::ZERO,
                 lod_multiplier: 1,
+                block_textures: None,
             },
         })),
     ));
@@ -242,7 +241,7 @@ fn update_blocks(
 
             voxel_data
                 .voxel_data
-                .set_block(current_block_pos, BlockType::Air);
+                .set_block(current_block_pos, BlockType::AIR);
         }
 
         gizmos.sphere(point, 0.05, RED_500);
@@ -257,8 +256,8 @@ fn render_gui(
 ) -> Result {
     egui::TopBottomPanel::top("top").show(contexts.ctx_mut()?, |ui| {
         ui.horizontal(|ui| {
-            for block in PLACEABLE_BLOCKS {
-                let mut button = ui.button(format!("{:?}", block));
+            for block in get_block_registry().get_placeable_blocks() {
+                let mut button = ui.button(&block.get_definition().name);
 
                 if voxel_data_resource.selected_block == block {
                     button = button.highlight();
@@ -289,7 +288,7 @@ fn render_gui(
 
                     let block = voxel_data_resource.voxel_data.get_block(pos);
 
-                    if let BlockType::Air = block {
+                    if let BlockType::AIR = block {
                         continue;
                     }
 
@@ -0,0 +1,412 @@
+//! Renders the terrain of a seed into PNG images without starting the game,
+//! for tuning `assets/terrain_noise.ron`.
+//!
+//! Writes `heightmap.png`, `slope.png` and `colors.png` into the output
+//! directory. Regions are given in voxels of the full LOD.
+
+use std::{env, path::PathBuf, process, sync::Arc};
+
+use anyhow::{Context, anyhow};
+use bevy::{
+    color::ColorToPacked,
+    math::{DVec2, IVec2},
+};
+use image::{GrayImage, ImageBuffer, Luma, Rgb, RgbImage};
+use rayon::prelude::*;
+use world_generation::{
+    chunk_generation::{
+        biomes::biome_sampler::BiomeSampler,
+        block_registry::load_block_registry,
+        block_type::BlockType,
+        country::{
+            country_cache::{COUNTRY_SIZE, CacheStore},
+            country_cache_position::CountryPosition,
+        },
+        noise::noise_function::NoiseFunction,
+    },
+    generation_options::GenerationOptions,
+};
+
+/// Steepness that is drawn fully white in the slope map.
+/// Grass stops growing at a steepness of 1.
+const MAX_DRAWN_STEEPNESS: f64 = 2.;
+const CITY_COLOR: Rgb<u8> = Rgb([220, 30, 30]);
+const ROAD_COLOR: Rgb<u8> = Rgb([120, 80, 20]);
+const RIVER_COLOR: Rgb<u8> = Rgb([40, 110, 200]);
+
+struct ExportConfig {
+    seed: u64,
+    min: IVec2,
+    size: u32,
+    resolution: u32,
+    output: PathBuf,
+    cities: bool,
+    roads: bool,
+    rivers: bool,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            min: IVec2::splat(-4096),
+            size: 8192,
+            resolution: 1024,
+            output: "terrain_export".into(),
+            cities: false,
+            roads: false,
+            rivers: false,
+        }
+    }
+}
+
+impl ExportConfig {
+    fn from_args(args: &[String]) -> Result<Self, anyhow::Error> {
+        let mut export_config = Self::default();
+
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--cities" => {
+                    export_config.cities = true;
+                    continue;
+                }
+                "--roads" => {
+                    export_config.roads = true;
+                    continue;
+                }
+                "--rivers" => {
+                    export_config.rivers = true;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let value =
+                args.next().ok_or(anyhow!("Missing value for {}", flag))?;
+
+            match flag.as_str() {
+                "--seed" => export_config.seed = value.parse()?,
+                "--x" => export_config.min.x = value.parse()?,
+                "--z" => export_config.min.y = value.parse()?,
+                "--size" => export_config.size = value.parse()?,
+                "--resolution" => export_config.resolution = value.parse()?,
+                "--output" => export_config.output = value.into(),
+                _ => return Err(anyhow!("Unknown flag {}", flag)),
+            }
+        }
+
+        if export_config.size == 0 || export_config.resolution == 0 {
+            return Err(anyhow!("Size and resolution have to be positive"));
+        }
+
+        Ok(export_config)
+    }
+
+    fn voxels_per_pixel(&self) -> f64 {
+        self.size as f64 / self.resolution as f64
+    }
+
+    fn pixel_to_voxel(&self, x: u32, y: u32) -> DVec2 {
+        self.min.as_dvec2()
+            + (DVec2::new(x as f64, y as f64) + 0.5) * self.voxels_per_pixel()
+    }
+
+    fn voxel_to_pixel(&self, voxel_pos: IVec2) -> Option<(u32, u32)> {
+        let pixel = (voxel_pos - self.min).as_dvec2() / self.voxels_per_pixel();
+
+        (pixel.x >= 0.
+            && pixel.y >= 0.
+            && pixel.x < self.resolution as f64
+            && pixel.y < self.resolution as f64)
+            .then_some((pixel.x as u32, pixel.y as u32))
+    }
+}
+
+/// What the noise functions return for one pixel.
+#[derive(Clone, Copy, Default)]
+struct TerrainSample {
+    height: f64,
+    steepness: f64,
+    color: [u8; 3],
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let export_config = match ExportConfig::from_args(&args) {
+        Ok(export_config) => export_config,
+        Err(err) => {
+            eprintln!("Invalid arguments: {:#}", err);
+            eprintln!(
+                "Usage: terrain_export [--seed <u64>] [--x <i32>] [--z <i32>] [--size <voxels>] [--resolution <pixels>] [--output <dir>] [--cities] [--roads] [--rivers]"
+            );
+            process::exit(1);
+        }
+    };
+
+    if let Err(err) = export(&export_config) {
+        eprintln!("Export failed: {:#}", err);
+        process::exit(1);
+    }
+}
+
+fn export(export_config: &ExportConfig) -> Result<(), anyhow::Error> {
+    load_block_registry()?;
+
+    let mut generation_options =
+        GenerationOptions::from_seed(export_config.seed);
+    generation_options.generate_paths = export_config.roads;
+    generation_options.load_building_templates()?;
+
+    let samples = sample_terrain(export_config, &generation_options);
+
+    let (min_height, max_height) = samples
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), sample| {
+            (min.min(sample.height), max.max(sample.height))
+        });
+    let height_range = (max_height - min_height).max(f64::EPSILON);
+
+    let resolution = export_config.resolution;
+    let get_sample = |x: u32, y: u32| samples[(y * resolution + x) as usize];
+
+    let heightmap: ImageBuffer<Luma<u16>, Vec<u16>> =
+        ImageBuffer::from_fn(resolution, resolution, |x, y| {
+            let height = (get_sample(x, y).height - min_height) / height_range;
+            Luma([(height * u16::MAX as f64) as u16])
+        });
+
+    let slope_map = GrayImage::from_fn(resolution, resolution, |x, y| {
+        let steepness = get_sample(x, y).steepness / MAX_DRAWN_STEEPNESS;
+        Luma([(steepness.clamp(0., 1.) * u8::MAX as f64) as u8])
+    });
+
+    let mut color_map = RgbImage::from_fn(resolution, resolution, |x, y| {
+        Rgb(get_sample(x, y).color)
+    });
+
+    if export_config.cities || export_config.roads || export_config.rivers {
+        draw_country_overlays(
+            &mut color_map,
+            export_config,
+            &generation_options,
+        );
+    }
+
+    std::fs::create_dir_all(&export_config.output).with_context(|| {
+        format!("Failed creating {}", export_config.output.display())
+    })?;
+
+    heightmap
+        .save(export_config.output.join("heightmap.png"))
+        .context("Failed writing heightmap.png")?;
+    slope_map
+        .save(export_config.output.join("slope.png"))
+        .context("Failed writing slope.png")?;
+    color_map
+        .save(export_config.output.join("colors.png"))
+        .context("Failed writing colors.png")?;
+
+    println!(
+        "Exported {}x{} voxels from {} at {}px into {}",
+        export_config.size,
+        export_config.size,
+        export_config.min,
+        resolution,
+        export_config.output.display()
+    );
+    println!(
+        "Heightmap range: {:.1} to {:.1} voxels",
+        min_height, max_height
+    );
+
+    Ok(())
+}
+
+fn sample_terrain(
+    export_config: &ExportConfig,
+    generation_options: &GenerationOptions,
+) -> Vec<TerrainSample> {
+    let terrain_noise = generation_options.get_terrain_noise();
+    let biome_sampler = BiomeSampler::new(generation_options);
+
+    let resolution = export_config.resolution;
+    let mut samples =
+        vec![TerrainSample::default(); (resolution * resolution) as usize];
+
+    samples
+        .par_chunks_mut(resolution as usize)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for (x, sample) in row.iter_mut().enumerate() {
+                let noise_position =
+                    export_config.pixel_to_voxel(x as u32, y as u32).to_array();
+                let noise_result = terrain_noise.get(noise_position);
+                let steepness =
+                    DVec2::from_array(noise_result.derivative).length();
+
+                let surface_block = if noise_result.value
+                    < generation_options.water_options.sea_level as f64
+                {
+                    BlockType::WATER
+                } else {
+                    biome_sampler
+                        .get_surface(
+                            noise_position,
+                            noise_result.value as f32,
+                            steepness,
+                        )
+                        .surface_block
+                };
+
+                *sample = TerrainSample {
+                    height: noise_result.value,
+                    steepness,
+                    color: surface_block.get_color().to_srgba().to_u8_array()
+                        [..3]
+                        .try_into()
+                        .unwrap(),
+                };
+            }
+        });
+
+    samples
+}
+
+/// Draws the cities, roads and rivers of every country touching the region.
+fn draw_country_overlays(
+    color_map: &mut RgbImage,
+    export_config: &ExportConfig,
+    generation_options: &GenerationOptions,
+) {
+    let cache_store = Arc::new(CacheStore::default());
+
+    let min_country = export_config
+        .min
+        .div_euclid(IVec2::splat(COUNTRY_SIZE as i32));
+    let max_country = (export_config.min
+        + IVec2::splat(export_config.size as i32))
+    .div_euclid(IVec2::splat(COUNTRY_SIZE as i32));
+
+    for country_x in min_country.x..=max_country.x {
+        for country_z in min_country.y..=max_country.y {
+            let country_pos =
+                CountryPosition::new(IVec2::new(country_x, country_z));
+
+            if export_config.roads {
+                let path_data = cache_store.path_cache.get_cache_entry(
+                    country_pos,
+                    generation_options,
+                    cache_store.clone(),
+                );
+
+                for line in path_data.paths.iter().flat_map(|path| &path.lines)
+                {
+                    for points in line.sample_points.windows(2) {
+                        draw_line(
+                            color_map,
+                            export_config,
+                            points[0],
+                            points[1],
+                            ROAD_COLOR,
+                        );
+                    }
+                }
+            }
+
+            if export_config.rivers {
+                let river_data = cache_store.river_cache.get_cache_entry(
+                    country_pos,
+                    generation_options,
+                    cache_store.clone(),
+                );
+
+                for river in &river_data.rivers {
+                    for points in river.points.windows(2) {
+                        draw_line(
+                            color_map,
+                            export_config,
+                            points[0].position,
+                            points[1].position,
+                            RIVER_COLOR,
+                        );
+                    }
+                }
+
+                for lake in &river_data.lakes {
+                    draw_square(
+                        color_map,
+                        export_config,
+                        lake.center,
+                        RIVER_COLOR,
+                    );
+                }
+            }
+
+            if export_config.cities {
+                let structure_data =
+                    cache_store.structure_cache.get_cache_entry(
+                        country_pos,
+                        generation_options,
+                        cache_store.clone(),
+                    );
+
+                draw_square(
+                    color_map,
+                    export_config,
+                    structure_data.city_location,
+                    CITY_COLOR,
+                );
+            }
+        }
+    }
+}
+
+fn draw_line(
+    color_map: &mut RgbImage,
+    export_config: &ExportConfig,
+    start: IVec2,
+    end: IVec2,
+    color: Rgb<u8>,
+) {
+    let steps = ((end - start).abs().max_element() as f64
+        / export_config.voxels_per_pixel())
+    .ceil()
+    .max(1.) as i32;
+
+    for step in 0..=steps {
+        let voxel_pos = start.as_dvec2()
+            + (end - start).as_dvec2() * (step as f64 / steps as f64);
+
+        if let Some((x, y)) = export_config.voxel_to_pixel(voxel_pos.as_ivec2())
+        {
+            color_map.put_pixel(x, y, color);
+        }
+    }
+}
+
+fn draw_square(
+    color_map: &mut RgbImage,
+    export_config: &ExportConfig,
+    center: IVec2,
+    color: Rgb<u8>,
+) {
+    let Some((center_x, center_y)) = export_config.voxel_to_pixel(center)
+    else {
+        return;
+    };
+
+    let resolution = export_config.resolution as i64;
+
+    for offset_x in -2..=2 {
+        for offset_y in -2..=2 {
+            let x = center_x as i64 + offset_x;
+            let y = center_y as i64 + offset_y;
+
+            if (0..resolution).contains(&x) && (0..resolution).contains(&y) {
+                color_map.put_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
+}
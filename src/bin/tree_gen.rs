@@ -17,6 +17,7 @@ use rand::{Rng, SeedableRng, rng, rngs::StdRng};
 use world_generation::{
     chunk_generation::{
         CHUNK_SIZE, VOXEL_SIZE,
+        block_registry::load_block_registry,
         block_type::BlockType,
         chunk_lod::ChunkLod,
         mesh_generation::generate_mesh,
@@ -36,6 +37,8 @@ use world_generation::{
 };
 
 fn main() {
+    load_block_registry().expect("Could not load blocks.");
+
     App::new()
         .add_plugins(
             (
@@ -155,6 +158,7 @@ fn spawn_mesh(
                 extension: TerrainMaterial {
                     chunk_position: chunk_pos.as_vec3(),
                     lod_multiplier: ChunkLod::Full.multiplier_i32() as u32,
+                    block_textures: None,
                 },
             })),
             TreeGen,